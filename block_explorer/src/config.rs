@@ -3,7 +3,8 @@
 use alloy_chains::{Chain, NamedChain};
 use url::Url;
 
-use crate::data::FetcherConfig;
+use crate::data::solana::SOLANA_MAINNET;
+use crate::data::{EndpointStrategy, FetcherConfig};
 
 const CHAIN_ENV_VARS: &[(NamedChain, &str)] = &[
     (NamedChain::Mainnet, "MAINNET_RPC_URL"),
@@ -12,28 +13,35 @@ const CHAIN_ENV_VARS: &[(NamedChain, &str)] = &[
     (NamedChain::Arbitrum, "ARBITRUM_RPC_URL"),
 ];
 
+/// Solana JSON-RPC endpoint(s); adds a Solana lane next to the EVM chains.
+const SOLANA_ENV_VAR: &str = "SOLANA_RPC_URL";
+
 const DEFAULT_RPC: &str = "http://127.0.0.1:8545";
 
+/// Selects how a chain with several endpoints spreads its requests.
+const STRATEGY_ENV_VAR: &str = "RPC_STRATEGY";
+
 /// Returns all configured chains by checking which env vars are set.
 /// Falls back to a single mainnet config if nothing is set.
+///
+/// Each variable may list several comma-separated URLs; the first is the
+/// primary endpoint and the rest are used according to `RPC_STRATEGY`.
 pub fn chain_configs() -> Vec<FetcherConfig> {
     let mut configs: Vec<FetcherConfig> = CHAIN_ENV_VARS
         .iter()
         .filter_map(|(named, env_var)| {
             let raw = std::env::var(env_var).ok()?;
-            match raw.parse::<Url>() {
-                Ok(url) => Some(FetcherConfig {
-                    chain: Chain::from_named(*named),
-                    rpc_url: url,
-                }),
-                Err(_) => {
-                    eprintln!("tessera: invalid URL in {env_var}: {raw:?}");
-                    None
-                }
-            }
+            endpoint_config(Chain::from_named(*named), parse_rpc_urls(env_var, &raw))
         })
         .collect();
 
+    if let Ok(raw) = std::env::var(SOLANA_ENV_VAR) {
+        configs.extend(endpoint_config(
+            SOLANA_MAINNET,
+            parse_rpc_urls(SOLANA_ENV_VAR, &raw),
+        ));
+    }
+
     if configs.is_empty() {
         configs.push(chain_config());
     }
@@ -46,25 +54,126 @@ pub fn chain_configs() -> Vec<FetcherConfig> {
 pub fn chain_config() -> FetcherConfig {
     for (named, env_var) in CHAIN_ENV_VARS {
         if let Ok(raw) = std::env::var(env_var) {
-            if let Ok(url) = raw.parse::<Url>() {
-                return FetcherConfig {
-                    chain: Chain::from_named(*named),
-                    rpc_url: url,
-                };
+            let urls = parse_rpc_urls(env_var, &raw);
+            if let Some(config) = endpoint_config(Chain::from_named(*named), urls) {
+                return config;
             }
-            eprintln!("tessera: invalid URL in {env_var}: {raw:?}");
         }
     }
     let raw = std::env::var("RPC_URL").unwrap_or_else(|_| DEFAULT_RPC.to_string());
-    let url = raw.parse::<Url>().unwrap_or_else(|err| {
-        panic!("tessera: invalid RPC_URL {raw:?}: {err}");
-    });
-    FetcherConfig {
-        chain: Chain::mainnet(),
-        rpc_url: url,
+    endpoint_config(Chain::mainnet(), parse_rpc_urls("RPC_URL", &raw)).unwrap_or_else(|| {
+        panic!("tessera: invalid RPC_URL {raw:?}");
+    })
+}
+
+/// Splits a comma-separated list of RPC URLs, skipping invalid entries.
+fn parse_rpc_urls(env_var: &str, raw: &str) -> Vec<Url> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .filter_map(|part| match part.parse::<Url>() {
+            Ok(url) => Some(url),
+            Err(_) => {
+                eprintln!("tessera: invalid URL in {env_var}: {part:?}");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Builds a config from a chain's endpoints, or `None` if it has none.
+fn endpoint_config(chain: Chain, urls: Vec<Url>) -> Option<FetcherConfig> {
+    let mut urls = urls.into_iter();
+    let config = FetcherConfig::new(chain, urls.next()?).with_fallback_urls(urls.collect());
+    Some(match endpoint_strategy() {
+        Some(strategy) => config.with_strategy(strategy),
+        None => config,
+    })
+}
+
+fn endpoint_strategy() -> Option<EndpointStrategy> {
+    let raw = std::env::var(STRATEGY_ENV_VAR).ok()?;
+    raw.parse()
+        .map_err(|err| eprintln!("tessera: {STRATEGY_ENV_VAR}: {err}"))
+        .ok()
+}
+
+/// Reads an on/off environment variable such as `TESSERA_RECEIPTS`. `1` and
+/// `true` turn it on; unset, empty, `0` and `false` leave it off.
+pub fn env_flag(name: &str) -> bool {
+    let Ok(raw) = std::env::var(name) else {
+        return false;
+    };
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" => true,
+        "" | "0" | "false" => false,
+        _ => {
+            eprintln!("tessera: ignoring {name}={raw:?}, expected 1/true or 0/false");
+            false
+        }
     }
 }
 
+/// Parses a `--at` timestamp: Unix seconds, an RFC 3339 UTC time such as
+/// `2024-03-13T13:55:00Z`, or a bare `YYYY-MM-DD` date (midnight UTC).
+pub fn parse_timestamp(raw: &str) -> Option<u64> {
+    if let Ok(secs) = raw.parse::<u64>() {
+        return Some(secs);
+    }
+
+    let (date, time) = match raw.split_once('T') {
+        Some((date, time)) => (date, Some(time.strip_suffix('Z')?)),
+        None => (raw, None),
+    };
+
+    let mut parts = date.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+
+    let seconds_of_day = match time {
+        Some(time) => {
+            let mut parts = time.splitn(3, ':');
+            let hour: u64 = parts.next()?.parse().ok()?;
+            let minute: u64 = parts.next()?.parse().ok()?;
+            let second: u64 = parts.next().map_or(Some(0), |s| s.parse().ok())?;
+            if hour > 23 || minute > 59 || second > 60 {
+                return None;
+            }
+            hour * 3600 + minute * 60 + second
+        }
+        None => 0,
+    };
+
+    let days = u64::try_from(days_from_civil(year, month, day)).ok()?;
+    Some(days * 86_400 + seconds_of_day)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's
+/// `days_from_civil`).
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = i64::from(month);
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -101,12 +210,14 @@ mod tests {
         }
     }
 
-    const ENV_KEYS: [&str; 5] = [
+    const ENV_KEYS: [&str; 7] = [
         "MAINNET_RPC_URL",
         "BASE_RPC_URL",
         "OPTIMISM_RPC_URL",
         "ARBITRUM_RPC_URL",
         "RPC_URL",
+        "RPC_STRATEGY",
+        "SOLANA_RPC_URL",
     ];
 
     #[test]
@@ -183,4 +294,108 @@ mod tests {
         assert_eq!(configs.len(), 1);
         assert_eq!(configs[0].chain, Chain::mainnet());
     }
+
+    #[test]
+    fn env_flag_reads_true_and_false_values() {
+        let _lock = lock_env();
+        let _guard = EnvGuard::capture(&["TESSERA_TEST_FLAG"]);
+
+        std::env::remove_var("TESSERA_TEST_FLAG");
+        assert!(!env_flag("TESSERA_TEST_FLAG"));
+        for on in ["1", "true", "TRUE"] {
+            std::env::set_var("TESSERA_TEST_FLAG", on);
+            assert!(env_flag("TESSERA_TEST_FLAG"), "{on}");
+        }
+        for off in ["", "0", "false", "no"] {
+            std::env::set_var("TESSERA_TEST_FLAG", off);
+            assert!(!env_flag("TESSERA_TEST_FLAG"), "{off}");
+        }
+    }
+
+    #[test]
+    fn comma_separated_urls_become_fallback_endpoints() {
+        let _lock = lock_env();
+        let _guard = EnvGuard::capture(&ENV_KEYS);
+
+        for key in &ENV_KEYS {
+            std::env::remove_var(key);
+        }
+
+        std::env::set_var(
+            "BASE_RPC_URL",
+            "http://127.0.0.1:8545, not-a-url ,wss://base.example.com/v2/key",
+        );
+        std::env::set_var("RPC_STRATEGY", "quorum");
+
+        let configs = chain_configs();
+
+        assert_eq!(configs.len(), 1);
+        let config = &configs[0];
+        assert_eq!(config.chain, Chain::from_named(NamedChain::Base));
+        assert_eq!(config.rpc_url.as_str(), "http://127.0.0.1:8545/");
+        assert_eq!(config.fallback_urls.len(), 1);
+        assert_eq!(config.fallback_urls[0].host_str(), Some("base.example.com"));
+        assert_eq!(config.strategy, EndpointStrategy::Quorum);
+    }
+
+    #[test]
+    fn solana_env_adds_its_own_lane() {
+        let _lock = lock_env();
+        let _guard = EnvGuard::capture(&ENV_KEYS);
+
+        for key in &ENV_KEYS {
+            std::env::remove_var(key);
+        }
+
+        std::env::set_var("SOLANA_RPC_URL", "https://api.mainnet-beta.solana.com");
+        let configs = chain_configs();
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].chain, SOLANA_MAINNET);
+
+        std::env::set_var("BASE_RPC_URL", "http://127.0.0.1:8546");
+        let chains: Vec<Chain> = chain_configs().into_iter().map(|c| c.chain).collect();
+        assert_eq!(
+            chains,
+            vec![Chain::from_named(NamedChain::Base), SOLANA_MAINNET]
+        );
+    }
+
+    #[test]
+    fn unknown_strategy_keeps_failover() {
+        let _lock = lock_env();
+        let _guard = EnvGuard::capture(&ENV_KEYS);
+
+        for key in &ENV_KEYS {
+            std::env::remove_var(key);
+        }
+
+        std::env::set_var("RPC_URL", "http://127.0.0.1:8545,http://127.0.0.1:8546");
+        std::env::set_var("RPC_STRATEGY", "random");
+
+        let config = chain_config();
+
+        assert_eq!(config.fallback_urls.len(), 1);
+        assert_eq!(config.strategy, EndpointStrategy::Failover);
+    }
+
+    #[test]
+    fn parse_timestamp_accepts_unix_seconds_and_rfc3339() {
+        assert_eq!(parse_timestamp("1710338100"), Some(1_710_338_100));
+        assert_eq!(parse_timestamp("2024-03-13T13:55:00Z"), Some(1_710_338_100));
+        assert_eq!(parse_timestamp("1970-01-01"), Some(0));
+        assert_eq!(parse_timestamp("2000-03-01T00:00Z"), Some(951_868_800));
+        assert_eq!(parse_timestamp("2024-02-29"), Some(1_709_164_800));
+    }
+
+    #[test]
+    fn parse_timestamp_rejects_malformed_input() {
+        assert_eq!(parse_timestamp("yesterday"), None);
+        assert_eq!(parse_timestamp("2024-13-01"), None);
+        assert_eq!(parse_timestamp("2024-03-13T25:00:00Z"), None);
+        assert_eq!(parse_timestamp("2024-03-13T13:55:00+02:00"), None);
+        assert_eq!(parse_timestamp("1969-12-31"), None);
+        assert_eq!(parse_timestamp("2024-02-31T00:00:00Z"), None);
+        assert_eq!(parse_timestamp("2023-02-29"), None);
+        assert_eq!(parse_timestamp("2024-04-31"), None);
+    }
 }
@@ -0,0 +1,223 @@
+//! Block builder attribution from the fee recipient and header extra data.
+//!
+//! Most mainnet blocks are built by a handful of MEV-Boost builders. They
+//! either set their own address as the fee recipient and pay the proposer in
+//! the block's last transaction, or tag the header's `extra_data` with their
+//! name. A bundled list covers the major builders; users can extend it from
+//! JSON or TOML files:
+//!
+//! ```json
+//! [{ "name": "Titan", "fee_recipients": ["0x4838...5f97"], "extra_data": ["titanbuilder"] }]
+//! ```
+//!
+//! ```toml
+//! [[builder]]
+//! name = "Titan"
+//! fee_recipients = ["0x4838...5f97"]
+//! extra_data = ["titanbuilder"]
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use alloy::primitives::{address, Address, U256};
+use serde::Deserialize;
+
+use crate::data::registry_file;
+
+/// Well-known builders: name, fee recipients, `extra_data` substrings.
+const BUNDLED_BUILDERS: &[(&str, &[Address], &[&str])] = &[
+    (
+        "beaverbuild",
+        &[address!("95222290dd7278aa3ddd389cc1e1d165cc4bafe5")],
+        &["beaverbuild"],
+    ),
+    (
+        "Titan",
+        &[address!("4838b106fce9647bdf1e7877bf73ce8b0bad5f97")],
+        &["titan"],
+    ),
+    (
+        "rsync",
+        &[address!("1f9090aae28b8a3dceadf281b0f12828e676c326")],
+        &["rsync"],
+    ),
+    (
+        "Flashbots",
+        &[address!("dafea492d9c6733ae3d56b7ed1adb60692c98bc5")],
+        &["illuminate dmocratize dstribute", "flashbots"],
+    ),
+    (
+        "builder0x69",
+        &[address!("690b9a9e9aa1c9db991c7721a92d351db4fac990")],
+        &["builder0x69"],
+    ),
+    ("BuilderNet", &[], &["buildernet"]),
+    ("bloXroute", &[], &["bloxroute"]),
+    ("Quasar", &[], &["quasar"]),
+    ("penguinbuild", &[], &["penguinbuild"]),
+];
+
+/// The transaction in which a builder pays the block's proposer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProposerPayment {
+    /// Proposer's fee recipient.
+    pub proposer: Address,
+    /// Amount paid, in the chain's smallest native unit.
+    pub value: U256,
+}
+
+/// Builder names by fee recipient and `extra_data` tag.
+#[derive(bevy::prelude::Resource, Clone, Debug)]
+pub struct BuilderRegistry {
+    by_fee_recipient: HashMap<Address, String>,
+    /// Lowercase `extra_data` substrings, checked in order.
+    by_extra_data: Vec<(String, String)>,
+}
+
+/// Entry in a user builder file.
+#[derive(Deserialize)]
+struct BuilderEntry {
+    name: String,
+    #[serde(default)]
+    fee_recipients: Vec<Address>,
+    #[serde(default)]
+    extra_data: Vec<String>,
+}
+
+impl Default for BuilderRegistry {
+    fn default() -> Self {
+        Self::bundled()
+    }
+}
+
+impl BuilderRegistry {
+    pub fn empty() -> Self {
+        Self {
+            by_fee_recipient: HashMap::new(),
+            by_extra_data: Vec::new(),
+        }
+    }
+
+    /// The builders shipped with the explorer.
+    pub fn bundled() -> Self {
+        let mut registry = Self::empty();
+        for (name, fee_recipients, tags) in BUNDLED_BUILDERS {
+            for fee_recipient in *fee_recipients {
+                registry.insert_fee_recipient(*fee_recipient, name);
+            }
+            for tag in *tags {
+                registry.insert_extra_data(tag, name);
+            }
+        }
+        registry
+    }
+
+    pub fn insert_fee_recipient(&mut self, fee_recipient: Address, name: &str) {
+        self.by_fee_recipient
+            .insert(fee_recipient, name.to_string());
+    }
+
+    /// Names blocks whose `extra_data` contains `tag`, ignoring case. Tags
+    /// added later take precedence.
+    pub fn insert_extra_data(&mut self, tag: &str, name: &str) {
+        self.by_extra_data
+            .insert(0, (tag.to_lowercase(), name.to_string()));
+    }
+
+    /// Adds the builders in a JSON or TOML file (by extension), taking precedence over bundled
+    /// entries. Returns how many were read.
+    pub fn extend_from_file(&mut self, path: &Path) -> Result<usize, String> {
+        let entries: Vec<BuilderEntry> = registry_file::read_entries(path, "builder")?;
+        let count = entries.len();
+        for entry in entries {
+            for fee_recipient in entry.fee_recipients {
+                self.insert_fee_recipient(fee_recipient, &entry.name);
+            }
+            for tag in &entry.extra_data {
+                self.insert_extra_data(tag, &entry.name);
+            }
+        }
+        Ok(count)
+    }
+
+    /// Names a block's builder: a known fee recipient first, then a known
+    /// `extra_data` tag, then the `extra_data` itself if it reads as text.
+    pub fn identify(&self, fee_recipient: Option<Address>, extra_data: &[u8]) -> Option<String> {
+        if let Some(name) = fee_recipient.and_then(|addr| self.by_fee_recipient.get(&addr)) {
+            return Some(name.clone());
+        }
+        let text = String::from_utf8_lossy(extra_data).to_lowercase();
+        if let Some((_, name)) = self
+            .by_extra_data
+            .iter()
+            .find(|(tag, _)| text.contains(tag.as_str()))
+        {
+            return Some(name.clone());
+        }
+        extra_data_text(extra_data)
+    }
+}
+
+/// `extra_data` as a name, when it is printable ASCII.
+fn extra_data_text(extra_data: &[u8]) -> Option<String> {
+    let text = std::str::from_utf8(extra_data)
+        .ok()?
+        .trim_matches(char::from(0));
+    let text = text.trim();
+    let printable = !text.is_empty() && text.chars().all(|c| c.is_ascii_graphic() || c == ' ');
+    printable.then(|| text.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identifies_by_fee_recipient_then_extra_data() {
+        let registry = BuilderRegistry::bundled();
+        let titan = address!("4838b106fce9647bdf1e7877bf73ce8b0bad5f97");
+
+        assert_eq!(
+            registry.identify(Some(titan), b"").as_deref(),
+            Some("Titan")
+        );
+        assert_eq!(
+            registry
+                .identify(Some(Address::ZERO), b"beaverbuild.org")
+                .as_deref(),
+            Some("beaverbuild")
+        );
+        // Unknown but readable tags name themselves; binary ones don't.
+        assert_eq!(
+            registry.identify(None, b"  my-builder ").as_deref(),
+            Some("my-builder")
+        );
+        assert_eq!(registry.identify(None, &[0xd8, 0x83, 0x01]), None);
+    }
+
+    #[test]
+    fn user_file_takes_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("builders.json");
+        std::fs::write(
+            &path,
+            r#"[{"name": "Local", "fee_recipients": ["0x0000000000000000000000000000000000000abc"], "extra_data": ["beaver"]}]"#,
+        )
+        .unwrap();
+
+        let mut registry = BuilderRegistry::bundled();
+        let added = registry.extend_from_file(&path).unwrap();
+
+        assert_eq!(added, 1);
+        let local = address!("0000000000000000000000000000000000000abc");
+        assert_eq!(
+            registry.identify(Some(local), b"").as_deref(),
+            Some("Local")
+        );
+        assert_eq!(
+            registry.identify(None, b"beaverbuild.org").as_deref(),
+            Some("Local")
+        );
+    }
+}
@@ -3,35 +3,82 @@ use std::path::Path;
 use crossbeam_channel::Receiver;
 
 use crate::data::evm::EvmFetcher;
+use crate::data::fixture::read_fixture;
+use crate::data::mempool::{MempoolEvent, MempoolFetcher};
 use crate::data::model::BlockPayload;
+use crate::data::solana::{is_solana, SolanaFetcher};
+use crate::data::status::FetcherStatus;
 use crate::data::{ChainFetcher, FetcherConfig};
 
-/// Bevy resource holding the channel from the EVM fetcher thread.
+/// Bevy resource holding the channel fed by the fetcher threads.
 /// Systems drain this in ingest_blocks.
 #[derive(bevy::prelude::Resource)]
 pub struct BlockChannel(pub Receiver<BlockPayload>);
 
-/// Create a block channel and spawn the EVM fetcher on a dedicated thread.
+/// Bevy resource holding one health stream per live fetcher.
+/// The HUD drains these into its per-chain indicators.
+#[derive(bevy::prelude::Resource)]
+pub struct StatusChannel(pub Vec<Receiver<FetcherStatus>>);
+
+/// Create a block channel and spawn an EVM fetcher on a dedicated thread.
 pub fn init_block_channel(config: FetcherConfig) -> BlockChannel {
     init_multi_chain_channel(vec![config])
 }
 
 /// Spawn one fetcher per config and fan them into a single receiver.
+pub fn init_multi_chain_channel(configs: Vec<FetcherConfig>) -> BlockChannel {
+    init_chain_channels(configs).0
+}
+
+/// Like [`init_multi_chain_channel`], but also returns each fetcher's health
+/// stream.
+pub fn init_chain_channels(configs: Vec<FetcherConfig>) -> (BlockChannel, StatusChannel) {
+    init_fetcher_channels(config_fetchers(configs))
+}
+
+/// Boxes the built-in fetcher for each config ([`SolanaFetcher`] for the
+/// Solana lane, [`EvmFetcher`] otherwise), ready for [`init_fetcher_channels`].
+pub(crate) fn config_fetchers(configs: Vec<FetcherConfig>) -> Vec<Box<dyn ChainFetcher>> {
+    configs
+        .into_iter()
+        .map(|config| -> Box<dyn ChainFetcher> {
+            if is_solana(&config.chain) {
+                Box::new(SolanaFetcher::new(config))
+            } else {
+                Box::new(EvmFetcher::new(config))
+            }
+        })
+        .collect()
+}
+
+/// Spawn every fetcher and fan their payloads into a single receiver.
 /// Each source gets its own forwarding thread so payloads from all chains
 /// arrive in a single channel that the ECS drains each frame.
-pub fn init_multi_chain_channel(configs: Vec<FetcherConfig>) -> BlockChannel {
-    assert!(!configs.is_empty(), "at least one chain config is required");
+pub fn init_fetcher_channels(
+    fetchers: Vec<Box<dyn ChainFetcher>>,
+) -> (BlockChannel, StatusChannel) {
+    let (rx, statuses) = spawn_fetchers(fetchers);
+    (BlockChannel(rx), StatusChannel(statuses))
+}
+
+/// Spawns the fetchers and fans them in, without any Bevy resource wrapping.
+pub(crate) fn spawn_fetchers(
+    fetchers: Vec<Box<dyn ChainFetcher>>,
+) -> (Receiver<BlockPayload>, Vec<Receiver<FetcherStatus>>) {
+    assert!(!fetchers.is_empty(), "at least one fetcher is required");
 
-    if configs.len() == 1 {
-        let rx = EvmFetcher::spawn(configs.into_iter().next().unwrap());
-        return BlockChannel(rx);
+    if fetchers.len() == 1 {
+        let (rx, status_rx) = fetchers.into_iter().next().unwrap().spawn_with_status();
+        return (rx, vec![status_rx]);
     }
 
     let (fan_tx, fan_rx) = crossbeam_channel::bounded(64);
+    let mut statuses = Vec::with_capacity(fetchers.len());
 
-    for config in configs {
+    for fetcher in fetchers {
         let tx = fan_tx.clone();
-        let rx = EvmFetcher::spawn(config);
+        let (rx, status_rx) = fetcher.spawn_with_status();
+        statuses.push(status_rx);
         std::thread::spawn(move || {
             while let Ok(payload) = rx.recv() {
                 if tx.send(payload).is_err() {
@@ -41,48 +88,51 @@ pub fn init_multi_chain_channel(configs: Vec<FetcherConfig>) -> BlockChannel {
         });
     }
 
-    BlockChannel(fan_rx)
+    (fan_rx, statuses)
 }
 
-/// Bevy resource that records ingested payloads for later serialization to a fixture file.
+/// Bevy resource holding the fanned-in mempool events of every chain that
+/// watches its pending transactions.
 #[derive(bevy::prelude::Resource)]
-pub struct RecordBuffer {
-    pub payloads: Vec<BlockPayload>,
-    pub path: std::path::PathBuf,
-}
-
-impl RecordBuffer {
-    pub fn new(path: std::path::PathBuf) -> Self {
-        Self {
-            payloads: Vec::new(),
-            path,
-        }
+pub struct MempoolChannel(pub Receiver<MempoolEvent>);
+
+/// Spawn a [`MempoolFetcher`] for each EVM config with `mempool` set.
+/// Returns `None` when no config asks for one.
+pub fn init_mempool_channel(configs: &[FetcherConfig]) -> Option<MempoolChannel> {
+    let watched: Vec<&FetcherConfig> = configs
+        .iter()
+        .filter(|config| config.mempool && !is_solana(&config.chain))
+        .collect();
+    if watched.is_empty() {
+        return None;
     }
 
-    /// Serialize accumulated payloads to the target path as JSON.
-    pub fn flush(&self) {
-        let json = serde_json::to_string_pretty(&self.payloads)
-            .expect("failed to serialize record buffer");
-        if let Some(parent) = self.path.parent() {
-            std::fs::create_dir_all(parent).ok();
-        }
-        std::fs::write(&self.path, json)
-            .unwrap_or_else(|e| panic!("failed to write fixture to {}: {e}", self.path.display()));
-        eprintln!(
-            "tessera: recorded {} blocks to {}",
-            self.payloads.len(),
-            self.path.display()
-        );
+    let (fan_tx, fan_rx) = crossbeam_channel::bounded(256);
+    for config in watched {
+        let tx = fan_tx.clone();
+        let rx = MempoolFetcher::new(config.clone()).spawn();
+        std::thread::spawn(move || {
+            while let Ok(event) = rx.recv() {
+                if tx.send(event).is_err() {
+                    return;
+                }
+            }
+        });
     }
+    Some(MempoolChannel(fan_rx))
 }
 
-/// Create a block channel that replays pre-recorded payloads from a JSON fixture file.
+/// Create a block channel that replays pre-recorded payloads from a fixture file: a JSON
+/// array, JSON Lines as written by [`Recorder`](crate::data::Recorder), or a binary fixture.
 /// Payloads are sent with a 50ms delay between each to simulate realistic ingestion pacing.
 pub fn init_fixture_channel(path: &Path) -> BlockChannel {
-    let json = std::fs::read_to_string(path)
+    BlockChannel(fixture_receiver(path))
+}
+
+/// Reads a fixture file and replays its payloads on a dedicated thread.
+pub(crate) fn fixture_receiver(path: &Path) -> Receiver<BlockPayload> {
+    let payloads = read_fixture(path)
         .unwrap_or_else(|e| panic!("failed to read fixture {}: {e}", path.display()));
-    let payloads: Vec<BlockPayload> = serde_json::from_str(&json)
-        .unwrap_or_else(|e| panic!("failed to parse fixture {}: {e}", path.display()));
 
     let (tx, rx) = crossbeam_channel::bounded(64);
 
@@ -95,5 +145,43 @@ pub fn init_fixture_channel(path: &Path) -> BlockChannel {
         }
     });
 
-    BlockChannel(rx)
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use alloy_chains::Chain;
+
+    use super::*;
+    use crate::data::test_support::ReplayFetcher;
+
+    #[test]
+    fn custom_fetchers_fan_into_one_channel() {
+        let base = Chain::base_mainnet();
+        let fetchers: Vec<Box<dyn ChainFetcher>> = vec![
+            Box::new(ReplayFetcher(vec![BlockPayload::for_test(
+                Chain::mainnet(),
+                1,
+            )])),
+            Box::new(ReplayFetcher(vec![
+                BlockPayload::for_test(base, 7),
+                BlockPayload::for_test(base, 8),
+            ])),
+        ];
+
+        let (BlockChannel(rx), StatusChannel(statuses)) = init_fetcher_channels(fetchers);
+
+        let mut received: Vec<(Chain, u64)> = (0..3)
+            .map(|_| rx.recv_timeout(Duration::from_secs(1)).unwrap())
+            .map(|p| (p.chain, p.number))
+            .collect();
+        received.sort_by_key(|(chain, number)| (chain.id(), *number));
+        assert_eq!(received, vec![(Chain::mainnet(), 1), (base, 7), (base, 8)]);
+
+        // Fetchers without health reporting get a status stream that stays quiet.
+        assert_eq!(statuses.len(), 2);
+        assert!(statuses[0].try_recv().is_err());
+    }
 }
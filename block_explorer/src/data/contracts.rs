@@ -0,0 +1,385 @@
+//! Known-contract labels by chain and address.
+//!
+//! Cluster labels, the inspector and the category colour mode name contracts
+//! from a [`ContractRegistry`]. It ships with well-known mainnet and OP Stack
+//! contracts; users can add their own from JSON or TOML files:
+//!
+//! ```json
+//! [{ "chain": 8453, "address": "0x8335...2913", "name": "USDC", "category": "stablecoin" }]
+//! ```
+//!
+//! ```toml
+//! [[contract]]
+//! chain = 8453
+//! address = "0x8335...2913"
+//! name = "USDC"
+//! category = "stablecoin"
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use alloy::primitives::{address, Address};
+use alloy_chains::{Chain, NamedChain};
+use serde::Deserialize;
+
+use crate::data::registry_file;
+use crate::data::tokens::BUNDLED_TOKENS;
+
+/// What a known contract does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContractCategory {
+    Dex,
+    Aggregator,
+    Bridge,
+    Stablecoin,
+    Token,
+    Lending,
+    Wallet,
+    /// Rollup batch inboxes and batcher accounts.
+    Batcher,
+    /// Protocol predeploys and system contracts.
+    System,
+    #[serde(other)]
+    Other,
+}
+
+impl ContractCategory {
+    pub const ALL: [Self; 10] = [
+        Self::Dex,
+        Self::Aggregator,
+        Self::Bridge,
+        Self::Stablecoin,
+        Self::Token,
+        Self::Lending,
+        Self::Wallet,
+        Self::Batcher,
+        Self::System,
+        Self::Other,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Dex => "DEX",
+            Self::Aggregator => "Aggregator",
+            Self::Bridge => "Bridge",
+            Self::Stablecoin => "Stablecoin",
+            Self::Token => "Token",
+            Self::Lending => "Lending",
+            Self::Wallet => "Wallet",
+            Self::Batcher => "Batcher",
+            Self::System => "System",
+            Self::Other => "Other",
+        }
+    }
+}
+
+impl fmt::Display for ContractCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Name and category of a known contract.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContractLabel {
+    pub name: String,
+    pub category: ContractCategory,
+}
+
+/// Token symbols labelled as stablecoins rather than tokens.
+const STABLECOINS: &[&str] = &["USDC", "USDT", "DAI", "USDbC"];
+
+/// Well-known contracts other than tokens, which come from
+/// [`BUNDLED_TOKENS`]: chain, address, name, category.
+const BUNDLED_CONTRACTS: &[(NamedChain, Address, &str, ContractCategory)] = &[
+    (
+        NamedChain::Mainnet,
+        address!("7a250d5630b4cf539739df2c5dacb4c659f2488d"),
+        "UniV2Router",
+        ContractCategory::Dex,
+    ),
+    (
+        NamedChain::Mainnet,
+        address!("e592427a0aece92de3edee1f18e0157c05861564"),
+        "UniV3Router",
+        ContractCategory::Dex,
+    ),
+    (
+        NamedChain::Mainnet,
+        address!("68b3465833fb72a70ecdf485e0e4c7bd8665fc45"),
+        "UniRouter2",
+        ContractCategory::Dex,
+    ),
+    (
+        NamedChain::Mainnet,
+        address!("3fc91a3afd70395cd496c647d5a6cc9d4b2b7fad"),
+        "UniRouter",
+        ContractCategory::Dex,
+    ),
+    (
+        NamedChain::Mainnet,
+        address!("1111111254eeb25477b68fb85ed929f73a960582"),
+        "1inch",
+        ContractCategory::Aggregator,
+    ),
+    (
+        NamedChain::Mainnet,
+        address!("881d40237659c251811cec9c364ef91dc08d300c"),
+        "Metamask",
+        ContractCategory::Wallet,
+    ),
+    (
+        NamedChain::Mainnet,
+        address!("3154cf16ccdb4c6d922629664174b904d80f2c35"),
+        "BaseBridge",
+        ContractCategory::Bridge,
+    ),
+    (
+        NamedChain::Mainnet,
+        address!("99c9fc46f92e8a1c0dec1b1747d010903e884be1"),
+        "OPBridge",
+        ContractCategory::Bridge,
+    ),
+    (
+        NamedChain::Mainnet,
+        address!("ff00000000000000000000000000000000008453"),
+        "BaseInbox",
+        ContractCategory::Batcher,
+    ),
+    (
+        NamedChain::Mainnet,
+        address!("ff00000000000000000000000000000000000010"),
+        "OPInbox",
+        ContractCategory::Batcher,
+    ),
+    (
+        NamedChain::Mainnet,
+        address!("5050f69a9786f081509234f1a7f4684b5e5b76c9"),
+        "BaseBatcher",
+        ContractCategory::Batcher,
+    ),
+    (
+        NamedChain::Mainnet,
+        address!("6887246668a3b87f54deb3b94ba47a6f63f32985"),
+        "OPBatcher",
+        ContractCategory::Batcher,
+    ),
+    (
+        NamedChain::Base,
+        address!("3fc91a3afd70395cd496c647d5a6cc9d4b2b7fad"),
+        "UniRouter",
+        ContractCategory::Dex,
+    ),
+    (
+        NamedChain::Base,
+        address!("4200000000000000000000000000000000000010"),
+        "L2Bridge",
+        ContractCategory::Bridge,
+    ),
+    (
+        NamedChain::Base,
+        address!("4200000000000000000000000000000000000015"),
+        "L1Block",
+        ContractCategory::System,
+    ),
+    (
+        NamedChain::Optimism,
+        address!("4200000000000000000000000000000000000010"),
+        "L2Bridge",
+        ContractCategory::Bridge,
+    ),
+    (
+        NamedChain::Optimism,
+        address!("4200000000000000000000000000000000000015"),
+        "L1Block",
+        ContractCategory::System,
+    ),
+];
+
+/// Contract labels by chain and address.
+#[derive(bevy::prelude::Resource, Clone, Debug)]
+pub struct ContractRegistry {
+    contracts: HashMap<(Chain, Address), ContractLabel>,
+}
+
+/// Entry in a user contract file.
+#[derive(Deserialize)]
+struct ContractEntry {
+    chain: u64,
+    address: Address,
+    name: String,
+    #[serde(default = "other_category")]
+    category: ContractCategory,
+}
+
+fn other_category() -> ContractCategory {
+    ContractCategory::Other
+}
+
+impl Default for ContractRegistry {
+    fn default() -> Self {
+        Self::bundled()
+    }
+}
+
+impl ContractRegistry {
+    pub fn empty() -> Self {
+        Self {
+            contracts: HashMap::new(),
+        }
+    }
+
+    /// The contracts shipped with the explorer, including the bundled tokens.
+    pub fn bundled() -> Self {
+        let mut registry = Self::empty();
+        for (chain, address, symbol, _) in BUNDLED_TOKENS {
+            let category = if STABLECOINS.contains(symbol) {
+                ContractCategory::Stablecoin
+            } else {
+                ContractCategory::Token
+            };
+            registry.insert(Chain::from(*chain), *address, symbol, category);
+        }
+        for (chain, address, name, category) in BUNDLED_CONTRACTS {
+            registry.insert(Chain::from(*chain), *address, name, *category);
+        }
+        registry
+    }
+
+    pub fn insert(
+        &mut self,
+        chain: Chain,
+        address: Address,
+        name: &str,
+        category: ContractCategory,
+    ) {
+        self.contracts.insert(
+            (chain, address),
+            ContractLabel {
+                name: name.to_string(),
+                category,
+            },
+        );
+    }
+
+    /// Adds the contracts in a JSON or TOML file (by extension), replacing
+    /// any bundled entry for the same address. Returns how many were read.
+    pub fn extend_from_file(&mut self, path: &Path) -> Result<usize, String> {
+        let entries: Vec<ContractEntry> = registry_file::read_entries(path, "contract")?;
+        let count = entries.len();
+        for entry in entries {
+            self.insert(
+                Chain::from_id(entry.chain),
+                entry.address,
+                &entry.name,
+                entry.category,
+            );
+        }
+        Ok(count)
+    }
+
+    pub fn get(&self, chain: Chain, address: &Address) -> Option<&ContractLabel> {
+        self.contracts.get(&(chain, *address))
+    }
+
+    pub fn name(&self, chain: Chain, address: &Address) -> Option<&str> {
+        self.get(chain, address).map(|label| label.name.as_str())
+    }
+
+    pub fn category(&self, chain: Chain, address: &Address) -> Option<ContractCategory> {
+        self.get(chain, address).map(|label| label.category)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_are_per_chain() {
+        let registry = ContractRegistry::bundled();
+        let usdc_base = address!("833589fcd6edb6e08f4c7c32d4f71b54bda02913");
+        let weth = address!("4200000000000000000000000000000000000006");
+
+        assert_eq!(
+            registry.name(Chain::base_mainnet(), &usdc_base),
+            Some("USDC")
+        );
+        assert_eq!(registry.name(Chain::mainnet(), &usdc_base), None);
+        assert_eq!(
+            registry.category(Chain::optimism_mainnet(), &weth),
+            Some(ContractCategory::Token)
+        );
+        assert_eq!(registry.name(Chain::mainnet(), &weth), None);
+    }
+
+    #[test]
+    fn bundled_tokens_are_labelled() {
+        let registry = ContractRegistry::bundled();
+        let dai = address!("6b175474e89094c44da98b954eedeac495271d0f");
+        let arb = address!("912ce59144191c1204e64559fe8253a0e49e6548");
+
+        assert_eq!(
+            registry.category(Chain::mainnet(), &dai),
+            Some(ContractCategory::Stablecoin)
+        );
+        assert_eq!(
+            registry.get(Chain::arbitrum_mainnet(), &arb),
+            Some(&ContractLabel {
+                name: "ARB".to_string(),
+                category: ContractCategory::Token,
+            })
+        );
+    }
+
+    #[test]
+    fn reads_json_and_toml_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = dir.path().join("contracts.json");
+        let toml = dir.path().join("contracts.toml");
+        std::fs::write(
+            &json,
+            r#"[{"chain": 1, "address": "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", "name": "Circle USD", "category": "stablecoin"}]"#,
+        )
+        .unwrap();
+        std::fs::write(
+            &toml,
+            r#"
+[[contract]]
+chain = 8453
+address = "0x0000000000000000000000000000000000000abc"
+name = "Local"
+category = "lending"
+
+[[contract]]
+chain = 8453
+address = "0x0000000000000000000000000000000000000def"
+name = "Mystery"
+category = "oracle"
+"#,
+        )
+        .unwrap();
+
+        let mut registry = ContractRegistry::bundled();
+        let from_json = registry.extend_from_file(&json);
+        let from_toml = registry.extend_from_file(&toml);
+
+        assert_eq!(from_json, Ok(1));
+        assert_eq!(from_toml, Ok(2));
+        let usdc = address!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48");
+        assert_eq!(registry.name(Chain::mainnet(), &usdc), Some("Circle USD"));
+        let local = address!("0000000000000000000000000000000000000abc");
+        assert_eq!(
+            registry.category(Chain::base_mainnet(), &local),
+            Some(ContractCategory::Lending)
+        );
+        let mystery = address!("0000000000000000000000000000000000000def");
+        assert_eq!(
+            registry.category(Chain::base_mainnet(), &mystery),
+            Some(ContractCategory::Other)
+        );
+    }
+}
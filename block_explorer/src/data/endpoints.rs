@@ -0,0 +1,239 @@
+//! Multiple RPC endpoints per chain: failover, round-robin and hash quorum.
+
+use std::cell::Cell;
+use std::fmt;
+use std::str::FromStr;
+
+use alloy::primitives::B256;
+use url::Url;
+
+/// How a fetcher spreads requests over a chain's RPC endpoints.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EndpointStrategy {
+    /// Use the first endpoint and move to the next one when requests fail.
+    #[default]
+    Failover,
+    /// Rotate endpoints block by block (and batch by batch during backfill).
+    RoundRobin,
+    /// Fetch from the active endpoint and check each block hash against the
+    /// others. When a majority disagrees, the block is taken from the majority.
+    Quorum,
+}
+
+impl FromStr for EndpointStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "failover" => Ok(Self::Failover),
+            "round-robin" | "roundrobin" | "round_robin" => Ok(Self::RoundRobin),
+            "quorum" => Ok(Self::Quorum),
+            other => Err(format!(
+                "unknown endpoint strategy {other:?} (expected failover, round-robin or quorum)"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for EndpointStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Failover => "failover",
+            Self::RoundRobin => "round-robin",
+            Self::Quorum => "quorum",
+        })
+    }
+}
+
+/// Host (and port) of an endpoint, safe to log: provider API keys usually
+/// live in the path or query, which this leaves out.
+pub fn endpoint_label(url: &Url) -> String {
+    match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{host}:{port}"),
+        (Some(host), None) => host.to_string(),
+        _ => url.scheme().to_string(),
+    }
+}
+
+/// Connected providers for one chain plus the index of the active one.
+pub(crate) struct Endpoints<P> {
+    providers: Vec<(Url, P)>,
+    active: Cell<usize>,
+    strategy: EndpointStrategy,
+}
+
+impl<P> Endpoints<P> {
+    /// `providers` must not be empty; the first entry starts active.
+    pub fn new(providers: Vec<(Url, P)>, strategy: EndpointStrategy) -> Self {
+        assert!(!providers.is_empty(), "at least one endpoint is required");
+        Self {
+            providers,
+            active: Cell::new(0),
+            strategy,
+        }
+    }
+
+    pub fn strategy(&self) -> EndpointStrategy {
+        self.strategy
+    }
+
+    pub fn len(&self) -> usize {
+        self.providers.len()
+    }
+
+    pub fn active(&self) -> &P {
+        &self.providers[self.active.get()].1
+    }
+
+    pub fn active_index(&self) -> usize {
+        self.active.get()
+    }
+
+    pub fn active_url(&self) -> &Url {
+        &self.providers[self.active.get()].0
+    }
+
+    pub fn active_label(&self) -> String {
+        endpoint_label(&self.providers[self.active.get()].0)
+    }
+
+    pub fn label(&self, index: usize) -> String {
+        endpoint_label(&self.providers[index].0)
+    }
+
+    /// The provider `offset` places after the active one, wrapping around.
+    pub fn nth_from_active(&self, offset: usize) -> &P {
+        &self.providers[(self.active.get() + offset) % self.providers.len()].1
+    }
+
+    /// Makes endpoint `index` active.
+    pub fn select(&self, index: usize) {
+        self.active.set(index % self.providers.len());
+    }
+
+    /// Moves to the next endpoint. Returns false when there is only one.
+    pub fn rotate(&self) -> bool {
+        if self.providers.len() < 2 {
+            return false;
+        }
+        self.select(self.active.get() + 1);
+        true
+    }
+
+    /// Every provider with its index, active one included.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &P)> {
+        self.providers.iter().map(|(_, p)| p).enumerate()
+    }
+}
+
+/// Result of comparing the block hash each endpoint reported.
+#[derive(Debug, PartialEq, Eq)]
+pub struct QuorumOutcome {
+    /// Hash reported by a strict majority of the endpoints that answered.
+    pub majority: Option<B256>,
+    /// Endpoints that answered with a different hash than the majority (or,
+    /// without a majority, than the active endpoint).
+    pub dissenters: Vec<usize>,
+}
+
+/// Finds the hash a strict majority of responding endpoints agree on.
+/// `hashes[i]` is `None` when endpoint `i` didn't answer.
+pub fn quorum(hashes: &[Option<B256>], active: usize) -> QuorumOutcome {
+    let answered: Vec<(usize, B256)> = hashes
+        .iter()
+        .enumerate()
+        .filter_map(|(i, h)| h.map(|h| (i, h)))
+        .collect();
+
+    let majority = answered.iter().map(|(_, h)| *h).find(|candidate| {
+        let votes = answered.iter().filter(|(_, h)| h == candidate).count();
+        votes * 2 > answered.len()
+    });
+
+    let reference = majority.or(hashes.get(active).copied().flatten());
+    let dissenters = match reference {
+        Some(reference) => answered
+            .iter()
+            .filter(|(_, h)| *h != reference)
+            .map(|(i, _)| *i)
+            .collect(),
+        None => Vec::new(),
+    };
+
+    QuorumOutcome {
+        majority,
+        dissenters,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(byte: u8) -> Option<B256> {
+        Some(B256::repeat_byte(byte))
+    }
+
+    #[test]
+    fn strategy_parses_from_env_values() {
+        assert_eq!(
+            "failover".parse::<EndpointStrategy>(),
+            Ok(EndpointStrategy::Failover)
+        );
+        assert_eq!(
+            "Round-Robin".parse::<EndpointStrategy>(),
+            Ok(EndpointStrategy::RoundRobin)
+        );
+        assert_eq!(
+            " quorum ".parse::<EndpointStrategy>(),
+            Ok(EndpointStrategy::Quorum)
+        );
+        assert!("random".parse::<EndpointStrategy>().is_err());
+    }
+
+    #[test]
+    fn endpoint_label_hides_path_and_query() {
+        let url: Url = "https://eth-mainnet.g.alchemy.com/v2/SECRET?key=x"
+            .parse()
+            .unwrap();
+        assert_eq!(endpoint_label(&url), "eth-mainnet.g.alchemy.com");
+        let url: Url = "ws://127.0.0.1:8546".parse().unwrap();
+        assert_eq!(endpoint_label(&url), "127.0.0.1:8546");
+    }
+
+    #[test]
+    fn rotate_wraps_and_needs_two_endpoints() {
+        let url: Url = "http://a".parse().unwrap();
+        let single = Endpoints::new(vec![(url.clone(), 'a')], EndpointStrategy::Failover);
+        assert!(!single.rotate());
+
+        let endpoints = Endpoints::new(
+            vec![(url.clone(), 'a'), (url.clone(), 'b'), (url, 'c')],
+            EndpointStrategy::RoundRobin,
+        );
+        assert_eq!(*endpoints.active(), 'a');
+        assert_eq!(*endpoints.nth_from_active(2), 'c');
+        assert!(endpoints.rotate());
+        assert!(endpoints.rotate());
+        assert!(endpoints.rotate());
+        assert_eq!(*endpoints.active(), 'a');
+    }
+
+    #[test]
+    fn quorum_finds_majority_and_dissenters() {
+        let outcome = quorum(&[hash(1), hash(2), hash(2)], 0);
+        assert_eq!(outcome.majority, hash(2));
+        assert_eq!(outcome.dissenters, vec![0]);
+
+        let outcome = quorum(&[hash(1), None, hash(1)], 0);
+        assert_eq!(outcome.majority, hash(1));
+        assert!(outcome.dissenters.is_empty());
+    }
+
+    #[test]
+    fn quorum_without_majority_compares_against_active() {
+        let outcome = quorum(&[hash(1), hash(2)], 0);
+        assert_eq!(outcome.majority, None);
+        assert_eq!(outcome.dissenters, vec![1]);
+    }
+}
@@ -0,0 +1,413 @@
+//! Chain-agnostic transaction envelope.
+//!
+//! Fetchers map their chain's transactions into a [`TransactionEnvelope`];
+//! rendering and UI read only the envelope (and [`ChainUnits`] to label it).
+//! Chain-specific detail rides along in optional typed addenda.
+//!
+//! [`ChainUnits`]: crate::data::ChainUnits
+
+use alloy::primitives::{Address, Bytes, Selector, B256, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::data::model::OpStackFees;
+use crate::data::tokens::TokenTransfer;
+use crate::data::traces::CallFrame;
+
+/// A single transaction's display-relevant fields, independent of the chain.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "EnvelopeRepr")]
+pub struct TransactionEnvelope {
+    /// Transaction hash (EVM) or the leading 32 bytes of the first signature (Solana).
+    pub id: B256,
+    /// Position within the block.
+    pub index: usize,
+    /// Resource the transaction may consume: gas limit, compute units.
+    pub resource_limit: u64,
+    /// Resource actually consumed, when the fetcher knows it.
+    #[serde(default)]
+    pub resource_used: Option<u64>,
+    /// Price bid per resource unit, in the chain's price unit (wei per gas,
+    /// micro-lamports per compute unit).
+    pub price: u128,
+    /// Total fee paid in the chain's smallest native unit (wei, lamports),
+    /// L1 data fee included.
+    #[serde(default)]
+    pub fee_paid: Option<u128>,
+    /// Native currency moved, exactly, in the chain's smallest unit (wei,
+    /// lamports).
+    #[serde(serialize_with = "serialize_hex")]
+    pub value: U256,
+    /// On Solana, a hash of the fee payer's key; the key itself is in the
+    /// [`SolanaAddendum`].
+    pub sender: Address,
+    /// `None` for contract creations. On Solana, a hash of the invoked
+    /// program's key.
+    pub recipient: Option<Address>,
+    /// Execution outcome, when the fetcher knows it.
+    #[serde(default)]
+    pub success: Option<bool>,
+    #[serde(default)]
+    pub evm: Option<EvmAddendum>,
+    /// OP Stack L1 fee data (present only for OP Stack L2 transactions with receipts).
+    #[serde(default)]
+    pub op_stack: Option<OpStackFees>,
+    #[serde(default)]
+    pub solana: Option<SolanaAddendum>,
+}
+
+/// EVM detail: blobs and receipt fields without a neutral counterpart.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EvmAddendum {
+    /// Sender nonce; a pending transaction is replaced by another with the
+    /// same sender and nonce.
+    #[serde(default)]
+    pub nonce: Option<u64>,
+    /// First four bytes of the calldata; `None` for plain transfers.
+    #[serde(default)]
+    pub selector: Option<Selector>,
+    /// Calldata after the selector, cut to [`MAX_ARG_WORDS`] words.
+    ///
+    /// [`MAX_ARG_WORDS`]: crate::data::selectors::MAX_ARG_WORDS
+    #[serde(default)]
+    pub args: Bytes,
+    /// EIP-2718 type byte; see [`TxType`].
+    #[serde(default)]
+    pub tx_type: Option<u8>,
+    /// Fee cap per gas (EIP-1559 style transactions only).
+    #[serde(default)]
+    pub max_fee_per_gas: Option<u128>,
+    /// Tip cap per gas (EIP-1559 style transactions only).
+    #[serde(default)]
+    pub max_priority_fee_per_gas: Option<u128>,
+    /// Addresses in the access list.
+    #[serde(default)]
+    pub access_list_addresses: usize,
+    /// Storage keys across all access list entries.
+    #[serde(default)]
+    pub access_list_keys: usize,
+    /// EIP-7702 delegations carried by a set-code transaction.
+    #[serde(default)]
+    pub authorization_count: usize,
+    pub blob_count: usize,
+    pub max_fee_per_blob_gas: Option<u128>,
+    /// Price actually paid per gas (from the receipt).
+    #[serde(default)]
+    pub effective_gas_price: Option<u128>,
+    #[serde(default)]
+    pub logs_count: Option<usize>,
+    /// Address of the contract deployed by a creation transaction, from the
+    /// receipt or derived from the sender and nonce.
+    #[serde(default)]
+    pub contract_address: Option<Address>,
+    /// ERC-20/721 `Transfer` events from the receipt logs, in log order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub token_transfers: Vec<TokenTransfer>,
+    /// Call tree from the `callTracer`, when tracing is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<CallFrame>,
+}
+
+/// EIP-2718 transaction type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TxType {
+    Legacy,
+    /// EIP-2930 access list transaction.
+    AccessList,
+    /// EIP-1559 fee market transaction.
+    DynamicFee,
+    /// EIP-4844 blob transaction.
+    Blob,
+    /// EIP-7702 set-code (delegation) transaction.
+    SetCode,
+    /// OP Stack deposit, derived from L1 rather than signed on L2.
+    Deposit,
+    Other(u8),
+}
+
+impl TxType {
+    pub fn from_byte(ty: u8) -> Self {
+        match ty {
+            0x00 => Self::Legacy,
+            0x01 => Self::AccessList,
+            0x02 => Self::DynamicFee,
+            0x03 => Self::Blob,
+            0x04 => Self::SetCode,
+            0x7e => Self::Deposit,
+            other => Self::Other(other),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Legacy => "Legacy",
+            Self::AccessList => "EIP-2930",
+            Self::DynamicFee => "EIP-1559",
+            Self::Blob => "EIP-4844",
+            Self::SetCode => "EIP-7702",
+            Self::Deposit => "Deposit",
+            Self::Other(_) => "Unknown",
+        }
+    }
+}
+
+/// Solana detail: full base58 keys, which the envelope's 20/32-byte ids truncate.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SolanaAddendum {
+    pub signature: String,
+    pub fee_payer: String,
+    /// First invoked program other than the compute budget program.
+    pub program: Option<String>,
+    /// Fee above the per-signature base fee, in lamports.
+    pub priority_fee: u64,
+    pub log_messages: usize,
+}
+
+impl TransactionEnvelope {
+    /// Resource consumed, falling back to the limit when usage is unknown.
+    pub fn resource_used_or_limit(&self) -> u64 {
+        self.resource_used.unwrap_or(self.resource_limit)
+    }
+
+    /// Returns true if the transaction is known to have failed.
+    pub fn reverted(&self) -> bool {
+        self.success == Some(false)
+    }
+
+    /// Blobs carried by the transaction (EVM type-3 only).
+    pub fn blob_count(&self) -> usize {
+        self.evm.as_ref().map_or(0, |evm| evm.blob_count)
+    }
+
+    /// Token movements decoded from the receipt (EVM with receipts only).
+    pub fn token_transfers(&self) -> &[TokenTransfer] {
+        self.evm
+            .as_ref()
+            .map_or(&[], |evm| evm.token_transfers.as_slice())
+    }
+
+    /// Internal call tree (EVM with tracing only).
+    pub fn trace(&self) -> Option<&CallFrame> {
+        self.evm.as_ref()?.trace.as_ref()
+    }
+
+    /// Contract deployed by a creation that didn't revert (EVM only).
+    pub fn deployed_contract(&self) -> Option<Address> {
+        if self.recipient.is_some() || self.reverted() {
+            return None;
+        }
+        self.evm.as_ref()?.contract_address
+    }
+
+    /// Transaction type (EVM only).
+    pub fn tx_type(&self) -> Option<TxType> {
+        self.evm.as_ref()?.tx_type.map(TxType::from_byte)
+    }
+
+    /// Function selector of the call (EVM only).
+    pub fn selector(&self) -> Option<Selector> {
+        self.evm.as_ref()?.selector
+    }
+
+    /// Fee paid for execution, excluding any L1 data fee.
+    pub fn execution_fee(&self) -> Option<u128> {
+        let l1_fee = self.op_stack.as_ref().map_or(0, |fees| fees.l1_fee);
+        Some(self.fee_paid?.saturating_sub(l1_fee))
+    }
+
+    /// Share of the total fee paid for L1 data (OP Stack transactions with receipts).
+    pub fn l1_fee_share(&self) -> Option<f32> {
+        let fees = self.op_stack.as_ref()?;
+        Some(fees.l1_share(self.execution_fee()?))
+    }
+
+    /// Full transaction id as the chain writes it.
+    pub fn id_display(&self) -> String {
+        match &self.solana {
+            Some(solana) => solana.signature.clone(),
+            None => self.id.to_string(),
+        }
+    }
+
+    /// Sender as the chain writes it.
+    pub fn sender_display(&self) -> String {
+        match &self.solana {
+            Some(solana) => solana.fee_payer.clone(),
+            None => self.sender.to_string(),
+        }
+    }
+
+    /// Recipient as the chain writes it, `None` for contract creations.
+    pub fn recipient_display(&self) -> Option<String> {
+        match &self.solana {
+            Some(solana) => solana.program.clone(),
+            None => self.recipient.map(|addr| addr.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+impl TransactionEnvelope {
+    /// A plain 21,000-gas transfer for tests, with its hash derived from the
+    /// index. Override fields with struct update syntax as needed.
+    pub(crate) fn for_test(index: usize) -> Self {
+        Self {
+            id: B256::with_last_byte(index as u8),
+            index,
+            resource_limit: 21_000,
+            resource_used: None,
+            price: 0,
+            fee_paid: None,
+            value: U256::ZERO,
+            sender: Address::ZERO,
+            recipient: None,
+            success: None,
+            evm: None,
+            op_stack: None,
+            solana: None,
+        }
+    }
+}
+
+/// Deserialization shape accepting both the envelope and the EVM-only
+/// transaction fixtures were recorded in before it (`hash`, `gas_price`,
+/// `value_eth`, a nested `receipt`, ...).
+#[derive(Deserialize)]
+struct EnvelopeRepr {
+    #[serde(alias = "hash")]
+    id: B256,
+    #[serde(alias = "tx_index")]
+    index: usize,
+    #[serde(alias = "gas")]
+    resource_limit: u64,
+    #[serde(default)]
+    resource_used: Option<u64>,
+    #[serde(alias = "gas_price")]
+    price: u128,
+    #[serde(default)]
+    fee_paid: Option<u128>,
+    #[serde(default)]
+    value: Option<ExactValue>,
+    /// Older fixtures' float value, always in whole native units.
+    #[serde(default)]
+    value_eth: Option<f64>,
+    #[serde(alias = "from")]
+    sender: Address,
+    #[serde(alias = "to")]
+    recipient: Option<Address>,
+    #[serde(default)]
+    success: Option<bool>,
+    #[serde(default)]
+    evm: Option<EvmAddendum>,
+    #[serde(default, alias = "op_stack_fees")]
+    op_stack: Option<OpStackFees>,
+    #[serde(default)]
+    solana: Option<SolanaAddendum>,
+    #[serde(default)]
+    blob_count: Option<usize>,
+    #[serde(default)]
+    max_fee_per_blob_gas: Option<u128>,
+    #[serde(default)]
+    receipt: Option<LegacyReceipt>,
+}
+
+/// Writes a value as a hex string in every format. Binary formats would
+/// otherwise get raw bytes, which [`ExactValue`] can't read back.
+fn serialize_hex<S: serde::Serializer>(value: &U256, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{value:#x}"))
+}
+
+/// An exact amount in the smallest unit: an integer, or a hex or decimal
+/// string. Floats are refused rather than guessed at; whole-unit amounts
+/// belong in `value_eth`.
+struct ExactValue(U256);
+
+impl<'de> Deserialize<'de> for ExactValue {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = ExactValue;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("an integer amount in the smallest unit")
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<ExactValue, E> {
+                Ok(ExactValue(U256::from(v)))
+            }
+
+            fn visit_u128<E: serde::de::Error>(self, v: u128) -> Result<ExactValue, E> {
+                Ok(ExactValue(U256::from(v)))
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<ExactValue, E> {
+                v.parse().map(ExactValue).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+/// Converts a float of whole native units to the smallest unit.
+fn whole_to_smallest_units(whole: f64, decimals: i32) -> U256 {
+    // Saturating cast: negative and NaN amounts become zero.
+    U256::from((whole * 10f64.powi(decimals)).round() as u128)
+}
+
+#[derive(Deserialize)]
+struct LegacyReceipt {
+    gas_used: u64,
+    status: bool,
+    effective_gas_price: u128,
+    logs_count: usize,
+    contract_address: Option<Address>,
+}
+
+impl From<EnvelopeRepr> for TransactionEnvelope {
+    fn from(repr: EnvelopeRepr) -> Self {
+        // `value_eth` is SOL on Solana and ETH everywhere else.
+        let decimals = if repr.solana.is_some() { 9 } else { 18 };
+        let mut envelope = Self {
+            id: repr.id,
+            index: repr.index,
+            resource_limit: repr.resource_limit,
+            resource_used: repr.resource_used,
+            price: repr.price,
+            fee_paid: repr.fee_paid,
+            value: match (repr.value, repr.value_eth) {
+                (Some(ExactValue(value)), _) => value,
+                (None, Some(whole)) => whole_to_smallest_units(whole, decimals),
+                (None, None) => U256::ZERO,
+            },
+            sender: repr.sender,
+            recipient: repr.recipient,
+            success: repr.success,
+            evm: repr.evm,
+            op_stack: repr.op_stack,
+            solana: repr.solana,
+        };
+
+        // Legacy EVM transactions carry blobs and the receipt at the top level.
+        if let Some(blob_count) = repr.blob_count {
+            let evm = envelope.evm.get_or_insert_with(EvmAddendum::default);
+            evm.blob_count = blob_count;
+            evm.max_fee_per_blob_gas = repr.max_fee_per_blob_gas;
+        }
+        if let Some(receipt) = repr.receipt {
+            let l1_fee = envelope.op_stack.as_ref().map_or(0, |fees| fees.l1_fee);
+            envelope.resource_used = Some(receipt.gas_used);
+            envelope.success = Some(receipt.status);
+            envelope.fee_paid = Some(
+                (receipt.gas_used as u128)
+                    .saturating_mul(receipt.effective_gas_price)
+                    .saturating_add(l1_fee),
+            );
+            let evm = envelope.evm.get_or_insert_with(EvmAddendum::default);
+            evm.effective_gas_price = Some(receipt.effective_gas_price);
+            evm.logs_count = Some(receipt.logs_count);
+            evm.contract_address = receipt.contract_address;
+        }
+        envelope
+    }
+}
@@ -1,17 +1,42 @@
 //! EVM block fetcher: dedicated thread + alloy → BlockPayload.
 
+use alloy::consensus::{BlockHeader, Transaction as TxConsensus};
 use alloy::eips::BlockNumberOrTag;
-use alloy::primitives::{address, Address};
-use alloy::providers::{Provider, ProviderBuilder};
-use alloy::rpc::types::BlockTransactions;
+use alloy::network::primitives::HeaderResponse;
+use alloy::network::{BlockResponse, Ethereum, Network, TransactionResponse};
+use alloy::primitives::{address, Address, Bytes, Selector, B256};
+use alloy::providers::{Identity, Provider, ProviderBuilder};
+use alloy::rpc::client::BatchRequest;
+use alloy::rpc::types::{BlockTransactions, Log};
+use alloy::transports::{TransportError, TransportErrorKind};
 use alloy_chains::Chain;
 use crossbeam_channel::{Receiver, Sender};
+use futures::StreamExt;
+use op_alloy::network::Optimism;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::ops::RangeInclusive;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
 use url::Url;
 
-use crate::data::model::{BlockPayload, TxPayload};
-use crate::data::{ChainFetcher, FetcherConfig};
+use crate::data::endpoints::{endpoint_label, quorum, EndpointStrategy, Endpoints};
+use crate::data::envelope::{EvmAddendum, TransactionEnvelope};
+use crate::data::model::{BlockPayload, OpStackFees, ReorgNotice};
+use crate::data::reorg::HashChain;
+use crate::data::retry::{jitter, method_unsupported, with_retries, Backoff, GapQueue};
+use crate::data::selectors::MAX_ARG_WORDS;
+use crate::data::status::{
+    ConnectionState, FetcherStatus, StatusReporter, STATUS_CHANNEL_CAPACITY,
+};
+use crate::data::tokens::TokenTransfer;
+use crate::data::traces::RpcTraceResult;
+use crate::data::{
+    BlockStart, ChainFetcher, FetcherConfig, DEFAULT_BATCH_SIZE, DEFAULT_CONCURRENCY,
+};
 
 const BACKFILL_COUNT: u64 = 20;
 const POLL_INTERVAL: Duration = Duration::from_secs(2);
@@ -20,13 +45,37 @@ const POLL_INTERVAL: Duration = Duration::from_secs(2);
 const L1_BLOCK_PREDEPLOY: Address = address!("4200000000000000000000000000000000000015");
 
 /// EVM-compatible block fetcher using Alloy.
-pub struct EvmFetcher;
+///
+/// Follows the chain tip with `eth_subscribe("newHeads")` when the active RPC
+/// URL is a WebSocket endpoint, and polls `eth_blockNumber` otherwise. Extra
+/// endpoints are used according to [`FetcherConfig::strategy`].
+pub struct EvmFetcher {
+    config: FetcherConfig,
+}
+
+impl EvmFetcher {
+    pub fn new(config: FetcherConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &FetcherConfig {
+        &self.config
+    }
+}
 
 impl ChainFetcher for EvmFetcher {
-    fn spawn(config: FetcherConfig) -> Receiver<BlockPayload> {
+    fn spawn(self: Box<Self>) -> Receiver<BlockPayload> {
+        self.spawn_with_status().0
+    }
+
+    fn spawn_with_status(self: Box<Self>) -> (Receiver<BlockPayload>, Receiver<FetcherStatus>) {
+        let config = self.config;
         let (tx, rx) = crossbeam_channel::bounded(64);
+        let (status_tx, status_rx) = crossbeam_channel::bounded(STATUS_CHANNEL_CAPACITY);
+        let status = StatusReporter::new(config.chain, status_tx);
         let is_op = crate::data::is_op_stack(&config.chain);
         thread::spawn(move || {
+            let mut status = status;
             let rt = match tokio::runtime::Builder::new_current_thread()
                 .enable_all()
                 .build()
@@ -34,265 +83,1067 @@ impl ChainFetcher for EvmFetcher {
                 Ok(rt) => rt,
                 Err(err) => {
                     eprintln!("tessera: failed to build tokio runtime: {err}");
+                    status.record_failure(format!("tokio runtime: {err}"));
                     return;
                 }
             };
             if is_op {
-                rt.block_on(op_stack_fetcher_loop(config.chain, config.rpc_url, tx));
+                rt.block_on(fetcher_loop::<Optimism>(config, tx, status));
             } else {
-                rt.block_on(fetcher_loop(config.chain, config.rpc_url, tx));
+                rt.block_on(fetcher_loop::<Ethereum>(config, tx, status));
             }
         });
-        rx
+        (rx, status_rx)
     }
 }
 
+/// Returns true if the RPC URL points at a WebSocket endpoint, which supports
+/// `eth_subscribe` push notifications.
+pub(crate) fn is_pubsub_url(url: &Url) -> bool {
+    matches!(url.scheme(), "ws" | "wss")
+}
+
 // ---------------------------------------------------------------------------
-// Standard (L1) fetcher
+// Fetcher loop (shared by L1 and OP Stack networks)
 // ---------------------------------------------------------------------------
 
-async fn fetcher_loop(chain: Chain, rpc_url: Url, tx: Sender<BlockPayload>) {
-    let provider = ProviderBuilder::new().connect_http(rpc_url);
+async fn fetcher_loop<N>(
+    config: FetcherConfig,
+    tx: Sender<BlockPayload>,
+    mut status: StatusReporter,
+) where
+    N: Network,
+    N::TransactionResponse: TxConsensus,
+{
+    let FetcherConfig {
+        chain,
+        rpc_url,
+        fallback_urls,
+        strategy,
+        receipts,
+        traces,
+        mempool: _,
+        from,
+        to,
+        concurrency,
+        batch_size,
+    } = config;
 
-    let latest = match provider.get_block_number().await {
-        Ok(n) => n,
-        Err(err) => {
-            eprintln!("tessera [{chain}]: failed to get latest block number: {err}");
-            return;
+    // Use default() (no fillers) since we only read blocks, not send transactions.
+    // ProviderBuilder::new() adds recommended fillers that are incompatible with
+    // the OP Stack transaction request type.
+    let mut providers = Vec::new();
+    let mut last_error = String::new();
+    for url in std::iter::once(rpc_url).chain(fallback_urls) {
+        let builder: ProviderBuilder<Identity, Identity> = ProviderBuilder::default();
+        match builder.network::<N>().connect(url.as_str()).await {
+            Ok(provider) => providers.push((url, provider)),
+            Err(err) => {
+                let label = endpoint_label(&url);
+                eprintln!("tessera [{chain}]: failed to connect to RPC {label}: {err}");
+                last_error = format!("connect {label}: {err}");
+            }
         }
+    }
+    if providers.is_empty() {
+        status.record_failure(last_error);
+        return;
+    }
+    let endpoints = Endpoints::new(providers, strategy);
+
+    // Take the tip from the first endpoint that answers.
+    let mut latest = None;
+    for _ in 0..endpoints.len() {
+        let started = Instant::now();
+        match endpoints.active().get_block_number().await {
+            Ok(n) => {
+                status.record_success(started.elapsed());
+                latest = Some(n);
+                break;
+            }
+            Err(err) => {
+                let label = endpoints.active_label();
+                eprintln!(
+                    "tessera [{chain}]: failed to get latest block number from {label}: {err}"
+                );
+                last_error = format!("eth_blockNumber: {err}");
+                endpoints.rotate();
+            }
+        }
+    }
+    let Some(latest) = latest else {
+        status.record_failure(last_error);
+        return;
     };
+    status.set_endpoint(endpoints.active_label());
+    status.set_tip(latest);
+    status.set_state(ConnectionState::Syncing);
 
-    let start = latest.saturating_sub(BACKFILL_COUNT - 1);
-    eprintln!("tessera [{chain}]: backfilling blocks {start}..={latest}");
+    let mut fetcher = BlockFetcher::new(endpoints, chain, tx, status);
+    fetcher.receipts = receipts;
+    fetcher.traces = traces;
+    fetcher.stop_at = to;
+    fetcher.concurrency = concurrency.max(1);
+    fetcher.batch_size = batch_size.max(1);
 
-    for n in start..=latest {
-        if fetch_and_send(&provider, chain, n, &tx).await.is_err() {
-            return;
+    let end = to.map_or(latest, |to| to.min(latest));
+    let start = match from {
+        Some(BlockStart::Number(number)) => number,
+        Some(BlockStart::Timestamp(timestamp)) => {
+            let Some(number) = fetcher.block_at_timestamp(timestamp, latest).await else {
+                fetcher
+                    .status
+                    .record_failure(format!("no block found for timestamp {timestamp}"));
+                return;
+            };
+            eprintln!("tessera [{chain}]: timestamp {timestamp} resolves to block {number}");
+            number
         }
+        None => end.saturating_sub(BACKFILL_COUNT - 1),
+    };
+    eprintln!("tessera [{chain}]: backfilling blocks {start}..={end}");
+
+    if fetcher.fetch_range(start, end).await.is_err() {
+        return;
     }
 
-    eprintln!("tessera [{chain}]: backfill complete, polling for new blocks");
+    // A start beyond the tip waits for the chain to catch up to it.
+    let mut last_seen = end.max(start.saturating_sub(1));
+    if fetcher.reached_end(last_seen) {
+        eprintln!("tessera [{chain}]: range fetched");
+    } else if is_pubsub_url(fetcher.endpoints.active_url()) {
+        eprintln!("tessera [{chain}]: backfill complete, subscribing to new heads");
+        fetcher.status.set_state(ConnectionState::Live);
+        if fetcher.follow_new_heads(&mut last_seen).await.is_err() {
+            return;
+        }
+        if !fetcher.reached_end(last_seen) {
+            eprintln!("tessera [{chain}]: subscription dropped, falling back to polling");
+        }
+    } else {
+        eprintln!("tessera [{chain}]: backfill complete, polling for new blocks");
+    }
+    if !fetcher.reached_end(last_seen) {
+        fetcher.status.set_state(ConnectionState::Live);
+    }
 
-    let mut last_seen = latest;
     loop {
+        if fetcher.reached_end(last_seen) && fetcher.gaps.is_empty() {
+            eprintln!("tessera [{chain}]: range complete");
+            fetcher.status.set_state(ConnectionState::Finished);
+            return;
+        }
         tokio::time::sleep(POLL_INTERVAL).await;
 
-        let tip = match provider.get_block_number().await {
+        if fetcher.retry_gaps().await.is_err() {
+            return;
+        }
+        if fetcher.reached_end(last_seen) {
+            continue;
+        }
+
+        let started = Instant::now();
+        let tip = match fetcher.endpoints.active().get_block_number().await {
             Ok(n) => n,
             Err(err) => {
                 eprintln!("tessera [{chain}]: poll error: {err}");
+                fetcher.status.record_retry(format!("poll: {err}"));
+                fetcher.rotate_endpoint();
                 continue;
             }
         };
+        fetcher.status.record_success(started.elapsed());
+        fetcher.status.set_tip(fetcher.clamp_to_end(tip));
 
-        for n in (last_seen + 1)..=tip {
-            if fetch_and_send(&provider, chain, n, &tx).await.is_err() {
-                return;
-            }
+        if fetcher.fetch_range(last_seen + 1, tip).await.is_err() {
+            return;
         }
-        last_seen = tip;
+        last_seen = last_seen.max(fetcher.clamp_to_end(tip));
     }
 }
 
-async fn fetch_and_send(
-    provider: &impl Provider,
+/// Binary-searches `0..=latest` for the first block whose timestamp is at or
+/// after `timestamp`, using `block_timestamp` to look up each probe.
+///
+/// Returns `latest` when every block is older than `timestamp`, and `None` if
+/// a lookup fails.
+async fn search_block_by_timestamp<F, Fut>(
+    timestamp: u64,
+    latest: u64,
+    mut block_timestamp: F,
+) -> Option<u64>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Option<u64>>,
+{
+    let (mut low, mut high) = (0, latest);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if block_timestamp(mid).await? < timestamp {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    Some(low)
+}
+
+/// Per-chain fetch state shared by backfill and tip following.
+///
+/// Methods returning `Result<(), ()>` fail only when the receiver is gone.
+struct BlockFetcher<N, P> {
+    endpoints: Endpoints<P>,
     chain: Chain,
-    number: u64,
-    tx: &Sender<BlockPayload>,
-) -> Result<(), ()> {
-    let block = match provider
-        .get_block_by_number(BlockNumberOrTag::Number(number))
-        .full()
+    tx: Sender<BlockPayload>,
+    hashes: HashChain,
+    /// Whether to run the receipts pass on every fetched block.
+    receipts: bool,
+    /// Cleared after the first failed `eth_getBlockReceipts` call so later
+    /// blocks go straight to per-transaction lookups.
+    block_receipts_supported: bool,
+    /// Whether to trace internal calls on every fetched block.
+    traces: bool,
+    /// Cleared after the first failed `debug_traceBlockByNumber` call.
+    traces_supported: bool,
+    /// Last block of a historical range; nothing past it is fetched.
+    stop_at: Option<u64>,
+    /// Batches in flight during multi-block fetches.
+    concurrency: usize,
+    /// Blocks per JSON-RPC batch request.
+    batch_size: usize,
+    backoff: Backoff,
+    /// Blocks that failed every attempt and are re-fetched in the background.
+    gaps: GapQueue,
+    status: StatusReporter,
+    _network: PhantomData<N>,
+}
+
+impl<N, P> BlockFetcher<N, P>
+where
+    N: Network,
+    N::TransactionResponse: TxConsensus,
+    P: Provider<N> + Clone,
+{
+    fn new(
+        endpoints: Endpoints<P>,
+        chain: Chain,
+        tx: Sender<BlockPayload>,
+        status: StatusReporter,
+    ) -> Self {
+        Self {
+            endpoints,
+            chain,
+            tx,
+            hashes: HashChain::default(),
+            receipts: false,
+            block_receipts_supported: true,
+            traces: false,
+            traces_supported: true,
+            stop_at: None,
+            concurrency: DEFAULT_CONCURRENCY,
+            batch_size: DEFAULT_BATCH_SIZE,
+            backoff: Backoff::default(),
+            gaps: GapQueue::default(),
+            status,
+            _network: PhantomData,
+        }
+    }
+
+    /// Moves to the next endpoint, if there is one, and reports the switch.
+    fn rotate_endpoint(&mut self) {
+        if self.endpoints.rotate() {
+            self.status.set_endpoint(self.endpoints.active_label());
+        }
+    }
+
+    /// Whether the configured range end has been fetched.
+    fn reached_end(&self, last_seen: u64) -> bool {
+        self.stop_at.is_some_and(|stop| last_seen >= stop)
+    }
+
+    fn clamp_to_end(&self, number: u64) -> u64 {
+        self.stop_at.map_or(number, |stop| number.min(stop))
+    }
+
+    /// Resolves a Unix timestamp to the first block produced at or after it.
+    async fn block_at_timestamp(&self, timestamp: u64, latest: u64) -> Option<u64> {
+        let chain = self.chain;
+        search_block_by_timestamp(timestamp, latest, |number| async move {
+            match self
+                .endpoints
+                .active()
+                .get_block_by_number(BlockNumberOrTag::Number(number))
+                .await
+            {
+                Ok(Some(block)) => Some(block.header().timestamp()),
+                Ok(None) => {
+                    eprintln!(
+                        "tessera [{chain}]: block {number} not found during timestamp search"
+                    );
+                    None
+                }
+                Err(err) => {
+                    eprintln!(
+                        "tessera [{chain}]: timestamp search failed at block {number}: {err}"
+                    );
+                    None
+                }
+            }
+        })
         .await
-    {
-        Ok(Some(block)) => block,
-        Ok(None) => {
-            eprintln!("tessera: block {number} not found");
-            return Ok(());
+    }
+
+    /// Follows the chain tip through an `eth_subscribe("newHeads")` subscription,
+    /// fetching every block between the last one seen and each announced head.
+    ///
+    /// Returns `Ok(())` when the subscription can't be established or ends, so
+    /// the caller can fall back to polling.
+    async fn follow_new_heads(&mut self, last_seen: &mut u64) -> Result<(), ()> {
+        let chain = self.chain;
+        let mut subscription = match self.endpoints.active().subscribe_blocks().await {
+            Ok(subscription) => subscription,
+            Err(err) => {
+                eprintln!("tessera [{chain}]: newHeads subscription failed: {err}");
+                return Ok(());
+            }
+        };
+
+        loop {
+            match subscription.recv().await {
+                Ok(head) => {
+                    let tip = head.number();
+                    self.status.set_tip(self.clamp_to_end(tip));
+                    self.retry_gaps().await?;
+                    self.fetch_range(*last_seen + 1, tip).await?;
+                    *last_seen = (*last_seen).max(self.clamp_to_end(tip));
+                    if self.reached_end(*last_seen) {
+                        return Ok(());
+                    }
+                }
+                // Skipped heads are picked up by the next head's catch-up range.
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return Ok(()),
+            }
         }
-        Err(err) => {
-            eprintln!("tessera: failed to fetch block {number}: {err}");
+    }
+
+    /// Fetches and sends blocks `start..=end` in order.
+    ///
+    /// Multi-block ranges are split into JSON-RPC batches with up to
+    /// `concurrency` batches in flight; results are still delivered in number
+    /// order. Blocks missing from a batch response are re-fetched one by one.
+    /// Round-robin spreads the batches over every endpoint.
+    async fn fetch_range(&mut self, start: u64, end: u64) -> Result<(), ()> {
+        let end = self.clamp_to_end(end);
+        if start >= end || (self.batch_size == 1 && self.concurrency == 1) {
+            for n in start..=end {
+                self.fetch_and_send(n).await?;
+            }
             return Ok(());
         }
-    };
 
-    let payload = block_to_payload(chain, &block);
-    eprintln!(
-        "tessera [{chain}]: block {} ({} txs, gas {}/{})",
-        payload.number, payload.tx_count, payload.gas_used, payload.gas_limit
-    );
-    tx.send(payload).map_err(|_| ())
-}
+        // Cloning shares the underlying client, and lets blocks be delivered
+        // while later batches are still in flight.
+        let providers: Vec<P> = match self.endpoints.strategy() {
+            EndpointStrategy::RoundRobin => (0..self.endpoints.len())
+                .map(|offset| self.endpoints.nth_from_active(offset).clone())
+                .collect(),
+            _ => vec![self.endpoints.active().clone()],
+        };
+        let with_receipts = self.receipts && self.block_receipts_supported;
+        let mut batches = futures::stream::iter(batch_ranges(start, end, self.batch_size))
+            .enumerate()
+            .map(|(i, range)| {
+                let provider = &providers[i % providers.len()];
+                async move {
+                    let started = Instant::now();
+                    let result = fetch_batch::<N, P>(provider, range.clone(), with_receipts).await;
+                    (range, started.elapsed(), result)
+                }
+            })
+            .buffered(self.concurrency);
 
-fn block_to_payload(chain: Chain, block: &alloy::rpc::types::Block) -> BlockPayload {
-    let header = &block.header;
+        while let Some((range, latency, result)) = batches.next().await {
+            let entries = match result {
+                Ok(entries) => {
+                    self.status.record_success(latency);
+                    entries
+                }
+                Err(err) => {
+                    eprintln!(
+                        "tessera [{}]: batch {}..={} failed ({err}), fetching blocks individually",
+                        self.chain,
+                        range.start(),
+                        range.end()
+                    );
+                    self.status.record_retry(format!("batch: {err}"));
+                    for n in range {
+                        self.fetch_and_send(n).await?;
+                    }
+                    continue;
+                }
+            };
 
-    let transactions: Vec<TxPayload> = match &block.transactions {
-        BlockTransactions::Full(txs) => txs
-            .iter()
-            .enumerate()
-            .map(|(i, tx)| tx_to_payload(i, tx))
-            .collect(),
-        _ => Vec::new(),
-    };
+            for entry in entries {
+                let Ok(Some(block)) = entry.block else {
+                    self.fetch_and_send(entry.number).await?;
+                    continue;
+                };
+                let mut payload = block_to_payload(self.chain, &block);
+                if self.receipts && !payload.transactions.is_empty() {
+                    let attached = match entry.receipts {
+                        Some(Ok(Some(receipts))) => {
+                            apply_receipts(&mut payload, receipts);
+                            true
+                        }
+                        _ => self.attach_receipts(&mut payload).await,
+                    };
+                    if !attached {
+                        self.record_gap(entry.number);
+                        continue;
+                    }
+                }
+                self.attach_traces(&mut payload).await;
+                let payload = self.confirm(payload).await;
+                self.deliver(payload).await?;
+            }
+        }
+        Ok(())
+    }
 
-    BlockPayload {
-        chain,
-        number: header.number,
-        gas_used: header.gas_used,
-        gas_limit: header.gas_limit,
-        timestamp: header.timestamp,
-        tx_count: transactions.len() as u32,
-        base_fee_per_gas: header.base_fee_per_gas,
-        blob_gas_used: header.blob_gas_used,
-        transactions,
-        l1_origin_number: None,
+    async fn fetch_and_send(&mut self, number: u64) -> Result<(), ()> {
+        let Some(payload) = self.fetch_payload(number, self.backoff.max_attempts).await else {
+            self.record_gap(number);
+            return Ok(());
+        };
+        self.deliver(payload).await
     }
-}
 
-fn tx_to_payload(index: usize, tx: &alloy::rpc::types::Transaction) -> TxPayload {
-    use alloy::consensus::Transaction as TxConsensus;
-    use alloy::network::TransactionResponse;
+    async fn deliver(&mut self, payload: BlockPayload) -> Result<(), ()> {
+        if !self.hashes.extends(payload.number, payload.parent_hash) {
+            return self.send_reorged_branch(payload).await;
+        }
+        self.send(payload)
+    }
 
-    let blob_count = TxConsensus::blob_versioned_hashes(tx).map_or(0, |h| h.len());
+    /// Queues a block that couldn't be fetched for a later retry.
+    fn record_gap(&mut self, number: u64) {
+        if let Some(dropped) = self.gaps.record_failure(number, Instant::now(), jitter()) {
+            eprintln!(
+                "tessera [{}]: giving up on block {dropped}, too many pending gaps",
+                self.chain
+            );
+        }
+        self.status.set_pending_gaps(self.gaps.len());
+    }
+
+    /// Re-fetches queued gaps whose backoff has elapsed, one attempt each.
+    async fn retry_gaps(&mut self) -> Result<(), ()> {
+        for number in self.gaps.due(Instant::now()) {
+            let Some(mut payload) = self.fetch_payload(number, 1).await else {
+                self.record_gap(number);
+                continue;
+            };
+            eprintln!("tessera [{}]: filled gap at block {number}", self.chain);
+            self.gaps.resolve(number);
+            self.status.set_pending_gaps(self.gaps.len());
 
-    TxPayload {
-        hash: tx.tx_hash(),
-        tx_index: index,
-        gas: tx.gas_limit(),
-        gas_price: TxConsensus::gas_price(tx).unwrap_or(0),
-        value_eth: wei_to_eth(tx.value()),
-        from: TransactionResponse::from(tx),
-        to: tx.to(),
-        blob_count,
-        max_fee_per_blob_gas: TxConsensus::max_fee_per_blob_gas(tx),
-        op_stack_fees: None,
+            // A gap that turns out to sit on a different branch than its
+            // neighbours orphans the blocks already delivered after it, so
+            // queue those again.
+            let tip = self.hashes.latest();
+            let extends_parent = self.hashes.extends(number, payload.parent_hash);
+            let precedes_child = self.hashes.precedes(number, payload.hash);
+            if extends_parent && !precedes_child {
+                let depth = self.hashes.rewind(number + 1);
+                eprintln!(
+                    "tessera [{}]: reorg after block {number}, {depth} block(s) orphaned",
+                    self.chain
+                );
+                payload.reorg = Some(ReorgNotice { depth, fork: None });
+                self.send(payload)?;
+            } else {
+                self.deliver(payload).await?;
+            }
+            if let (false, Some(tip)) = (extends_parent && precedes_child, tip) {
+                for n in number + 1..=tip {
+                    self.record_gap(n);
+                }
+            }
+        }
+        Ok(())
     }
-}
 
-// ---------------------------------------------------------------------------
-// OP Stack (L2) fetcher
-// ---------------------------------------------------------------------------
+    /// Fetches a full block, making up to `attempts` attempts, and checks it
+    /// against the other endpoints under the quorum strategy.
+    async fn fetch_payload(&mut self, number: u64, attempts: u32) -> Option<BlockPayload> {
+        if self.endpoints.strategy() == EndpointStrategy::RoundRobin {
+            self.rotate_endpoint();
+        }
+        let payload = self.fetch_from_active(number, attempts).await?;
+        Some(self.confirm(payload).await)
+    }
 
-async fn op_stack_fetcher_loop(chain: Chain, rpc_url: Url, tx: Sender<BlockPayload>) {
-    use op_alloy::network::Optimism;
+    /// Fetches a full block from the active endpoint, failing over to the
+    /// next one between attempts.
+    async fn fetch_from_active(&mut self, number: u64, attempts: u32) -> Option<BlockPayload> {
+        let chain = self.chain;
+        let block = with_retries(
+            &self.endpoints,
+            &self.backoff,
+            &mut self.status,
+            &format!("block {number}"),
+            attempts,
+            |provider| async move {
+                // Load-balanced providers can briefly lag the head they announced,
+                // so a missing block is retried like any other failure.
+                provider
+                    .get_block_by_number(BlockNumberOrTag::Number(number))
+                    .full()
+                    .await?
+                    .ok_or_else(|| TransportErrorKind::custom_str("block not found"))
+            },
+        )
+        .await;
+        let mut payload = match block {
+            Ok(block) => block_to_payload(chain, &block),
+            Err(err) => {
+                eprintln!("tessera [{chain}]: failed to fetch block {number}: {err}");
+                return None;
+            }
+        };
 
-    // Use default() (no fillers) since we only read blocks, not send transactions.
-    // ProviderBuilder::new() adds recommended fillers that are incompatible with
-    // the OP Stack transaction request type.
-    use alloy::providers::Identity;
-    let builder: ProviderBuilder<Identity, Identity> = ProviderBuilder::default();
-    let provider = builder.network::<Optimism>().connect_http(rpc_url);
-
-    let latest = match provider.get_block_number().await {
-        Ok(n) => n,
-        Err(err) => {
-            eprintln!("tessera [{chain}]: failed to get latest block number: {err}");
-            return;
+        if self.receipts
+            && !payload.transactions.is_empty()
+            && !self.attach_receipts(&mut payload).await
+        {
+            return None;
+        }
+        self.attach_traces(&mut payload).await;
+        Some(payload)
+    }
+
+    /// Fills in receipt fields using `eth_getBlockReceipts`, falling back
+    /// to `eth_getTransactionReceipt` per transaction when the node lacks it.
+    /// Returns `false` when receipts failed to fetch, leaving the block to be
+    /// retried as a gap.
+    async fn attach_receipts(&mut self, payload: &mut BlockPayload) -> bool {
+        let chain = self.chain;
+        let mut receipts: Vec<RpcReceipt> = Vec::new();
+
+        if self.block_receipts_supported {
+            let number = BlockNumberOrTag::Number(payload.number);
+            match with_retries(
+                &self.endpoints,
+                &self.backoff,
+                &mut self.status,
+                "eth_getBlockReceipts",
+                self.backoff.max_attempts,
+                |provider| {
+                    provider.raw_request::<_, Option<Vec<RpcReceipt>>>(
+                        "eth_getBlockReceipts".into(),
+                        (number,),
+                    )
+                },
+            )
+            .await
+            {
+                Ok(Some(block_receipts)) => receipts = block_receipts,
+                Ok(None) => {
+                    eprintln!(
+                        "tessera [{chain}]: no receipts for block {} yet",
+                        payload.number
+                    );
+                    return false;
+                }
+                Err(err) if method_unsupported(&err) => {
+                    eprintln!(
+                        "tessera [{chain}]: eth_getBlockReceipts unavailable ({err}), \
+                         falling back to per-transaction receipts"
+                    );
+                    self.block_receipts_supported = false;
+                }
+                Err(err) => {
+                    eprintln!(
+                        "tessera [{chain}]: failed to fetch receipts for block {}: {err}",
+                        payload.number
+                    );
+                    return false;
+                }
+            }
         }
-    };
 
-    let start = latest.saturating_sub(BACKFILL_COUNT - 1);
-    eprintln!("tessera [{chain}]: backfilling blocks {start}..={latest}");
+        if !self.block_receipts_supported {
+            for tx in &payload.transactions {
+                let hash = tx.id;
+                match with_retries(
+                    &self.endpoints,
+                    &self.backoff,
+                    &mut self.status,
+                    "eth_getTransactionReceipt",
+                    self.backoff.max_attempts,
+                    |provider| {
+                        provider.raw_request::<_, Option<RpcReceipt>>(
+                            "eth_getTransactionReceipt".into(),
+                            (hash,),
+                        )
+                    },
+                )
+                .await
+                {
+                    Ok(Some(receipt)) => receipts.push(receipt),
+                    Ok(None) => {}
+                    Err(err) => {
+                        eprintln!(
+                            "tessera [{chain}]: failed to fetch receipt {}: {err}",
+                            tx.id
+                        );
+                        return false;
+                    }
+                }
+            }
+        }
 
-    for n in start..=latest {
-        if op_fetch_and_send(&provider, chain, n, &tx).await.is_err() {
+        apply_receipts(payload, receipts);
+        true
+    }
+
+    /// Records each transaction's call tree using `debug_traceBlockByNumber`
+    /// with the `callTracer`. Tracing stops for good on a node without the
+    /// method; any other failure leaves just this block untraced.
+    async fn attach_traces(&mut self, payload: &mut BlockPayload) {
+        if !self.traces || !self.traces_supported || payload.transactions.is_empty() {
             return;
         }
+        let chain = self.chain;
+        let number = BlockNumberOrTag::Number(payload.number);
+        let tracer = TracerOptions {
+            tracer: "callTracer",
+        };
+        match with_retries(
+            &self.endpoints,
+            &self.backoff,
+            &mut self.status,
+            "debug_traceBlockByNumber",
+            self.backoff.max_attempts,
+            |provider| {
+                provider.raw_request::<_, Vec<RpcTraceResult>>(
+                    "debug_traceBlockByNumber".into(),
+                    (number, tracer),
+                )
+            },
+        )
+        .await
+        {
+            Ok(results) => apply_traces(payload, results),
+            Err(err) if method_unsupported(&err) => {
+                eprintln!(
+                    "tessera [{chain}]: debug_traceBlockByNumber unavailable ({err}), \
+                     internal calls will not be traced"
+                );
+                self.traces_supported = false;
+            }
+            Err(err) => eprintln!(
+                "tessera [{chain}]: failed to trace block {}: {err}",
+                payload.number
+            ),
+        }
     }
 
-    eprintln!("tessera [{chain}]: backfill complete, polling for new blocks");
+    /// Under the quorum strategy, compares the block's hash with what the
+    /// other endpoints report for the same number. Disagreements are reported;
+    /// when a majority contradicts the active endpoint, the fetcher switches
+    /// to a majority endpoint and takes the block from there instead.
+    async fn confirm(&mut self, payload: BlockPayload) -> BlockPayload {
+        if self.endpoints.strategy() != EndpointStrategy::Quorum || self.endpoints.len() < 2 {
+            return payload;
+        }
+        let chain = self.chain;
+        let (number, hash) = (payload.number, payload.hash);
+        let active = self.endpoints.active_index();
+        let hashes: Vec<Option<B256>> =
+            futures::future::join_all(self.endpoints.iter().map(|(i, provider)| async move {
+                if i == active {
+                    return Some(hash);
+                }
+                match provider
+                    .get_block_by_number(BlockNumberOrTag::Number(number))
+                    .await
+                {
+                    Ok(Some(block)) => Some(block.header().hash()),
+                    _ => None,
+                }
+            }))
+            .await;
 
-    let mut last_seen = latest;
-    loop {
-        tokio::time::sleep(POLL_INTERVAL).await;
+        let outcome = quorum(&hashes, active);
+        if outcome.dissenters.is_empty() {
+            return payload;
+        }
+        let dissenters: Vec<String> = outcome
+            .dissenters
+            .iter()
+            .map(|&i| self.endpoints.label(i))
+            .collect();
+        eprintln!(
+            "tessera [{chain}]: endpoints disagree on block {number} ({} differ)",
+            dissenters.join(", ")
+        );
+        self.status
+            .record_disagreement(format!("block {number}: {}", dissenters.join(", ")));
 
-        let tip = match provider.get_block_number().await {
-            Ok(n) => n,
-            Err(err) => {
-                eprintln!("tessera [{chain}]: poll error: {err}");
-                continue;
-            }
+        let Some(majority) = outcome.majority.filter(|majority| *majority != hash) else {
+            return payload;
         };
+        let Some(index) = hashes.iter().position(|h| *h == Some(majority)) else {
+            return payload;
+        };
+        self.endpoints.select(index);
+        self.status.set_endpoint(self.endpoints.active_label());
+        eprintln!(
+            "tessera [{chain}]: switching to {}, which agrees with the majority",
+            self.endpoints.active_label()
+        );
+        match self
+            .fetch_from_active(number, self.backoff.max_attempts)
+            .await
+        {
+            Some(replacement) if replacement.hash == majority => replacement,
+            _ => payload,
+        }
+    }
 
-        for n in (last_seen + 1)..=tip {
-            if op_fetch_and_send(&provider, chain, n, &tx).await.is_err() {
-                return;
+    /// Handles a block whose parent doesn't match the tracked hash chain.
+    ///
+    /// Walks back along the new branch until it meets a tracked ancestor,
+    /// then re-sends the branch with a [`ReorgNotice`] on its first block.
+    async fn send_reorged_branch(&mut self, head: BlockPayload) -> Result<(), ()> {
+        let mut branch = vec![head];
+        loop {
+            let child = branch.last().expect("branch is never empty");
+            let Some(parent) = child.number.checked_sub(1) else {
+                break;
+            };
+            match self.hashes.hash_at(parent) {
+                Some(hash) if hash != child.parent_hash => {
+                    match self.fetch_payload(parent, self.backoff.max_attempts).await {
+                        Some(payload) => branch.push(payload),
+                        None => break,
+                    }
+                }
+                _ => break,
             }
         }
-        last_seen = tip;
+        branch.reverse();
+
+        let fork = branch[0].number;
+        let depth = self.hashes.rewind(fork);
+        self.gaps.rewind(fork);
+        if depth > 0 {
+            eprintln!(
+                "tessera [{}]: reorg at block {fork}, {depth} block(s) orphaned",
+                self.chain
+            );
+            branch[0].reorg = Some(ReorgNotice { depth, fork: None });
+        }
+
+        for payload in branch {
+            self.send(payload)?;
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, payload: BlockPayload) -> Result<(), ()> {
+        let chain = self.chain;
+        match payload.l1_origin_number {
+            Some(l1_origin) => eprintln!(
+                "tessera [{chain}]: block {} ({} txs, gas {}/{}, L1 origin: {l1_origin})",
+                payload.number, payload.tx_count, payload.gas_used, payload.gas_limit
+            ),
+            None => eprintln!(
+                "tessera [{chain}]: block {} ({} txs, gas {}/{})",
+                payload.number, payload.tx_count, payload.gas_used, payload.gas_limit
+            ),
+        }
+        self.hashes
+            .push(payload.number, payload.hash, payload.parent_hash);
+        self.status
+            .record_delivered(payload.number, self.gaps.len());
+        self.tx.send(payload).map_err(|_| ())
     }
 }
 
-async fn op_fetch_and_send(
-    provider: &impl Provider<op_alloy::network::Optimism>,
-    chain: Chain,
+/// One block's results from a JSON-RPC batch.
+struct BatchEntry<B> {
     number: u64,
-    tx: &Sender<BlockPayload>,
-) -> Result<(), ()> {
-    use alloy::consensus::Transaction as TxConsensus;
-    use alloy::network::TransactionResponse;
-
-    let block = match provider
-        .get_block_by_number(BlockNumberOrTag::Number(number))
-        .full()
-        .await
-    {
-        Ok(Some(block)) => block,
-        Ok(None) => {
-            eprintln!("tessera: block {number} not found");
-            return Ok(());
+    block: Result<Option<B>, TransportError>,
+    /// Present when receipts were requested in the same batch.
+    receipts: Option<Result<Option<Vec<RpcReceipt>>, TransportError>>,
+}
+
+/// Splits `start..=end` into consecutive ranges of at most `size` blocks.
+fn batch_ranges(start: u64, end: u64, size: usize) -> Vec<RangeInclusive<u64>> {
+    let size = size.max(1) as u64;
+    let mut ranges = Vec::new();
+    let mut first = start;
+    while first <= end {
+        let last = first.saturating_add(size - 1).min(end);
+        ranges.push(first..=last);
+        if last == u64::MAX {
+            break;
         }
-        Err(err) => {
-            eprintln!("tessera: failed to fetch block {number}: {err}");
-            return Ok(());
+        first = last + 1;
+    }
+    ranges
+}
+
+/// Requests every block in `range` (and its receipts, if asked) in a single
+/// JSON-RPC batch. The outer error covers the batch as a whole; each entry
+/// carries its own per-call result.
+async fn fetch_batch<N, P>(
+    provider: &P,
+    range: RangeInclusive<u64>,
+    with_receipts: bool,
+) -> Result<Vec<BatchEntry<N::BlockResponse>>, TransportError>
+where
+    N: Network,
+    P: Provider<N>,
+{
+    let mut batch = BatchRequest::new(provider.client());
+    let mut waiters = Vec::new();
+    for number in range {
+        let tag = BlockNumberOrTag::Number(number);
+        let block =
+            batch.add_call::<_, Option<N::BlockResponse>>("eth_getBlockByNumber", &(tag, true))?;
+        let receipts = if with_receipts {
+            Some(batch.add_call::<_, Option<Vec<RpcReceipt>>>("eth_getBlockReceipts", &(tag,))?)
+        } else {
+            None
+        };
+        waiters.push((number, block, receipts));
+    }
+    batch.send().await?;
+
+    let mut entries = Vec::with_capacity(waiters.len());
+    for (number, block, receipts) in waiters {
+        let receipts = match receipts {
+            Some(waiter) => Some(waiter.await),
+            None => None,
+        };
+        entries.push(BatchEntry {
+            number,
+            block: block.await,
+            receipts,
+        });
+    }
+    Ok(entries)
+}
+
+/// Fills in receipt fields and OP Stack fees by matching receipts to
+/// transactions by hash.
+fn apply_receipts(payload: &mut BlockPayload, receipts: Vec<RpcReceipt>) {
+    let by_hash: HashMap<B256, RpcReceipt> = receipts
+        .into_iter()
+        .map(|r| (r.transaction_hash, r))
+        .collect();
+    for tx in &mut payload.transactions {
+        if let Some(receipt) = by_hash.get(&tx.id) {
+            receipt.apply_to(tx);
         }
-    };
+    }
+}
 
-    let header = &block.header;
-    let l1_origin = extract_l1_origin(block.transactions.as_transactions());
+/// Tracer selection for `debug_trace*` calls.
+#[derive(Clone, Copy, Debug, Serialize)]
+struct TracerOptions {
+    tracer: &'static str,
+}
 
-    let transactions: Vec<TxPayload> = match &block.transactions {
+/// Attaches call trees to transactions, by hash when the node reports one and
+/// by position otherwise.
+fn apply_traces(payload: &mut BlockPayload, results: Vec<RpcTraceResult>) {
+    for (position, result) in results.into_iter().enumerate() {
+        let Some(frame) = result.result else {
+            continue;
+        };
+        let tx = match result.tx_hash {
+            Some(hash) => payload.transactions.iter_mut().find(|tx| tx.id == hash),
+            None => payload.transactions.get_mut(position),
+        };
+        if let Some(tx) = tx {
+            tx.evm.get_or_insert_with(EvmAddendum::default).trace = Some(frame.into_frame());
+        }
+    }
+}
+
+/// Receipt fields shared by L1 and OP Stack receipts, read from raw JSON so one
+/// shape serves both networks.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcReceipt {
+    transaction_hash: B256,
+    #[serde(with = "alloy::serde::quantity")]
+    gas_used: u64,
+    /// Absent on pre-Byzantium receipts, which carry a state root instead.
+    #[serde(default, with = "alloy::serde::quantity::opt")]
+    status: Option<u64>,
+    #[serde(default, with = "alloy::serde::quantity::opt")]
+    effective_gas_price: Option<u128>,
+    #[serde(default)]
+    logs: Vec<Log>,
+    contract_address: Option<Address>,
+    // OP Stack L1 fee fields (absent on L1 receipts and deposit transactions).
+    #[serde(default, with = "alloy::serde::quantity::opt")]
+    l1_fee: Option<u128>,
+    #[serde(default, with = "alloy::serde::quantity::opt")]
+    l1_gas_price: Option<u128>,
+    #[serde(default, with = "alloy::serde::quantity::opt")]
+    l1_blob_base_fee: Option<u128>,
+    #[serde(default, with = "alloy::serde::quantity::opt")]
+    l1_gas_used: Option<u128>,
+    #[serde(default, with = "alloy::serde::quantity::opt")]
+    l1_base_fee_scalar: Option<u64>,
+    #[serde(default, with = "alloy::serde::quantity::opt")]
+    l1_blob_base_fee_scalar: Option<u64>,
+}
+
+impl RpcReceipt {
+    /// Records gas used, status and fees on the envelope. The fee paid adds
+    /// the OP Stack L1 data fee to gas used × effective gas price.
+    fn apply_to(&self, tx: &mut TransactionEnvelope) {
+        let effective_gas_price = self.effective_gas_price.unwrap_or(tx.price);
+        let op_stack = self.op_stack_fees();
+        let l1_fee = op_stack.as_ref().map_or(0, |fees| fees.l1_fee);
+
+        tx.resource_used = Some(self.gas_used);
+        tx.success = Some(self.status.is_none_or(|status| status == 1));
+        tx.fee_paid = Some(
+            (self.gas_used as u128)
+                .saturating_mul(effective_gas_price)
+                .saturating_add(l1_fee),
+        );
+        tx.op_stack = op_stack;
+        let evm = tx.evm.get_or_insert_with(EvmAddendum::default);
+        evm.effective_gas_price = Some(effective_gas_price);
+        evm.logs_count = Some(self.logs.len());
+        evm.contract_address = self.contract_address.or(evm.contract_address);
+        evm.token_transfers = self
+            .logs
+            .iter()
+            .filter_map(|log| {
+                TokenTransfer::from_log(log.address(), log.topics(), &log.data().data)
+            })
+            .collect();
+    }
+
+    fn op_stack_fees(&self) -> Option<OpStackFees> {
+        Some(OpStackFees {
+            l1_fee: self.l1_fee?,
+            l1_gas_price: self.l1_gas_price,
+            l1_blob_base_fee: self.l1_blob_base_fee,
+            l1_gas_used: self.l1_gas_used,
+            l1_base_fee_scalar: self.l1_base_fee_scalar,
+            l1_blob_base_fee_scalar: self.l1_blob_base_fee_scalar,
+        })
+    }
+}
+
+fn block_to_payload<B>(chain: Chain, block: &B) -> BlockPayload
+where
+    B: BlockResponse,
+    B::Header: HeaderResponse,
+    B::Transaction: TxConsensus,
+{
+    let header = block.header();
+
+    let transactions: Vec<TransactionEnvelope> = match block.transactions() {
         BlockTransactions::Full(txs) => txs
             .iter()
             .enumerate()
-            .map(|(i, op_tx)| {
-                let blob_count = TxConsensus::blob_versioned_hashes(op_tx).map_or(0, |h| h.len());
-                TxPayload {
-                    hash: op_tx.tx_hash(),
-                    tx_index: i,
-                    gas: op_tx.gas_limit(),
-                    gas_price: TxConsensus::gas_price(op_tx).unwrap_or(0),
-                    value_eth: wei_to_eth(op_tx.value()),
-                    from: TransactionResponse::from(op_tx),
-                    to: op_tx.to(),
-                    blob_count,
-                    max_fee_per_blob_gas: TxConsensus::max_fee_per_blob_gas(op_tx),
-                    op_stack_fees: None,
-                }
-            })
+            .map(|(i, tx)| tx_to_envelope(i, tx))
             .collect(),
         _ => Vec::new(),
     };
 
-    let payload = BlockPayload {
+    // Only OP Stack blocks open with an L1 attributes deposit.
+    let l1_origin_number = if crate::data::is_op_stack(&chain) {
+        extract_l1_origin(block.transactions().as_transactions())
+    } else {
+        None
+    };
+
+    BlockPayload {
         chain,
-        number: header.number,
-        gas_used: header.gas_used,
-        gas_limit: header.gas_limit,
-        timestamp: header.timestamp,
+        number: header.number(),
+        hash: header.hash(),
+        parent_hash: header.parent_hash(),
+        gas_used: header.gas_used(),
+        gas_limit: header.gas_limit(),
+        timestamp: header.timestamp(),
         tx_count: transactions.len() as u32,
-        base_fee_per_gas: header.base_fee_per_gas,
-        blob_gas_used: header.blob_gas_used,
+        base_fee_per_gas: header.base_fee_per_gas(),
+        blob_gas_used: header.blob_gas_used(),
+        fee_recipient: Some(header.beneficiary()),
+        extra_data: header.extra_data().clone(),
         transactions,
-        l1_origin_number: l1_origin,
-    };
+        l1_origin_number,
+        reorg: None,
+    }
+}
 
-    eprintln!(
-        "tessera [{chain}]: block {} ({} txs, gas {}/{}, L1 origin: {:?})",
-        payload.number, payload.tx_count, payload.gas_used, payload.gas_limit, l1_origin
-    );
-    tx.send(payload).map_err(|_| ())
+/// Maps an EVM transaction into the envelope: gas is the resource, wei per
+/// gas the price. Receipt fields are filled in later by `apply_receipts`.
+pub(crate) fn tx_to_envelope<T>(index: usize, tx: &T) -> TransactionEnvelope
+where
+    T: TxConsensus + TransactionResponse,
+{
+    let blob_count = TxConsensus::blob_versioned_hashes(tx).map_or(0, |h| h.len());
+    let input = TxConsensus::input(tx);
+    let selector = input.get(..4).map(Selector::from_slice);
+    let args = input
+        .get(4..input.len().min(4 + MAX_ARG_WORDS * 32))
+        .map_or_else(Bytes::new, Bytes::copy_from_slice);
+    let access_list = TxConsensus::access_list(tx);
+    let sender = TransactionResponse::from(tx);
+    let nonce = TxConsensus::nonce(tx);
+    // Receipts confirm it later; derive it now so creations without one
+    // still name their contract.
+    let contract_address = TxConsensus::kind(tx)
+        .is_create()
+        .then(|| sender.create(nonce));
+
+    TransactionEnvelope {
+        id: tx.tx_hash(),
+        index,
+        resource_limit: tx.gas_limit(),
+        resource_used: None,
+        price: TxConsensus::gas_price(tx).unwrap_or(0),
+        fee_paid: None,
+        value: tx.value(),
+        sender,
+        recipient: TxConsensus::to(tx),
+        success: None,
+        evm: Some(EvmAddendum {
+            nonce: Some(nonce),
+            selector,
+            args,
+            tx_type: Some(tx.ty()),
+            max_fee_per_gas: TxConsensus::is_dynamic_fee(tx)
+                .then(|| TxConsensus::max_fee_per_gas(tx)),
+            max_priority_fee_per_gas: TxConsensus::max_priority_fee_per_gas(tx),
+            access_list_addresses: access_list.map_or(0, |list| list.len()),
+            access_list_keys: access_list.map_or(0, |list| {
+                list.iter().map(|item| item.storage_keys.len()).sum()
+            }),
+            authorization_count: TxConsensus::authorization_list(tx).map_or(0, <[_]>::len),
+            blob_count,
+            max_fee_per_blob_gas: TxConsensus::max_fee_per_blob_gas(tx),
+            contract_address,
+            ..Default::default()
+        }),
+        op_stack: None,
+        solana: None,
+    }
 }
 
 /// Extracts the L1 block number from the first deposit transaction's calldata.
@@ -316,26 +1167,440 @@ fn extract_l1_origin<T: alloy::consensus::Transaction>(txs: Option<&[T]>) -> Opt
     Some(u64::from_be_bytes(bytes))
 }
 
-fn wei_to_eth(wei: alloy::primitives::U256) -> f64 {
-    let wei_u128: u128 = wei.try_into().unwrap_or(u128::MAX);
-    wei_u128 as f64 / 1e18
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use alloy::primitives::U256;
+    use alloy::rpc::client::RpcClient;
+    use alloy::rpc::json_rpc::{
+        RequestPacket, Response, ResponsePacket, ResponsePayload, SerializedRequest,
+    };
+
+    #[test]
+    fn batch_ranges_splits_into_ordered_chunks() {
+        assert_eq!(batch_ranges(10, 34, 10), vec![10..=19, 20..=29, 30..=34]);
+        assert_eq!(batch_ranges(5, 5, 10), vec![5..=5]);
+        assert_eq!(batch_ranges(0, 2, 1), vec![0..=0, 1..=1, 2..=2]);
+        assert!(batch_ranges(6, 5, 10).is_empty());
+        assert_eq!(
+            batch_ranges(u64::MAX - 1, u64::MAX, 4),
+            vec![u64::MAX - 1..=u64::MAX]
+        );
+    }
+
+    /// Answers every JSON-RPC batch with synthetic blocks and receipts, later
+    /// batches first: each batch waits less the higher its first block.
+    #[derive(Clone, Default)]
+    struct ShuffledTransport {
+        answered: std::sync::Arc<std::sync::Mutex<Vec<u64>>>,
+    }
+
+    impl ShuffledTransport {
+        fn block(number: u64) -> serde_json::Value {
+            serde_json::json!({
+                "hash": B256::with_last_byte(number as u8),
+                "parentHash": B256::with_last_byte(number as u8 - 1),
+                "sha3Uncles": B256::ZERO,
+                "miner": Address::ZERO,
+                "stateRoot": B256::ZERO,
+                "transactionsRoot": B256::ZERO,
+                "receiptsRoot": B256::ZERO,
+                "logsBloom": alloy::primitives::Bloom::ZERO,
+                "difficulty": "0x0",
+                "number": format!("{number:#x}"),
+                "gasLimit": "0x1c9c380",
+                "gasUsed": "0x5208",
+                "timestamp": format!("{:#x}", 1_700_000_000 + number * 12),
+                "extraData": "0x",
+                "mixHash": B256::ZERO,
+                "nonce": "0x0000000000000000",
+                "uncles": [],
+                "transactions": [{
+                    "type": "0x0",
+                    "nonce": format!("{number:#x}"),
+                    "gas": "0x5208",
+                    "gasPrice": "0x3b9aca00",
+                    "to": "0x00000000000000000000000000000000000000aa",
+                    "value": "0x0",
+                    "input": "0x",
+                    "v": "0x1b",
+                    "r": "0x1",
+                    "s": "0x1",
+                    "hash": Self::tx_hash(number),
+                    "from": "0x00000000000000000000000000000000000000dd",
+                    "blockHash": B256::with_last_byte(number as u8),
+                    "blockNumber": format!("{number:#x}"),
+                    "transactionIndex": "0x0"
+                }]
+            })
+        }
+
+        fn receipts(number: u64) -> serde_json::Value {
+            serde_json::json!([{
+                "transactionHash": Self::tx_hash(number),
+                "gasUsed": "0x5208",
+                "status": "0x1",
+                "logs": []
+            }])
+        }
+
+        fn tx_hash(number: u64) -> B256 {
+            B256::left_padding_from(&number.to_be_bytes())
+        }
+    }
+
+    impl tower::Service<RequestPacket> for ShuffledTransport {
+        type Response = ResponsePacket;
+        type Error = TransportError;
+        type Future = alloy::transports::TransportFut<'static>;
+
+        fn poll_ready(
+            &mut self,
+            _: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<Result<(), TransportError>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: RequestPacket) -> Self::Future {
+            let answered = self.answered.clone();
+            Box::pin(async move {
+                let RequestPacket::Batch(calls) = request else {
+                    panic!("backfill should only send batches");
+                };
+                let number = |call: &SerializedRequest| -> u64 {
+                    let params: serde_json::Value =
+                        serde_json::from_str(call.params().unwrap().get()).unwrap();
+                    let tag = params[0].as_str().unwrap().trim_start_matches("0x");
+                    u64::from_str_radix(tag, 16).unwrap()
+                };
+                let first = number(&calls[0]);
+                tokio::time::sleep(Duration::from_millis(100u64.saturating_sub(first * 10))).await;
+                answered.lock().unwrap().push(first);
+                let responses = calls
+                    .iter()
+                    .map(|call| {
+                        let result = match call.method() {
+                            "eth_getBlockByNumber" => Self::block(number(call)),
+                            "eth_getBlockReceipts" => Self::receipts(number(call)),
+                            other => panic!("unexpected method {other}"),
+                        };
+                        Response {
+                            id: call.id().clone(),
+                            payload: ResponsePayload::Success(
+                                serde_json::value::to_raw_value(&result).unwrap(),
+                            ),
+                        }
+                    })
+                    .collect();
+                Ok(ResponsePacket::Batch(responses))
+            })
+        }
+    }
+
+    #[test]
+    fn concurrent_backfill_delivers_in_order_with_receipts() {
+        let transport = ShuffledTransport::default();
+        let answered = transport.answered.clone();
+        let builder: ProviderBuilder<Identity, Identity> = ProviderBuilder::default();
+        let provider = builder
+            .network::<Ethereum>()
+            .connect_client(RpcClient::new(transport, true));
+        let url: Url = "http://mock.invalid".parse().unwrap();
+        let endpoints = Endpoints::new(vec![(url, provider)], EndpointStrategy::Failover);
+        let chain = Chain::mainnet();
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let (status_tx, _status_rx) = crossbeam_channel::unbounded();
+        let mut fetcher =
+            BlockFetcher::new(endpoints, chain, tx, StatusReporter::new(chain, status_tx));
+        fetcher.receipts = true;
+        fetcher.batch_size = 2;
+        fetcher.concurrency = 4;
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        rt.block_on(fetcher.fetch_range(1, 8)).unwrap();
+        drop(fetcher);
+
+        // The batches really did come back out of order.
+        let answered = answered.lock().unwrap().clone();
+        assert_eq!(answered.len(), 4);
+        assert!(
+            !answered.is_sorted(),
+            "batches answered in order: {answered:?}"
+        );
+
+        let payloads: Vec<BlockPayload> = rx.iter().collect();
+        let numbers: Vec<u64> = payloads.iter().map(|p| p.number).collect();
+        assert_eq!(numbers, (1..=8).collect::<Vec<_>>());
+        for payload in &payloads {
+            let tx = &payload.transactions[0];
+            assert_eq!(tx.resource_used, Some(21_000));
+            assert_eq!(tx.success, Some(true));
+        }
+    }
+
+    fn search(timestamp: u64, timestamps: &[u64]) -> Option<u64> {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let latest = timestamps.len() as u64 - 1;
+        rt.block_on(search_block_by_timestamp(timestamp, latest, |n| {
+            std::future::ready(timestamps.get(n as usize).copied())
+        }))
+    }
 
     #[test]
-    fn wei_to_eth_converts_1_eth() {
-        let wei = U256::from(1_000_000_000_000_000_000u128);
-        let eth = wei_to_eth(wei);
-        assert!((eth - 1.0).abs() < f64::EPSILON);
+    fn timestamp_search_finds_first_block_at_or_after() {
+        let timestamps = [100, 112, 124, 136, 148, 160];
+        assert_eq!(search(124, &timestamps), Some(2));
+        assert_eq!(search(125, &timestamps), Some(3));
+        assert_eq!(search(0, &timestamps), Some(0));
+        assert_eq!(search(1_000, &timestamps), Some(5));
     }
 
     #[test]
-    fn wei_to_eth_handles_zero() {
-        let eth = wei_to_eth(U256::ZERO);
-        assert_eq!(eth, 0.0);
+    fn timestamp_search_fails_when_lookup_fails() {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let result = rt.block_on(search_block_by_timestamp(50, 10, |_| {
+            std::future::ready(None)
+        }));
+        assert_eq!(result, None);
+    }
+
+    fn envelope_with_price(price: u128) -> TransactionEnvelope {
+        TransactionEnvelope {
+            price,
+            ..TransactionEnvelope::for_test(0)
+        }
+    }
+
+    #[test]
+    fn rpc_receipt_parses_status_and_creation() {
+        let json = r#"{
+            "transactionHash": "0x1111111111111111111111111111111111111111111111111111111111111111",
+            "gasUsed": "0x5208",
+            "status": "0x0",
+            "effectiveGasPrice": "0x3b9aca00",
+            "logs": [],
+            "contractAddress": "0x5fbdb2315678afecb367f032d93f642f64180aa3"
+        }"#;
+        let receipt: RpcReceipt = serde_json::from_str(json).expect("parse receipt");
+        let mut tx = envelope_with_price(0);
+        receipt.apply_to(&mut tx);
+
+        assert_eq!(tx.resource_used, Some(21_000));
+        assert!(tx.reverted());
+        assert_eq!(tx.fee_paid, Some(21_000_000_000_000));
+        let evm = tx.evm.expect("EVM addendum");
+        assert_eq!(evm.effective_gas_price, Some(1_000_000_000));
+        assert_eq!(evm.logs_count, Some(0));
+        assert!(evm.contract_address.is_some());
+    }
+
+    #[test]
+    fn rpc_receipt_decodes_token_transfers() {
+        let json = r#"{
+            "transactionHash": "0x1111111111111111111111111111111111111111111111111111111111111111",
+            "gasUsed": "0xc350",
+            "status": "0x1",
+            "logs": [{
+                "address": "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+                "topics": [
+                    "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+                    "0x00000000000000000000000000000000000000000000000000000000000000aa",
+                    "0x00000000000000000000000000000000000000000000000000000000000000bb"
+                ],
+                "data": "0x00000000000000000000000000000000000000000000000000000000000f4240",
+                "blockNumber": "0x1",
+                "transactionIndex": "0x0",
+                "logIndex": "0x0",
+                "removed": false
+            }, {
+                "address": "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+                "topics": ["0x8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925"],
+                "data": "0x",
+                "blockNumber": "0x1",
+                "transactionIndex": "0x0",
+                "logIndex": "0x1",
+                "removed": false
+            }],
+            "contractAddress": null
+        }"#;
+        let receipt: RpcReceipt = serde_json::from_str(json).expect("parse receipt");
+        let mut tx = envelope_with_price(1);
+        receipt.apply_to(&mut tx);
+
+        let transfers = tx.token_transfers();
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].to, Address::with_last_byte(0xbb));
+        assert_eq!(
+            transfers[0].amount,
+            alloy::primitives::U256::from(1_000_000)
+        );
+        assert_eq!(tx.evm.unwrap().logs_count, Some(2));
+    }
+
+    #[test]
+    fn rpc_receipt_without_status_counts_as_success() {
+        let json = r#"{
+            "transactionHash": "0x1111111111111111111111111111111111111111111111111111111111111111",
+            "gasUsed": "0x5208",
+            "root": "0x2222222222222222222222222222222222222222222222222222222222222222",
+            "contractAddress": null
+        }"#;
+        let receipt: RpcReceipt = serde_json::from_str(json).expect("parse receipt");
+        let mut tx = envelope_with_price(7);
+        receipt.apply_to(&mut tx);
+
+        assert!(receipt.op_stack_fees().is_none());
+        assert_eq!(tx.success, Some(true));
+        assert_eq!(tx.evm.unwrap().effective_gas_price, Some(7));
+    }
+
+    #[test]
+    fn rpc_receipt_reads_op_stack_fees() {
+        let json = r#"{
+            "transactionHash": "0x1111111111111111111111111111111111111111111111111111111111111111",
+            "gasUsed": "0x5208",
+            "status": "0x1",
+            "effectiveGasPrice": "0xf4240",
+            "logs": [],
+            "contractAddress": null,
+            "l1Fee": "0x1c6bf52634000",
+            "l1GasPrice": "0x3b9aca00",
+            "l1GasUsed": "0x640",
+            "l1BlobBaseFee": "0x1",
+            "l1BaseFeeScalar": "0x558",
+            "l1BlobBaseFeeScalar": "0xc5fc5",
+            "l1FeeScalar": "0.684"
+        }"#;
+        let receipt: RpcReceipt = serde_json::from_str(json).expect("parse receipt");
+        let fees = receipt.op_stack_fees().expect("OP fees");
+        let mut tx = envelope_with_price(0);
+        receipt.apply_to(&mut tx);
+        assert_eq!(tx.execution_fee(), Some(21_000_000_000));
+
+        assert_eq!(fees.l1_fee, 500_000_000_000_000);
+        assert_eq!(fees.l1_gas_price, Some(1_000_000_000));
+        assert_eq!(fees.l1_gas_used, Some(1_600));
+        assert_eq!(fees.l1_base_fee_scalar, Some(1_368));
+        assert_eq!(fees.l1_blob_base_fee_scalar, Some(810_949));
+    }
+
+    #[test]
+    fn envelope_records_type_and_fee_market_fields() {
+        let tx: alloy::rpc::types::Transaction = serde_json::from_value(serde_json::json!({
+            "type": "0x2",
+            "chainId": "0x1",
+            "nonce": "0x7",
+            "gas": "0x5208",
+            "maxFeePerGas": "0x77359400",
+            "maxPriorityFeePerGas": "0x3b9aca00",
+            "to": "0x00000000000000000000000000000000000000aa",
+            "value": "0x0",
+            "input": "0x",
+            "accessList": [
+                {
+                    "address": "0x00000000000000000000000000000000000000bb",
+                    "storageKeys": [
+                        "0x0000000000000000000000000000000000000000000000000000000000000001",
+                        "0x0000000000000000000000000000000000000000000000000000000000000002"
+                    ]
+                },
+                { "address": "0x00000000000000000000000000000000000000cc", "storageKeys": [] }
+            ],
+            "yParity": "0x0",
+            "v": "0x0",
+            "r": "0x1",
+            "s": "0x1",
+            "hash": format!("{}", B256::repeat_byte(0x42)),
+            "from": "0x00000000000000000000000000000000000000dd",
+            "blockHash": null,
+            "blockNumber": null,
+            "transactionIndex": null
+        }))
+        .expect("deserialize");
+
+        let envelope = tx_to_envelope(0, &tx);
+        let evm = envelope.evm.as_ref().unwrap();
+        assert_eq!(envelope.tx_type(), Some(crate::data::TxType::DynamicFee));
+        assert_eq!(evm.max_fee_per_gas, Some(2_000_000_000));
+        assert_eq!(evm.max_priority_fee_per_gas, Some(1_000_000_000));
+        assert_eq!((evm.access_list_addresses, evm.access_list_keys), (2, 2));
+        assert_eq!(evm.authorization_count, 0);
+    }
+
+    #[test]
+    fn creation_derives_contract_address() {
+        let tx: alloy::rpc::types::Transaction = serde_json::from_value(serde_json::json!({
+            "type": "0x0",
+            "nonce": "0x0",
+            "gas": "0x100000",
+            "gasPrice": "0x3b9aca00",
+            "to": null,
+            "value": "0x0",
+            "input": "0x6080",
+            "v": "0x1b",
+            "r": "0x1",
+            "s": "0x1",
+            "hash": format!("{}", B256::repeat_byte(0x43)),
+            "from": "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266",
+            "blockHash": null,
+            "blockNumber": null,
+            "transactionIndex": null
+        }))
+        .expect("deserialize");
+
+        let envelope = tx_to_envelope(0, &tx);
+        assert_eq!(envelope.tx_type(), Some(crate::data::TxType::Legacy));
+        assert_eq!(
+            envelope.deployed_contract(),
+            Some(address!("5fbdb2315678afecb367f032d93f642f64180aa3"))
+        );
+    }
+
+    #[test]
+    fn traces_without_hashes_attach_by_position() {
+        let json = r#"[
+            {"result": {"type": "CALL", "from": "0x00000000000000000000000000000000000000aa", "input": "0x"}},
+            {"error": "execution timeout"}
+        ]"#;
+        let results: Vec<RpcTraceResult> = serde_json::from_str(json).expect("parse traces");
+        let mut payload = BlockPayload {
+            chain: Chain::mainnet(),
+            number: 1,
+            hash: B256::ZERO,
+            parent_hash: B256::ZERO,
+            gas_used: 0,
+            gas_limit: 0,
+            timestamp: 0,
+            tx_count: 2,
+            base_fee_per_gas: None,
+            blob_gas_used: None,
+            fee_recipient: None,
+            extra_data: Bytes::new(),
+            transactions: vec![envelope_with_price(0), envelope_with_price(0)],
+            l1_origin_number: None,
+            reorg: None,
+        };
+        apply_traces(&mut payload, results);
+
+        let root = payload.transactions[0].trace().expect("first tx traced");
+        assert_eq!(root.from, Address::with_last_byte(0xaa));
+        assert!(payload.transactions[1].trace().is_none());
+    }
+
+    #[test]
+    fn pubsub_url_detects_websocket_schemes() {
+        let ws: Url = "ws://127.0.0.1:8546".parse().unwrap();
+        let wss: Url = "wss://base-mainnet.example.com/v2/key".parse().unwrap();
+        let http: Url = "http://127.0.0.1:8545".parse().unwrap();
+
+        assert!(is_pubsub_url(&ws));
+        assert!(is_pubsub_url(&wss));
+        assert!(!is_pubsub_url(&http));
     }
 }
@@ -0,0 +1,411 @@
+//! Fixture files: JSON arrays, JSON Lines recordings, and the compact binary
+//! format.
+//!
+//! A binary fixture starts with the magic bytes [`FIXTURE_MAGIC`], a
+//! little-endian `u16` schema version and a `u32` header length. The header
+//! (CBOR) lists each chain's block range so tools can describe a fixture
+//! without decoding it. The payloads follow as a zstd-compressed stream of
+//! CBOR values. [`read_fixture`] tells the formats apart by their first
+//! bytes; writers pick one from the file extension.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use alloy_chains::Chain;
+use serde::{Deserialize, Serialize};
+
+use crate::data::model::BlockPayload;
+
+/// First bytes of every binary fixture.
+pub const FIXTURE_MAGIC: &[u8; 8] = b"TESSFIX\0";
+/// Binary fixture schema version written by this build.
+pub const FIXTURE_VERSION: u16 = 1;
+/// Extension that selects the binary format when writing.
+pub const BINARY_EXTENSION: &str = "tfix";
+/// zstd level for binary fixtures: most of the gain of higher levels at a
+/// fraction of their time.
+const ZSTD_LEVEL: i32 = 9;
+
+/// On-disk fixture formats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FixtureFormat {
+    /// A pretty-printed JSON array, convenient to edit by hand.
+    Json,
+    /// One JSON payload per line, as written by the recorder.
+    JsonLines,
+    /// Versioned header plus zstd-compressed CBOR.
+    Binary,
+}
+
+impl FixtureFormat {
+    /// Format for a path to write: `.tfix` is binary, `.jsonl`/`.ndjson` is
+    /// JSON Lines and anything else a JSON array.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(BINARY_EXTENSION) => Self::Binary,
+            Some("jsonl" | "ndjson") => Self::JsonLines,
+            _ => Self::Json,
+        }
+    }
+}
+
+/// Blocks of one chain in a fixture.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainRange {
+    pub chain: Chain,
+    pub first: u64,
+    pub last: u64,
+    pub count: u64,
+}
+
+/// What a fixture holds: its schema version, and the chains and block
+/// ranges it covers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FixtureHeader {
+    pub version: u16,
+    pub chains: Vec<ChainRange>,
+    pub blocks: u64,
+}
+
+/// The CBOR part of the binary header; the version sits before it.
+#[derive(Serialize, Deserialize)]
+struct HeaderBody {
+    chains: Vec<ChainRange>,
+    blocks: u64,
+}
+
+impl FixtureHeader {
+    /// Describes a list of payloads, chains in order of first appearance.
+    pub fn describe(payloads: &[BlockPayload]) -> Self {
+        let mut chains: Vec<ChainRange> = Vec::new();
+        for payload in payloads {
+            match chains.iter_mut().find(|range| range.chain == payload.chain) {
+                Some(range) => {
+                    range.first = range.first.min(payload.number);
+                    range.last = range.last.max(payload.number);
+                    range.count += 1;
+                }
+                None => chains.push(ChainRange {
+                    chain: payload.chain,
+                    first: payload.number,
+                    last: payload.number,
+                    count: 1,
+                }),
+            }
+        }
+        Self {
+            version: FIXTURE_VERSION,
+            chains,
+            blocks: payloads.len() as u64,
+        }
+    }
+}
+
+/// Reads a fixture in any format. JSON Lines and binary fixtures are
+/// streamed. A JSON line that fails to parse (such as one cut short by a
+/// crash) is skipped with a warning.
+pub fn read_fixture(path: &Path) -> Result<Box<dyn Iterator<Item = BlockPayload> + Send>, String> {
+    let mut reader = BufReader::new(File::open(path).map_err(|e| e.to_string())?);
+    if reader
+        .fill_buf()
+        .map_err(|e| e.to_string())?
+        .starts_with(FIXTURE_MAGIC)
+    {
+        return read_binary(reader, path);
+    }
+
+    let is_array = loop {
+        let buf = reader.fill_buf().map_err(|e| e.to_string())?;
+        let Some(skip) = buf.iter().position(|b| !b.is_ascii_whitespace()) else {
+            if buf.is_empty() {
+                break false;
+            }
+            let len = buf.len();
+            reader.consume(len);
+            continue;
+        };
+        let first = buf[skip];
+        reader.consume(skip);
+        break first == b'[';
+    };
+
+    if is_array {
+        let mut json = String::new();
+        reader
+            .read_to_string(&mut json)
+            .map_err(|e| e.to_string())?;
+        let payloads: Vec<BlockPayload> = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+        return Ok(Box::new(payloads.into_iter()));
+    }
+
+    let path = path.to_path_buf();
+    let lines = reader.lines().enumerate().filter_map(move |(i, line)| {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                eprintln!("tessera: failed to read {}: {e}", path.display());
+                return None;
+            }
+        };
+        if line.trim().is_empty() {
+            return None;
+        }
+        serde_json::from_str(&line)
+            .map_err(|e| {
+                eprintln!(
+                    "tessera: skipping line {} of {}: {e}",
+                    i + 1,
+                    path.display()
+                )
+            })
+            .ok()
+    });
+    Ok(Box::new(lines))
+}
+
+/// Reads the version and header that open a binary fixture.
+fn read_binary_header(reader: &mut impl Read) -> Result<FixtureHeader, String> {
+    let mut prefix = [0u8; 14];
+    reader
+        .read_exact(&mut prefix)
+        .map_err(|e| format!("truncated fixture header: {e}"))?;
+    if &prefix[..8] != FIXTURE_MAGIC {
+        return Err("not a binary fixture".to_string());
+    }
+    let version = u16::from_le_bytes([prefix[8], prefix[9]]);
+    if version > FIXTURE_VERSION {
+        return Err(format!(
+            "fixture version {version} is newer than this build supports ({FIXTURE_VERSION})"
+        ));
+    }
+    let len = u32::from_le_bytes([prefix[10], prefix[11], prefix[12], prefix[13]]);
+    let mut body = vec![0u8; len as usize];
+    reader
+        .read_exact(&mut body)
+        .map_err(|e| format!("truncated fixture header: {e}"))?;
+    let body: HeaderBody = ciborium::from_reader(body.as_slice()).map_err(|e| e.to_string())?;
+    Ok(FixtureHeader {
+        version,
+        chains: body.chains,
+        blocks: body.blocks,
+    })
+}
+
+fn read_binary(
+    mut reader: BufReader<File>,
+    path: &Path,
+) -> Result<Box<dyn Iterator<Item = BlockPayload> + Send>, String> {
+    let header = read_binary_header(&mut reader)?;
+    let mut decoder = zstd::Decoder::with_buffer(reader).map_err(|e| e.to_string())?;
+    let path = path.to_path_buf();
+    let mut failed = false;
+    let payloads = (0..header.blocks).map_while(move |i| {
+        if failed {
+            return None;
+        }
+        match ciborium::from_reader(&mut decoder) {
+            Ok(payload) => Some(payload),
+            Err(e) => {
+                eprintln!(
+                    "tessera: fixture {} ends early at block {i} of {}: {e}",
+                    path.display(),
+                    header.blocks
+                );
+                failed = true;
+                None
+            }
+        }
+    });
+    Ok(Box::new(payloads))
+}
+
+/// Describes a fixture. Binary fixtures only have their header read; other
+/// formats are read in full.
+pub fn read_fixture_header(path: &Path) -> Result<FixtureHeader, String> {
+    let mut reader = BufReader::new(File::open(path).map_err(|e| e.to_string())?);
+    if reader
+        .fill_buf()
+        .map_err(|e| e.to_string())?
+        .starts_with(FIXTURE_MAGIC)
+    {
+        return read_binary_header(&mut reader);
+    }
+    let payloads: Vec<BlockPayload> = read_fixture(path)?.collect();
+    Ok(FixtureHeader::describe(&payloads))
+}
+
+/// Writes payloads as a fixture in the given format, replacing any file at
+/// `path`.
+pub fn write_fixture(
+    path: &Path,
+    payloads: &[BlockPayload],
+    format: FixtureFormat,
+) -> Result<(), String> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut out = BufWriter::new(File::create(path).map_err(|e| e.to_string())?);
+    match format {
+        FixtureFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, payloads).map_err(|e| e.to_string())?;
+        }
+        FixtureFormat::JsonLines => {
+            for payload in payloads {
+                serde_json::to_writer(&mut out, payload).map_err(|e| e.to_string())?;
+                out.write_all(b"\n").map_err(|e| e.to_string())?;
+            }
+        }
+        FixtureFormat::Binary => {
+            let header = FixtureHeader::describe(payloads);
+            let mut body = Vec::new();
+            ciborium::into_writer(
+                &HeaderBody {
+                    chains: header.chains,
+                    blocks: header.blocks,
+                },
+                &mut body,
+            )
+            .map_err(|e| e.to_string())?;
+            out.write_all(FIXTURE_MAGIC).map_err(|e| e.to_string())?;
+            out.write_all(&FIXTURE_VERSION.to_le_bytes())
+                .map_err(|e| e.to_string())?;
+            out.write_all(&(body.len() as u32).to_le_bytes())
+                .map_err(|e| e.to_string())?;
+            out.write_all(&body).map_err(|e| e.to_string())?;
+
+            let mut encoder =
+                zstd::Encoder::new(&mut out, ZSTD_LEVEL).map_err(|e| e.to_string())?;
+            for payload in payloads {
+                ciborium::into_writer(payload, &mut encoder).map_err(|e| e.to_string())?;
+            }
+            encoder.finish().map_err(|e| e.to_string())?;
+        }
+    }
+    out.flush().map_err(|e| e.to_string())
+}
+
+/// Converts a fixture to the format picked by the output's extension.
+/// Returns the number of blocks written.
+pub fn convert_fixture(input: &Path, output: &Path) -> Result<usize, String> {
+    let payloads: Vec<BlockPayload> = read_fixture(input)?.collect();
+    write_fixture(output, &payloads, FixtureFormat::from_path(output))?;
+    Ok(payloads.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Address, B256, U256};
+
+    use super::*;
+    use crate::data::{EvmAddendum, TransactionEnvelope};
+
+    fn payload(chain: Chain, number: u64) -> BlockPayload {
+        BlockPayload {
+            gas_used: 21_000,
+            tx_count: 1,
+            base_fee_per_gas: Some(7),
+            fee_recipient: Some(Address::with_last_byte(0xfe)),
+            transactions: vec![TransactionEnvelope {
+                id: B256::with_last_byte(0xaa),
+                resource_used: Some(21_000),
+                price: 2_000_000_000,
+                value: U256::from(10).pow(U256::from(30)),
+                sender: Address::with_last_byte(1),
+                recipient: Some(Address::with_last_byte(2)),
+                success: Some(true),
+                evm: Some(EvmAddendum {
+                    tx_type: Some(2),
+                    max_fee_per_gas: Some(u128::MAX),
+                    ..Default::default()
+                }),
+                ..TransactionEnvelope::for_test(0)
+            }],
+            ..BlockPayload::for_test(chain, number)
+        }
+    }
+
+    #[test]
+    fn converts_between_json_and_binary() {
+        let payloads = vec![
+            payload(Chain::mainnet(), 10),
+            payload(Chain::base_mainnet(), 500),
+            payload(Chain::mainnet(), 11),
+        ];
+        let dir = tempfile::tempdir().unwrap();
+        let json = dir.path().join("roundtrip.json");
+        let binary = dir.path().join("roundtrip.tfix");
+        let back = dir.path().join("roundtrip-back.json");
+        write_fixture(&json, &payloads, FixtureFormat::Json).unwrap();
+
+        assert_eq!(convert_fixture(&json, &binary).unwrap(), 3);
+        let header = read_fixture_header(&binary).unwrap();
+        assert_eq!(header.version, FIXTURE_VERSION);
+        assert_eq!(header.blocks, 3);
+        assert_eq!(
+            header.chains,
+            vec![
+                ChainRange {
+                    chain: Chain::mainnet(),
+                    first: 10,
+                    last: 11,
+                    count: 2
+                },
+                ChainRange {
+                    chain: Chain::base_mainnet(),
+                    first: 500,
+                    last: 500,
+                    count: 1
+                },
+            ]
+        );
+
+        convert_fixture(&binary, &back).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&json).unwrap(),
+            std::fs::read_to_string(&back).unwrap()
+        );
+    }
+
+    #[test]
+    fn reads_arrays_and_lines_with_a_torn_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let array = dir.path().join("array.json");
+        let lines = dir.path().join("lines.jsonl");
+
+        std::fs::write(
+            &array,
+            serde_json::to_string_pretty(&vec![
+                payload(Chain::mainnet(), 1),
+                payload(Chain::mainnet(), 2),
+            ])
+            .unwrap(),
+        )
+        .unwrap();
+        let mut jsonl = String::new();
+        for n in [3, 4] {
+            jsonl.push_str(&serde_json::to_string(&payload(Chain::mainnet(), n)).unwrap());
+            jsonl.push('\n');
+        }
+        jsonl.push_str("{\"chain\":1,\"num");
+        std::fs::write(&lines, jsonl).unwrap();
+
+        let numbers =
+            |path: &Path| -> Vec<u64> { read_fixture(path).unwrap().map(|p| p.number).collect() };
+        assert_eq!(numbers(&array), [1, 2]);
+        assert_eq!(numbers(&lines), [3, 4]);
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("future.tfix");
+        let mut bytes = FIXTURE_MAGIC.to_vec();
+        bytes.extend_from_slice(&(FIXTURE_VERSION + 1).to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        std::fs::write(&path, bytes).unwrap();
+
+        let err = read_fixture(&path).err().unwrap();
+        assert!(err.contains("newer"), "{err}");
+    }
+}
@@ -0,0 +1,362 @@
+//! Pending transaction (mempool) watcher for EVM chains.
+//!
+//! Follows `eth_subscribe("newPendingTransactions")` on WebSocket endpoints
+//! and polls `txpool_content` otherwise (or once the subscription drops).
+//! Nodes without the `txpool` namespace are polled through a pending
+//! transaction filter instead. Pending transactions are mapped into the same
+//! envelope as mined ones.
+
+use std::collections::{HashMap, HashSet};
+use std::thread;
+use std::time::Duration;
+
+use alloy::consensus::Transaction as TxConsensus;
+use alloy::network::{Ethereum, TransactionResponse};
+use alloy::primitives::{Address, B256, U256};
+use alloy::providers::{Identity, Provider, ProviderBuilder};
+use alloy::rpc::types::Transaction;
+use alloy_chains::Chain;
+use crossbeam_channel::{Receiver, Sender};
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::data::endpoints::endpoint_label;
+use crate::data::envelope::TransactionEnvelope;
+use crate::data::evm::{is_pubsub_url, tx_to_envelope};
+use crate::data::retry::method_unsupported;
+use crate::data::FetcherConfig;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Most new transactions reported per poll, so a busy public mempool can't
+/// flood the scene.
+const MAX_NEW_PER_POLL: usize = 200;
+
+/// A transaction seen in the mempool.
+#[derive(Clone, Debug)]
+pub struct PendingTx {
+    pub chain: Chain,
+    pub tx: TransactionEnvelope,
+}
+
+/// Changes to a chain's mempool.
+#[derive(Clone, Debug)]
+pub enum MempoolEvent {
+    /// A transaction entered the pool.
+    Pending(Box<PendingTx>),
+    /// A transaction left the pool: mined, dropped or replaced. Consumers
+    /// tell these apart by whether it shows up in a block. Only reported
+    /// while polling `txpool_content`.
+    Left { chain: Chain, id: B256 },
+}
+
+/// Watches one EVM chain's pending transactions on a dedicated thread.
+pub struct MempoolFetcher {
+    config: FetcherConfig,
+}
+
+impl MempoolFetcher {
+    pub fn new(config: FetcherConfig) -> Self {
+        Self { config }
+    }
+
+    /// Starts watching and returns the event receiver. The watcher stops once
+    /// the receiver is dropped.
+    pub fn spawn(self) -> Receiver<MempoolEvent> {
+        let config = self.config;
+        let (tx, rx) = crossbeam_channel::bounded(256);
+        thread::spawn(move || {
+            let rt = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(rt) => rt,
+                Err(err) => {
+                    eprintln!("tessera: failed to build tokio runtime: {err}");
+                    return;
+                }
+            };
+            rt.block_on(mempool_loop(config, tx));
+        });
+        rx
+    }
+}
+
+async fn mempool_loop(config: FetcherConfig, tx: Sender<MempoolEvent>) {
+    let chain = config.chain;
+
+    // Pending transactions only make sense from one node's view of the pool,
+    // so the first endpoint that connects is used throughout.
+    let mut connected = None;
+    for url in config.rpc_urls() {
+        let builder: ProviderBuilder<Identity, Identity> = ProviderBuilder::default();
+        match builder.network::<Ethereum>().connect(url.as_str()).await {
+            Ok(provider) => {
+                connected = Some((url.clone(), provider));
+                break;
+            }
+            Err(err) => {
+                let label = endpoint_label(url);
+                eprintln!("tessera [{chain}]: mempool failed to connect to {label}: {err}");
+            }
+        }
+    }
+    let Some((url, provider)) = connected else {
+        return;
+    };
+
+    let mut watcher = MempoolWatcher {
+        chain,
+        provider,
+        tx,
+        pool: HashSet::new(),
+        txpool_supported: true,
+        filter: None,
+    };
+
+    if is_pubsub_url(&url) {
+        eprintln!("tessera [{chain}]: subscribing to pending transactions");
+        if watcher.follow_subscription().await.is_err() {
+            return;
+        }
+        eprintln!("tessera [{chain}]: pending transaction subscription ended, polling instead");
+    }
+
+    loop {
+        let result = if watcher.txpool_supported {
+            watcher.poll_txpool().await
+        } else {
+            watcher.poll_filter().await
+        };
+        match result {
+            Ok(true) => {}
+            Ok(false) => {
+                eprintln!("tessera [{chain}]: no mempool access on this endpoint, giving up");
+                return;
+            }
+            Err(()) => return,
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// `txpool_content` response; only the executable `pending` part is used.
+#[derive(Debug, Deserialize)]
+struct TxpoolContent {
+    #[serde(default)]
+    pending: HashMap<Address, HashMap<String, Transaction>>,
+}
+
+/// Mempool watch state. Methods returning `Result` fail only when the
+/// receiver is gone.
+struct MempoolWatcher<P> {
+    chain: Chain,
+    provider: P,
+    tx: Sender<MempoolEvent>,
+    /// Transactions reported as pending and still in the pool at the last
+    /// `txpool_content` poll.
+    pool: HashSet<B256>,
+    txpool_supported: bool,
+    /// Installed pending transaction filter, once `txpool_content` failed.
+    filter: Option<U256>,
+}
+
+impl<P: Provider<Ethereum>> MempoolWatcher<P> {
+    fn send(&self, event: MempoolEvent) -> Result<(), ()> {
+        self.tx.send(event).map_err(|_| ())
+    }
+
+    fn send_pending(&self, tx: &Transaction) -> Result<(), ()> {
+        self.send(MempoolEvent::Pending(Box::new(pending_tx(self.chain, tx))))
+    }
+
+    /// Reports each announced hash until the subscription ends.
+    async fn follow_subscription(&mut self) -> Result<(), ()> {
+        let chain = self.chain;
+        let mut subscription = match self.provider.subscribe_pending_transactions().await {
+            Ok(subscription) => subscription,
+            Err(err) => {
+                eprintln!("tessera [{chain}]: newPendingTransactions subscription failed: {err}");
+                return Ok(());
+            }
+        };
+
+        loop {
+            match subscription.recv().await {
+                Ok(hash) => self.lookup(hash).await?,
+                // A busy pool outpaces the lookups; skip what we missed.
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return Ok(()),
+            }
+        }
+    }
+
+    /// Fetches an announced transaction and reports it.
+    async fn lookup(&self, hash: B256) -> Result<(), ()> {
+        match self.provider.get_transaction_by_hash(hash).await {
+            Ok(Some(tx)) => self.send_pending(&tx),
+            // Mined or dropped before the lookup.
+            Ok(None) => Ok(()),
+            Err(err) => {
+                let chain = self.chain;
+                eprintln!("tessera [{chain}]: failed to look up pending tx {hash}: {err}");
+                Ok(())
+            }
+        }
+    }
+
+    /// Diffs the pool against the last poll, switching to filter polling if
+    /// the node lacks `txpool_content`. Other failures skip this poll.
+    async fn poll_txpool(&mut self) -> Result<bool, ()> {
+        let chain = self.chain;
+        let content = match self
+            .provider
+            .raw_request::<_, TxpoolContent>("txpool_content".into(), ())
+            .await
+        {
+            Ok(content) => content,
+            Err(err) if method_unsupported(&err) => {
+                eprintln!(
+                    "tessera [{chain}]: txpool_content unavailable ({err}), \
+                     polling a pending transaction filter"
+                );
+                self.txpool_supported = false;
+                return Ok(true);
+            }
+            Err(err) => {
+                eprintln!("tessera [{chain}]: txpool_content failed: {err}");
+                return Ok(true);
+            }
+        };
+
+        let current: HashMap<B256, &Transaction> = content
+            .pending
+            .values()
+            .flat_map(|by_nonce| by_nonce.values())
+            .map(|tx| (tx.tx_hash(), tx))
+            .collect();
+
+        for id in left_pool(&self.pool, &current) {
+            self.send(MempoolEvent::Left { chain, id })?;
+        }
+        self.pool.retain(|id| current.contains_key(id));
+
+        let mut fresh: Vec<&Transaction> = current
+            .iter()
+            .filter(|(id, _)| !self.pool.contains(*id))
+            .map(|(_, tx)| *tx)
+            .collect();
+        // When the pool outgrows the cap, show the highest bidders.
+        fresh.sort_by_key(|tx| std::cmp::Reverse(TxConsensus::max_fee_per_gas(*tx)));
+        for tx in fresh.into_iter().take(MAX_NEW_PER_POLL) {
+            self.send_pending(tx)?;
+            self.pool.insert(tx.tx_hash());
+        }
+        Ok(true)
+    }
+
+    /// Reports transactions announced through a pending transaction filter.
+    /// Returns false if the node can't install one.
+    async fn poll_filter(&mut self) -> Result<bool, ()> {
+        let chain = self.chain;
+        let id = match self.filter {
+            Some(id) => id,
+            None => match self.provider.new_pending_transactions_filter(false).await {
+                Ok(id) => *self.filter.insert(id),
+                Err(err) => {
+                    eprintln!("tessera [{chain}]: pending transaction filter unavailable: {err}");
+                    return Ok(false);
+                }
+            },
+        };
+
+        match self.provider.get_filter_changes::<B256>(id).await {
+            Ok(hashes) => {
+                for hash in hashes.into_iter().take(MAX_NEW_PER_POLL) {
+                    self.lookup(hash).await?;
+                }
+            }
+            Err(err) => {
+                // Nodes expire idle filters; install a new one next poll.
+                eprintln!("tessera [{chain}]: pending filter poll failed: {err}");
+                self.filter = None;
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Previously reported ids no longer in the pool.
+fn left_pool<T>(previous: &HashSet<B256>, current: &HashMap<B256, T>) -> Vec<B256> {
+    previous
+        .iter()
+        .filter(|id| !current.contains_key(*id))
+        .copied()
+        .collect()
+}
+
+fn pending_tx(chain: Chain, tx: &Transaction) -> PendingTx {
+    let mut envelope = tx_to_envelope(0, tx);
+    // Without a block there is no effective price yet; show the bid's cap.
+    envelope.price = TxConsensus::max_fee_per_gas(tx);
+    PendingTx {
+        chain,
+        tx: envelope,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_txpool_content() {
+        let json = r#"{
+            "pending": {
+                "0x00000000000000000000000000000000000000aa": {
+                    "7": {
+                        "type": "0x2",
+                        "chainId": "0x1",
+                        "nonce": "0x7",
+                        "gas": "0x5208",
+                        "maxFeePerGas": "0x77359400",
+                        "maxPriorityFeePerGas": "0x3b9aca00",
+                        "to": "0x00000000000000000000000000000000000000bb",
+                        "value": "0xde0b6b3a7640000",
+                        "accessList": [],
+                        "input": "0x",
+                        "r": "0x1",
+                        "s": "0x1",
+                        "yParity": "0x0",
+                        "v": "0x0",
+                        "hash": "0x0000000000000000000000000000000000000000000000000000000000000001",
+                        "blockHash": null,
+                        "blockNumber": null,
+                        "transactionIndex": null,
+                        "from": "0x00000000000000000000000000000000000000aa"
+                    }
+                }
+            },
+            "queued": {}
+        }"#;
+        let content: TxpoolContent = serde_json::from_str(json).unwrap();
+        let tx = &content.pending[&Address::with_last_byte(0xaa)]["7"];
+
+        let pending = pending_tx(Chain::mainnet(), tx);
+        assert_eq!(pending.tx.evm.as_ref().and_then(|evm| evm.nonce), Some(7));
+        assert_eq!(pending.tx.id, B256::with_last_byte(1));
+        assert_eq!(pending.tx.resource_limit, 21_000);
+        assert_eq!(pending.tx.price, 2_000_000_000);
+        assert_eq!(pending.tx.value, U256::from(1_000_000_000_000_000_000u64));
+        assert_eq!(pending.tx.recipient, Some(Address::with_last_byte(0xbb)));
+    }
+
+    #[test]
+    fn reports_ids_that_left_the_pool() {
+        let previous: HashSet<B256> = [1, 2, 3].map(B256::with_last_byte).into();
+        let current: HashMap<B256, ()> = [2, 4].map(|n| (B256::with_last_byte(n), ())).into();
+
+        let mut left = left_pool(&previous, &current);
+        left.sort();
+        assert_eq!(left, vec![B256::with_last_byte(1), B256::with_last_byte(3)]);
+    }
+}
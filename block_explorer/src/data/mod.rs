@@ -1,30 +1,217 @@
+mod builders;
 mod channel;
+mod contracts;
+mod endpoints;
+mod envelope;
 pub mod evm;
+mod fixture;
+pub mod mempool;
 mod model;
-#[allow(dead_code)]
-mod solana;
+mod record;
+mod registry_file;
+mod reorg;
+mod retry;
+mod selectors;
+pub mod solana;
+mod status;
+#[cfg(feature = "store")]
+mod store;
+mod stream;
+#[cfg(test)]
+mod test_support;
+mod tokens;
+mod traces;
+mod units;
 
 use alloy_chains::{Chain, NamedChain};
 use crossbeam_channel::Receiver;
 use url::Url;
 
+pub use builders::{BuilderRegistry, ProposerPayment};
+pub(crate) use channel::config_fetchers;
 pub use channel::{
-    init_block_channel, init_fixture_channel, init_multi_chain_channel, BlockChannel, RecordBuffer,
+    init_block_channel, init_chain_channels, init_fetcher_channels, init_fixture_channel,
+    init_mempool_channel, init_multi_chain_channel, BlockChannel, MempoolChannel, StatusChannel,
 };
-pub use model::{BlockPayload, OpStackFees, TxPayload};
+pub use contracts::{ContractCategory, ContractLabel, ContractRegistry};
+pub use endpoints::EndpointStrategy;
+pub use envelope::{EvmAddendum, SolanaAddendum, TransactionEnvelope, TxType};
+pub use fixture::{
+    convert_fixture, read_fixture, read_fixture_header, write_fixture, ChainRange, FixtureFormat,
+    FixtureHeader, BINARY_EXTENSION, FIXTURE_MAGIC, FIXTURE_VERSION,
+};
+pub use model::{BlockPayload, OpStackFees, ReorgNotice};
+pub use record::{RecordWriter, Recorder, Rotation};
+pub use selectors::{DecodedCall, SelectorDb, MAX_ARG_WORDS};
+pub use status::{ConnectionState, FetcherStatus};
+#[cfg(feature = "store")]
+pub(crate) use store::{replay_receiver, warm_receiver, WARM_BLOCKS, WARM_MAX_AGE};
+#[cfg(feature = "store")]
+pub use store::{BlockStore, Retention, StoreHistory, StoreWriter, StoredSpan};
+pub use stream::{BlockIter, BlockStream};
+pub use tokens::{TokenInfo, TokenRegistry, TokenStandard, TokenTransfer, TRANSFER_TOPIC};
+pub use traces::{CallFrame, CallKind, InternalTransfer, MAX_CALL_FRAMES};
+pub use units::{chain_units, format_ether, format_gwei, format_units, format_wei, ChainUnits};
+
+/// Display name for a chain's lane: the named chain, "Solana", or the raw ID.
+pub fn chain_name(chain: &Chain) -> String {
+    if solana::is_solana(chain) {
+        return "Solana".to_string();
+    }
+    chain
+        .named()
+        .map(|n| n.to_string())
+        .unwrap_or_else(|| chain.id().to_string())
+}
 
 /// Returns true if the chain is an OP Stack L2 (Base, Optimism).
 pub fn is_op_stack(chain: &Chain) -> bool {
     matches!(chain.named(), Some(NamedChain::Base | NamedChain::Optimism))
 }
 
+/// Default number of backfill batches in flight at once.
+pub const DEFAULT_CONCURRENCY: usize = 4;
+/// Default number of blocks per JSON-RPC batch request.
+pub const DEFAULT_BATCH_SIZE: usize = 10;
+
+/// Where a fetcher starts when exploring a historical range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockStart {
+    /// Start at an explicit block number.
+    Number(u64),
+    /// Start at the first block with a timestamp at or after this Unix time.
+    Timestamp(u64),
+}
+
 /// Configuration for spawning a chain fetcher.
+#[derive(Clone, Debug)]
 pub struct FetcherConfig {
     pub chain: Chain,
+    /// Primary RPC endpoint.
     pub rpc_url: Url,
+    /// Further endpoints for the same chain, tried in order after `rpc_url`.
+    pub fallback_urls: Vec<Url>,
+    /// How requests are spread over `rpc_url` and `fallback_urls`.
+    pub strategy: EndpointStrategy,
+    /// Fetch transaction receipts for gas used, status and logs.
+    pub receipts: bool,
+    /// Trace every transaction's internal calls with `debug_traceBlockByNumber`.
+    pub traces: bool,
+    /// Also watch pending transactions (EVM only); see [`mempool`].
+    pub mempool: bool,
+    /// First block to fetch. `None` backfills a short window before the tip.
+    pub from: Option<BlockStart>,
+    /// Last block to fetch. `None` keeps following the chain tip.
+    pub to: Option<u64>,
+    /// Backfill batches requested concurrently. Blocks are still delivered in
+    /// number order.
+    pub concurrency: usize,
+    /// Blocks per JSON-RPC batch request. `1` disables batching.
+    pub batch_size: usize,
+}
+
+impl FetcherConfig {
+    /// Receipts are on by default for OP Stack chains, whose L1 fee data is
+    /// only available from receipts.
+    pub fn new(chain: Chain, rpc_url: Url) -> Self {
+        Self {
+            receipts: is_op_stack(&chain),
+            chain,
+            rpc_url,
+            fallback_urls: Vec::new(),
+            strategy: EndpointStrategy::default(),
+            traces: false,
+            mempool: false,
+            from: None,
+            to: None,
+            concurrency: DEFAULT_CONCURRENCY,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    /// Add endpoints to use alongside the primary RPC URL.
+    pub fn with_fallback_urls(mut self, urls: Vec<Url>) -> Self {
+        self.fallback_urls = urls;
+        self
+    }
+
+    /// Set how requests are spread over the endpoints.
+    pub fn with_strategy(mut self, strategy: EndpointStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Primary endpoint followed by the fallbacks.
+    pub fn rpc_urls(&self) -> impl Iterator<Item = &Url> {
+        std::iter::once(&self.rpc_url).chain(&self.fallback_urls)
+    }
+
+    /// Start fetching at an explicit block number.
+    pub fn from_block(mut self, number: u64) -> Self {
+        self.from = Some(BlockStart::Number(number));
+        self
+    }
+
+    /// Start fetching at the first block produced at or after a Unix timestamp.
+    pub fn from_timestamp(mut self, timestamp: u64) -> Self {
+        self.from = Some(BlockStart::Timestamp(timestamp));
+        self
+    }
+
+    /// Stop after fetching this block instead of following the tip.
+    pub fn to_block(mut self, number: u64) -> Self {
+        self.to = Some(number);
+        self
+    }
+
+    /// Set how many backfill batches may be in flight at once.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Set how many blocks are requested per JSON-RPC batch.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Enable or disable the per-block receipts pass.
+    pub fn with_receipts(mut self, enabled: bool) -> Self {
+        self.receipts = enabled;
+        self
+    }
+
+    /// Enable or disable the per-block call tracing pass. Nodes without the
+    /// `debug` namespace are skipped after the first failure.
+    pub fn with_traces(mut self, enabled: bool) -> Self {
+        self.traces = enabled;
+        self
+    }
+
+    /// Enable or disable the pending transaction watcher.
+    pub fn with_mempool(mut self, enabled: bool) -> Self {
+        self.mempool = enabled;
+        self
+    }
 }
 
-/// Interface for chain-specific block fetchers.
+/// A source of blocks for one or more lanes.
+///
+/// Implementors carry their own configuration and are consumed when spawned,
+/// so anything they need (a client, a database handle, a simulator) can move
+/// onto the thread that produces payloads. The trait is object-safe; the SDK
+/// builder takes custom sources through
+/// [`add_fetcher`](crate::sdk::BlockExplorerBuilder::add_fetcher).
 pub trait ChainFetcher: Send + 'static {
-    fn spawn(config: FetcherConfig) -> Receiver<BlockPayload>;
+    /// Starts producing blocks in the background and returns their receiver.
+    /// Payloads are tagged with their chain, which picks the lane.
+    fn spawn(self: Box<Self>) -> Receiver<BlockPayload>;
+
+    /// Like [`spawn`](Self::spawn), plus a stream of [`FetcherStatus`] health
+    /// updates. Fetchers that don't report health get a status channel that
+    /// never yields.
+    fn spawn_with_status(self: Box<Self>) -> (Receiver<BlockPayload>, Receiver<FetcherStatus>) {
+        (self.spawn(), crossbeam_channel::never())
+    }
 }
@@ -1,92 +1,204 @@
 // Block and transaction payloads using Alloy primitive types for type safety.
 
-use alloy::primitives::{Address, B256};
+use alloy::primitives::{Address, Bytes, B256};
 use alloy_chains::Chain;
 use serde::{Deserialize, Serialize};
 
+use crate::data::builders::ProposerPayment;
+use crate::data::envelope::TransactionEnvelope;
+
 /// A single block's summary and its transactions.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BlockPayload {
     pub chain: Chain,
     pub number: u64,
+    #[serde(default)]
+    pub hash: B256,
+    #[serde(default)]
+    pub parent_hash: B256,
+    /// Resource consumed by the block (gas, compute units).
     pub gas_used: u64,
+    /// Resource the block may consume.
     pub gas_limit: u64,
     pub timestamp: u64,
     pub tx_count: u32,
     pub base_fee_per_gas: Option<u64>,
     pub blob_gas_used: Option<u64>,
-    pub transactions: Vec<TxPayload>,
+    /// Header beneficiary: the builder or proposer collecting priority fees.
+    /// `None` where the chain has no such field (Solana).
+    #[serde(default)]
+    pub fee_recipient: Option<Address>,
+    /// Header extra data, which builders often tag with their name.
+    #[serde(default)]
+    pub extra_data: Bytes,
+    pub transactions: Vec<TransactionEnvelope>,
     /// L1 block number this L2 block was derived from (OP Stack only).
     pub l1_origin_number: Option<u64>,
+    /// Set on the first block of a new canonical branch after a reorg.
+    #[serde(default)]
+    pub reorg: Option<ReorgNotice>,
+}
+
+impl BlockPayload {
+    /// The builder's payment to the proposer: a last transaction sent by the
+    /// fee recipient, as MEV-Boost builders that keep the fees append.
+    pub fn proposer_payment(&self) -> Option<ProposerPayment> {
+        let last = self.transactions.last()?;
+        if Some(last.sender) != self.fee_recipient {
+            return None;
+        }
+        Some(ProposerPayment {
+            proposer: last.recipient?,
+            value: last.value,
+        })
+    }
+
+    /// The first orphaned block number when this payload carries a reorg.
+    pub fn reorg_fork(&self) -> Option<u64> {
+        let reorg = self.reorg.as_ref()?;
+        Some(reorg.fork.unwrap_or(self.number))
+    }
+}
+
+#[cfg(test)]
+impl BlockPayload {
+    /// An empty block for tests: hashes derived from the number and 12s
+    /// block times. Override fields with struct update syntax as needed.
+    pub(crate) fn for_test(chain: Chain, number: u64) -> Self {
+        Self {
+            chain,
+            number,
+            hash: B256::with_last_byte(number as u8),
+            parent_hash: B256::with_last_byte((number as u8).wrapping_sub(1)),
+            gas_used: 0,
+            gas_limit: 30_000_000,
+            timestamp: 1_700_000_000 + number * 12,
+            tx_count: 0,
+            base_fee_per_gas: None,
+            blob_gas_used: None,
+            fee_recipient: None,
+            extra_data: Bytes::new(),
+            transactions: Vec::new(),
+            l1_origin_number: None,
+            reorg: None,
+        }
+    }
+}
+
+/// Marks a block as the fork point of a chain reorganization.
+///
+/// Every previously delivered block of the same chain numbered at or above
+/// the fork was orphaned and should be removed from the scene.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReorgNotice {
+    /// Number of previously delivered blocks that were orphaned.
+    pub depth: u64,
+    /// First orphaned block number when it sits below the carrying block;
+    /// `None` means the carrying block itself is the fork point.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fork: Option<u64>,
 }
 
 /// OP Stack L1 fee data extracted from transaction receipts.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OpStackFees {
+    /// Fee paid for posting the transaction's data to L1, in wei.
     pub l1_fee: u128,
     pub l1_gas_price: Option<u128>,
     pub l1_blob_base_fee: Option<u128>,
+    /// L1 gas attributed to the transaction's compressed data.
+    #[serde(default)]
+    pub l1_gas_used: Option<u128>,
+    /// Ecotone base fee scalar.
+    #[serde(default)]
+    pub l1_base_fee_scalar: Option<u64>,
+    /// Ecotone blob base fee scalar.
+    #[serde(default)]
+    pub l1_blob_base_fee_scalar: Option<u64>,
 }
 
-/// A single transaction's display-relevant fields.
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct TxPayload {
-    pub hash: B256,
-    pub tx_index: usize,
-    pub gas: u64,
-    pub gas_price: u128,
-    pub value_eth: f64,
-    pub from: Address,
-    pub to: Option<Address>,
-    pub blob_count: usize,
-    pub max_fee_per_blob_gas: Option<u128>,
-    /// OP Stack L1 fee data (present only for OP Stack L2 transactions).
-    pub op_stack_fees: Option<OpStackFees>,
+impl OpStackFees {
+    /// Fraction of the total fee spent on L1 data, given the L2 execution fee.
+    pub fn l1_share(&self, l2_execution_fee: u128) -> f32 {
+        let total = self.l1_fee.saturating_add(l2_execution_fee);
+        if total == 0 {
+            0.0
+        } else {
+            (self.l1_fee as f64 / total as f64) as f32
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::data::envelope::EvmAddendum;
+    use alloy::primitives::U256;
 
     fn sample_block() -> BlockPayload {
         BlockPayload {
             chain: Chain::mainnet(),
             number: 18_000_000,
+            hash: B256::repeat_byte(0x11),
+            parent_hash: B256::repeat_byte(0x10),
             gas_used: 12_000_000,
             gas_limit: 30_000_000,
             timestamp: 1_700_000_000,
             tx_count: 2,
             base_fee_per_gas: Some(30_000_000_000),
             blob_gas_used: Some(131_072),
+            fee_recipient: None,
+            extra_data: Default::default(),
             l1_origin_number: None,
+            reorg: Some(ReorgNotice {
+                depth: 2,
+                fork: None,
+            }),
             transactions: vec![
-                TxPayload {
-                    hash: B256::ZERO,
-                    tx_index: 0,
-                    gas: 21_000,
-                    gas_price: 30_000_000_000,
-                    value_eth: 1.5,
-                    from: Address::ZERO,
-                    to: Some(Address::ZERO),
-                    blob_count: 0,
-                    max_fee_per_blob_gas: None,
-                    op_stack_fees: None,
+                TransactionEnvelope {
+                    id: B256::ZERO,
+                    index: 0,
+                    resource_limit: 21_000,
+                    resource_used: Some(21_000),
+                    price: 30_000_000_000,
+                    fee_paid: Some(630_000_000_000_000),
+                    value: U256::from(1_500_000_000_000_000_000u64),
+                    sender: Address::ZERO,
+                    recipient: Some(Address::ZERO),
+                    success: Some(true),
+                    evm: Some(EvmAddendum {
+                        effective_gas_price: Some(30_000_000_000),
+                        logs_count: Some(0),
+                        ..Default::default()
+                    }),
+                    op_stack: None,
+                    solana: None,
                 },
-                TxPayload {
-                    hash: B256::ZERO,
-                    tx_index: 1,
-                    gas: 100_000,
-                    gas_price: 50_000_000_000,
-                    value_eth: 0.0,
-                    from: Address::ZERO,
-                    to: None,
-                    blob_count: 3,
-                    max_fee_per_blob_gas: Some(1_000_000_000),
-                    op_stack_fees: Some(OpStackFees {
+                TransactionEnvelope {
+                    id: B256::ZERO,
+                    index: 1,
+                    resource_limit: 100_000,
+                    resource_used: None,
+                    price: 50_000_000_000,
+                    fee_paid: None,
+                    value: U256::ZERO,
+                    sender: Address::ZERO,
+                    recipient: None,
+                    success: None,
+                    evm: Some(EvmAddendum {
+                        blob_count: 3,
+                        max_fee_per_blob_gas: Some(1_000_000_000),
+                        ..Default::default()
+                    }),
+                    op_stack: Some(OpStackFees {
                         l1_fee: 5_000_000_000_000,
                         l1_gas_price: Some(20_000_000_000),
                         l1_blob_base_fee: Some(1_000_000),
+                        l1_gas_used: Some(1_600),
+                        l1_base_fee_scalar: Some(1_368),
+                        l1_blob_base_fee_scalar: Some(810_949),
                     }),
+                    solana: None,
                 },
             ],
         }
@@ -101,15 +213,22 @@ mod tests {
         assert_eq!(block.chain, deserialized.chain);
         assert_eq!(block.number, deserialized.number);
         assert_eq!(block.gas_used, deserialized.gas_used);
+        assert_eq!(block.parent_hash, deserialized.parent_hash);
+        assert_eq!(block.reorg, deserialized.reorg);
+        assert_eq!(
+            block.transactions[0].resource_used_or_limit(),
+            deserialized.transactions[0].resource_used_or_limit()
+        );
         assert_eq!(block.transactions.len(), deserialized.transactions.len());
         assert_eq!(
-            block.transactions[1].op_stack_fees.as_ref().unwrap().l1_fee,
+            block.transactions[1].op_stack.as_ref().unwrap().l1_fee,
             deserialized.transactions[1]
-                .op_stack_fees
+                .op_stack
                 .as_ref()
                 .unwrap()
                 .l1_fee
         );
+        assert_eq!(deserialized.transactions[1].blob_count(), 3);
     }
 
     #[test]
@@ -119,4 +238,149 @@ mod tests {
         let deserialized: Vec<BlockPayload> = serde_json::from_str(&json).expect("deserialize vec");
         assert_eq!(blocks.len(), deserialized.len());
     }
+
+    #[test]
+    fn deserializes_payload_without_hashes() {
+        let mut value = serde_json::to_value(sample_block()).expect("serialize");
+        let obj = value.as_object_mut().unwrap();
+        obj.remove("hash");
+        obj.remove("parent_hash");
+        obj.remove("reorg");
+
+        let block: BlockPayload = serde_json::from_value(value).expect("deserialize");
+        assert_eq!(block.hash, B256::ZERO);
+        assert!(block.reorg.is_none());
+    }
+
+    #[test]
+    fn deserializes_legacy_evm_transactions() {
+        let mut value = serde_json::to_value(sample_block()).expect("serialize");
+        value["transactions"] = serde_json::json!([{
+            "hash": format!("{}", B256::repeat_byte(0x22)),
+            "tx_index": 0,
+            "gas": 50_000,
+            "gas_price": 2_000_000,
+            "value_eth": 0.25,
+            "from": format!("{}", Address::ZERO),
+            "to": null,
+            "blob_count": 0,
+            "max_fee_per_blob_gas": null,
+            "op_stack_fees": { "l1_fee": 1_000, "l1_gas_price": null, "l1_blob_base_fee": null },
+            "receipt": {
+                "gas_used": 40_000,
+                "status": false,
+                "effective_gas_price": 1_000_000,
+                "logs_count": 2,
+                "contract_address": null
+            }
+        }]);
+
+        let block: BlockPayload = serde_json::from_value(value).expect("deserialize");
+        let tx = &block.transactions[0];
+        assert_eq!(tx.id, B256::repeat_byte(0x22));
+        assert_eq!(tx.resource_used_or_limit(), 40_000);
+        assert_eq!(tx.fee_paid, Some(40_000_001_000));
+        assert_eq!(tx.execution_fee(), Some(40_000_000_000));
+        assert!(tx.reverted());
+        assert_eq!(tx.value, U256::from(250_000_000_000_000_000u64));
+        assert_eq!(tx.evm.as_ref().unwrap().logs_count, Some(2));
+    }
+
+    #[test]
+    fn proposer_payment_is_last_tx_from_fee_recipient() {
+        let mut block = sample_block();
+        assert_eq!(block.proposer_payment(), None, "no fee recipient");
+
+        let builder = Address::with_last_byte(0xb1);
+        let proposer = Address::with_last_byte(0xa7);
+        block.fee_recipient = Some(builder);
+        block.transactions[1].sender = builder;
+        block.transactions[1].recipient = Some(proposer);
+        block.transactions[1].value = U256::from(50_000_000_000_000_000u64);
+        assert_eq!(
+            block.proposer_payment(),
+            Some(ProposerPayment {
+                proposer,
+                value: U256::from(50_000_000_000_000_000u64)
+            })
+        );
+    }
+
+    #[test]
+    fn values_round_trip_exactly_and_read_legacy_value_eth() {
+        let mut block = sample_block();
+        block.transactions[0].value = U256::MAX;
+        let mut value = serde_json::to_value(&block).expect("serialize");
+        let exact: BlockPayload = serde_json::from_value(value.clone()).expect("deserialize");
+        assert_eq!(exact.transactions[0].value, U256::MAX);
+
+        // Older fixtures hold whole units in `value_eth`.
+        for tx in value["transactions"].as_array_mut().unwrap() {
+            tx.as_object_mut().unwrap().remove("value");
+        }
+        value["transactions"][0]["value_eth"] = serde_json::json!(1.5);
+        value["transactions"][1]["value_eth"] = serde_json::json!(0.051);
+        value["transactions"][1]["solana"] = serde_json::json!({
+            "signature": "", "fee_payer": "", "program": null,
+            "priority_fee": 0, "log_messages": 0
+        });
+        let legacy: BlockPayload = serde_json::from_value(value).expect("deserialize");
+        assert_eq!(
+            legacy.transactions[0].value,
+            U256::from(1_500_000_000_000_000_000u64)
+        );
+        assert_eq!(legacy.transactions[1].value, U256::from(51_000_000));
+    }
+
+    #[test]
+    fn integer_value_eth_is_whole_units_and_value_is_exact() {
+        let mut value = serde_json::to_value(sample_block()).expect("serialize");
+        value["transactions"][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("value");
+        value["transactions"][0]["value_eth"] = serde_json::json!(2);
+        value["transactions"][1]["value"] = serde_json::json!(2);
+        let block: BlockPayload = serde_json::from_value(value.clone()).expect("deserialize");
+        assert_eq!(
+            block.transactions[0].value,
+            U256::from(2_000_000_000_000_000_000u64)
+        );
+        assert_eq!(block.transactions[1].value, U256::from(2));
+
+        value["transactions"][1]["value"] = serde_json::json!(2.0);
+        assert!(serde_json::from_value::<BlockPayload>(value).is_err());
+    }
+
+    #[test]
+    fn resource_used_falls_back_to_limit_without_receipt() {
+        let block = sample_block();
+        let with_receipt = &block.transactions[0];
+        let without_receipt = &block.transactions[1];
+
+        assert_eq!(with_receipt.resource_used_or_limit(), 21_000);
+        assert!(!with_receipt.reverted());
+        assert_eq!(without_receipt.resource_used_or_limit(), 100_000);
+        assert!(!without_receipt.reverted());
+    }
+
+    #[test]
+    fn l1_share_splits_total_fee() {
+        let fees = OpStackFees {
+            l1_fee: 3_000,
+            l1_gas_price: None,
+            l1_blob_base_fee: None,
+            l1_gas_used: None,
+            l1_base_fee_scalar: None,
+            l1_blob_base_fee_scalar: None,
+        };
+
+        assert!((fees.l1_share(1_000) - 0.75).abs() < f32::EPSILON);
+        assert_eq!(fees.l1_share(0), 1.0);
+
+        let mut tx = sample_block().transactions[1].clone();
+        assert_eq!(tx.l1_fee_share(), None, "no fee paid, no L2 fee");
+        tx.fee_paid = Some(5_000_000_000_000 + 5_000_000_000_000);
+        assert!((tx.l1_fee_share().unwrap() - 0.5).abs() < f32::EPSILON);
+    }
 }
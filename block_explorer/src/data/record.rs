@@ -0,0 +1,345 @@
+//! Session recording to JSON Lines fixtures.
+//!
+//! [`Recorder`] appends each ingested payload to the recording as one JSON
+//! line, on a background thread that fsyncs at least once a second, so a
+//! crash loses at most the last second. With a [`Rotation`] set, the file is
+//! moved aside once it grows too large or too old and a fresh one started.
+//! [`read_fixture`](crate::data::read_fixture) replays these recordings
+//! like any other fixture.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{RecvTimeoutError, Sender};
+
+use crate::data::fixture::FIXTURE_MAGIC;
+use crate::data::model::BlockPayload;
+
+/// Longest time written payloads wait before being synced to disk.
+const SYNC_INTERVAL: Duration = Duration::from_secs(1);
+
+/// When to start a new recording file. Unset limits never rotate.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rotation {
+    /// Rotate once the file reaches this many bytes.
+    pub max_bytes: Option<u64>,
+    /// Rotate once the file has been written to for this long.
+    pub max_age: Option<Duration>,
+}
+
+/// Appends payloads to a JSON Lines file, rotating it by size or age.
+///
+/// The active file is always the configured path. Rotated files are renamed
+/// next to it with a sequence number, `session.jsonl` becoming
+/// `session.1.jsonl`, `session.2.jsonl`, and so on.
+pub struct RecordWriter {
+    path: PathBuf,
+    rotation: Rotation,
+    file: BufWriter<File>,
+    bytes: u64,
+    opened: Instant,
+    rotated: u32,
+}
+
+impl RecordWriter {
+    /// Opens the recording, appending to an existing JSON Lines file at
+    /// `path`. A line cut short by a crash is dropped first so the next
+    /// payload starts on a line of its own. An existing JSON array or binary
+    /// fixture can't be appended to, so it is moved aside like a rotated file
+    /// instead.
+    pub fn open(path: &Path, rotation: Rotation) -> Result<Self, String> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let mut rotated = 0;
+        if is_other_format(path)? {
+            let aside = next_rotated_path(path, &mut rotated);
+            std::fs::rename(path, &aside).map_err(|e| e.to_string())?;
+            eprintln!(
+                "tessera: {} is not a JSON Lines recording, moved it to {}",
+                path.display(),
+                aside.display()
+            );
+        } else {
+            trim_torn_tail(path)?;
+        }
+        let (file, bytes) = open_append(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            rotation,
+            file,
+            bytes,
+            opened: Instant::now(),
+            rotated,
+        })
+    }
+
+    /// Writes one payload as a line, rotating first if the file is due.
+    pub fn write(&mut self, payload: &BlockPayload) -> Result<(), String> {
+        let mut line = serde_json::to_vec(payload).map_err(|e| e.to_string())?;
+        line.push(b'\n');
+        if self.bytes > 0 && self.due(line.len() as u64) {
+            self.rotate()?;
+        }
+        self.file.write_all(&line).map_err(|e| e.to_string())?;
+        self.bytes += line.len() as u64;
+        Ok(())
+    }
+
+    /// Flushes buffered lines and syncs them to disk.
+    pub fn sync(&mut self) -> Result<(), String> {
+        self.file.flush().map_err(|e| e.to_string())?;
+        self.file.get_ref().sync_data().map_err(|e| e.to_string())
+    }
+
+    fn due(&self, next_line: u64) -> bool {
+        let too_big = self
+            .rotation
+            .max_bytes
+            .is_some_and(|max| self.bytes + next_line > max);
+        let too_old = self
+            .rotation
+            .max_age
+            .is_some_and(|max| self.opened.elapsed() >= max);
+        too_big || too_old
+    }
+
+    /// Moves the current file aside and starts an empty one in its place.
+    fn rotate(&mut self) -> Result<(), String> {
+        self.sync()?;
+        let rotated = next_rotated_path(&self.path, &mut self.rotated);
+        std::fs::rename(&self.path, &rotated).map_err(|e| e.to_string())?;
+        let (file, bytes) = open_append(&self.path)?;
+        self.file = file;
+        self.bytes = bytes;
+        self.opened = Instant::now();
+        eprintln!("tessera: rotated recording to {}", rotated.display());
+        Ok(())
+    }
+}
+
+fn open_append(path: &Path) -> Result<(BufWriter<File>, u64), String> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| e.to_string())?;
+    let bytes = file.metadata().map_err(|e| e.to_string())?.len();
+    Ok((BufWriter::new(file), bytes))
+}
+
+/// Whether `path` holds a fixture in a format other than JSON Lines: a JSON
+/// array or a binary fixture.
+fn is_other_format(path: &Path) -> Result<bool, String> {
+    let mut head = Vec::with_capacity(64);
+    match File::open(path) {
+        Ok(file) => file
+            .take(64)
+            .read_to_end(&mut head)
+            .map_err(|e| e.to_string())?,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.to_string()),
+    };
+    let first = head.iter().find(|b| !b.is_ascii_whitespace());
+    Ok(head.starts_with(FIXTURE_MAGIC) || first == Some(&b'['))
+}
+
+/// Truncates `path` back to its last newline, dropping a partial line left
+/// by a crash mid-write.
+fn trim_torn_tail(path: &Path) -> Result<(), String> {
+    let mut file = match OpenOptions::new().read(true).write(true).open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.to_string()),
+    };
+    let len = file.metadata().map_err(|e| e.to_string())?.len();
+    let mut end = len;
+    let mut buf = [0u8; 4096];
+    while end > 0 {
+        let start = end.saturating_sub(buf.len() as u64);
+        let chunk = &mut buf[..(end - start) as usize];
+        file.seek(SeekFrom::Start(start))
+            .map_err(|e| e.to_string())?;
+        file.read_exact(chunk).map_err(|e| e.to_string())?;
+        if let Some(newline) = chunk.iter().rposition(|b| *b == b'\n') {
+            end = start + newline as u64 + 1;
+            break;
+        }
+        end = start;
+    }
+    if end < len {
+        eprintln!(
+            "tessera: dropping a partial last line from {}",
+            path.display()
+        );
+        file.set_len(end).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// The first unused rotated name after `*n`, advancing `*n` to it.
+fn next_rotated_path(path: &Path, n: &mut u32) -> PathBuf {
+    loop {
+        *n += 1;
+        let candidate = rotated_path(path, *n);
+        if !candidate.exists() {
+            return candidate;
+        }
+    }
+}
+
+/// `dir/session.jsonl` → `dir/session.<n>.jsonl`.
+fn rotated_path(path: &Path, n: u32) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = match path.extension() {
+        Some(ext) => format!("{stem}.{n}.{}", ext.to_string_lossy()),
+        None => format!("{stem}.{n}"),
+    };
+    path.with_file_name(name)
+}
+
+/// Bevy resource that streams ingested payloads to a recording.
+#[derive(bevy::prelude::Resource)]
+pub struct Recorder {
+    tx: Option<Sender<BlockPayload>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Recorder {
+    /// Opens the recording and starts its writer thread.
+    pub fn start(path: &Path, rotation: Rotation) -> Result<Self, String> {
+        let mut writer = RecordWriter::open(path, rotation)?;
+        let (tx, rx) = crossbeam_channel::unbounded::<BlockPayload>();
+        let path = path.to_path_buf();
+        let thread = std::thread::spawn(move || {
+            let mut written = 0usize;
+            let mut dirty = false;
+            let mut last_sync = Instant::now();
+            loop {
+                match rx.recv_timeout(SYNC_INTERVAL) {
+                    Ok(payload) => match writer.write(&payload) {
+                        Ok(()) => {
+                            written += 1;
+                            dirty = true;
+                        }
+                        Err(e) => eprintln!("tessera: failed to record block: {e}"),
+                    },
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+                if dirty && last_sync.elapsed() >= SYNC_INTERVAL {
+                    if let Err(e) = writer.sync() {
+                        eprintln!("tessera: failed to sync recording: {e}");
+                    }
+                    dirty = false;
+                    last_sync = Instant::now();
+                }
+            }
+            if let Err(e) = writer.sync() {
+                eprintln!("tessera: failed to sync recording: {e}");
+            }
+            eprintln!("tessera: recorded {written} blocks to {}", path.display());
+        });
+        Ok(Self {
+            tx: Some(tx),
+            thread: Some(thread),
+        })
+    }
+
+    /// Queues a payload for the recording.
+    pub fn send(&self, payload: BlockPayload) {
+        if let Some(tx) = &self.tx {
+            // The writer only stops once `finish` is called.
+            let _ = tx.send(payload);
+        }
+    }
+
+    /// Writes out everything queued and waits for the final sync.
+    pub fn finish(&mut self) {
+        self.tx.take();
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_chains::Chain;
+
+    use super::*;
+    use crate::data::read_fixture;
+
+    fn payload(number: u64) -> BlockPayload {
+        BlockPayload::for_test(Chain::mainnet(), number)
+    }
+
+    fn numbers(path: &Path) -> Vec<u64> {
+        read_fixture(path).unwrap().map(|p| p.number).collect()
+    }
+
+    #[test]
+    fn rotates_by_size_and_reads_back_each_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+
+        let line_len = serde_json::to_vec(&payload(1)).unwrap().len() as u64 + 1;
+        let rotation = Rotation {
+            max_bytes: Some(line_len * 2),
+            max_age: None,
+        };
+        let mut recorder = Recorder::start(&path, rotation).unwrap();
+        for n in 1..=5 {
+            recorder.send(payload(n));
+        }
+        recorder.finish();
+
+        assert_eq!(numbers(&dir.path().join("session.1.jsonl")), [1, 2]);
+        assert_eq!(numbers(&dir.path().join("session.2.jsonl")), [3, 4]);
+        assert_eq!(numbers(&path), [5]);
+    }
+
+    #[test]
+    fn moves_an_existing_json_array_aside() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+        std::fs::write(
+            &path,
+            serde_json::to_string_pretty(&vec![payload(1), payload(2)]).unwrap(),
+        )
+        .unwrap();
+
+        let mut recorder = Recorder::start(&path, Rotation::default()).unwrap();
+        recorder.send(payload(3));
+        recorder.finish();
+
+        assert_eq!(numbers(&dir.path().join("session.1.json")), [1, 2]);
+        assert_eq!(numbers(&path), [3]);
+    }
+
+    #[test]
+    fn appends_after_dropping_a_torn_last_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let mut jsonl = String::new();
+        for n in [1, 2] {
+            jsonl.push_str(&serde_json::to_string(&payload(n)).unwrap());
+            jsonl.push('\n');
+        }
+        jsonl.push_str("{\"chain\":1,\"num");
+        std::fs::write(&path, jsonl).unwrap();
+
+        let mut recorder = Recorder::start(&path, Rotation::default()).unwrap();
+        recorder.send(payload(3));
+        recorder.send(payload(4));
+        recorder.finish();
+
+        assert_eq!(numbers(&path), [1, 2, 3, 4]);
+    }
+}
@@ -0,0 +1,28 @@
+//! User files for the token, contract and builder registries.
+//!
+//! Each file is a JSON array of entries or, when the name ends in `.toml`, an
+//! array of tables under one key (`[[token]]`, `[[contract]]`,
+//! `[[builder]]`). The selector database keeps its own one-signature-per-line
+//! format.
+
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+
+/// Reads the entries in a JSON or TOML file, picked by extension. `table` is
+/// the TOML array-of-tables key; a TOML file without it has no entries.
+pub(crate) fn read_entries<T: DeserializeOwned>(
+    path: &Path,
+    table: &str,
+) -> Result<Vec<T>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    if path.extension().is_some_and(|ext| ext == "toml") {
+        let mut file: toml::Table = toml::from_str(&text).map_err(|e| e.to_string())?;
+        match file.remove(table) {
+            Some(entries) => entries.try_into().map_err(|e| e.to_string()),
+            None => Ok(Vec::new()),
+        }
+    } else {
+        serde_json::from_str(&text).map_err(|e| e.to_string())
+    }
+}
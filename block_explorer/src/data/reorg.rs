@@ -0,0 +1,140 @@
+//! Short hash history used by fetchers to detect chain reorganizations.
+
+use std::collections::VecDeque;
+
+use alloy::primitives::B256;
+
+/// Number of recent block hashes kept per chain. Reorgs deeper than this
+/// are rolled back only as far as the tracked history reaches.
+pub const REORG_HISTORY: usize = 64;
+
+/// Rolling window of `(number, hash, parent_hash)` for delivered blocks.
+pub struct HashChain {
+    entries: VecDeque<(u64, B256, B256)>,
+    capacity: usize,
+}
+
+impl Default for HashChain {
+    fn default() -> Self {
+        Self::new(REORG_HISTORY)
+    }
+}
+
+impl HashChain {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Records a delivered block, evicting the oldest entry when full.
+    pub fn push(&mut self, number: u64, hash: B256, parent_hash: B256) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((number, hash, parent_hash));
+    }
+
+    /// Returns the tracked hash for a block number, if still in the window.
+    pub fn hash_at(&self, number: u64) -> Option<B256> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(n, _, _)| *n == number)
+            .map(|(_, hash, _)| *hash)
+    }
+
+    /// Highest block number still tracked.
+    pub fn latest(&self) -> Option<u64> {
+        self.entries.iter().map(|(n, _, _)| *n).max()
+    }
+
+    /// Returns false only when the tracked parent of `number` is known and
+    /// differs from `parent_hash`. Blocks outside the window are assumed to
+    /// extend the chain.
+    pub fn extends(&self, number: u64, parent_hash: B256) -> bool {
+        match number
+            .checked_sub(1)
+            .and_then(|parent| self.hash_at(parent))
+        {
+            Some(hash) => hash == parent_hash,
+            None => true,
+        }
+    }
+
+    /// Returns false only when the tracked child of `number` is known and
+    /// names a different parent than `hash`. The counterpart of
+    /// [`extends`](Self::extends) for a block delivered out of order.
+    pub fn precedes(&self, number: u64, hash: B256) -> bool {
+        let child = number + 1;
+        match self.entries.iter().rev().find(|(n, _, _)| *n == child) {
+            Some((_, _, parent_hash)) => *parent_hash == hash,
+            None => true,
+        }
+    }
+
+    /// Drops every entry numbered at or above `fork`, returning how many
+    /// delivered blocks were orphaned.
+    pub fn rewind(&mut self, fork: u64) -> u64 {
+        let before = self.entries.len();
+        self.entries.retain(|(n, _, _)| *n < fork);
+        (before - self.entries.len()) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(byte: u8) -> B256 {
+        B256::repeat_byte(byte)
+    }
+
+    #[test]
+    fn extends_checks_tracked_parent() {
+        let mut chain = HashChain::new(4);
+        chain.push(10, hash(10), hash(9));
+        chain.push(11, hash(11), hash(10));
+
+        assert!(chain.extends(12, hash(11)));
+        assert!(!chain.extends(12, hash(0xee)));
+        // Parent outside the window can't be checked.
+        assert!(chain.extends(20, hash(0xee)));
+    }
+
+    #[test]
+    fn precedes_checks_tracked_child() {
+        let mut chain = HashChain::new(4);
+        chain.push(10, hash(10), hash(9));
+        chain.push(12, hash(12), hash(11));
+
+        assert!(chain.precedes(11, hash(11)));
+        assert!(!chain.precedes(11, hash(0xee)));
+        // No tracked child to check against.
+        assert!(chain.precedes(12, hash(0xee)));
+    }
+
+    #[test]
+    fn push_evicts_oldest_when_full() {
+        let mut chain = HashChain::new(2);
+        chain.push(1, hash(1), hash(0));
+        chain.push(2, hash(2), hash(1));
+        chain.push(3, hash(3), hash(2));
+
+        assert_eq!(chain.hash_at(1), None);
+        assert_eq!(chain.hash_at(3), Some(hash(3)));
+    }
+
+    #[test]
+    fn rewind_reports_orphaned_depth() {
+        let mut chain = HashChain::new(8);
+        for n in 100..=105 {
+            chain.push(n, hash(n as u8), hash(n as u8 - 1));
+        }
+
+        assert_eq!(chain.rewind(103), 3);
+        assert_eq!(chain.hash_at(102), Some(hash(102)));
+        assert_eq!(chain.hash_at(103), None);
+    }
+}
@@ -0,0 +1,355 @@
+//! Retry policy for RPC requests and the queue of blocks still to re-fetch.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher, RandomState};
+use std::time::{Duration, Instant};
+
+use alloy::transports::layers::{RateLimitRetryPolicy, RetryPolicy};
+use alloy::transports::{RpcError, TransportError, TransportErrorKind};
+
+use crate::data::endpoints::Endpoints;
+use crate::data::status::StatusReporter;
+
+/// Maximum number of missing blocks tracked per chain. When full, the oldest
+/// gap is given up so a long outage can't grow the queue without bound.
+pub const MAX_PENDING_GAPS: usize = 256;
+
+/// Exponential backoff with jitter.
+#[derive(Clone, Copy, Debug)]
+pub struct Backoff {
+    /// Delay before the first retry.
+    pub base: Duration,
+    /// Upper bound for any single delay, including server hints.
+    pub max: Duration,
+    /// Attempts per request, including the first one.
+    pub max_attempts: u32,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            base: Duration::from_millis(250),
+            max: Duration::from_secs(30),
+            max_attempts: 4,
+        }
+    }
+}
+
+impl Backoff {
+    /// Delay before retry number `retry` (0 for the first retry).
+    ///
+    /// The exponential delay is scaled into `[50%, 100%]` by `jitter` (a value
+    /// in `[0, 1)`) so fetchers sharing a provider don't retry in lockstep. A
+    /// server-provided `hint` is honoured as a minimum.
+    pub fn delay(&self, retry: u32, jitter: f64, hint: Option<Duration>) -> Duration {
+        let exponential = self
+            .base
+            .saturating_mul(1u32.checked_shl(retry).unwrap_or(u32::MAX))
+            .min(self.max);
+        let jittered = exponential.mul_f64(0.5 + 0.5 * jitter.clamp(0.0, 1.0));
+        hint.map_or(jittered, |hint| jittered.max(hint))
+            .min(self.max)
+    }
+}
+
+/// Uniform value in `[0, 1)` for jittering retry delays.
+pub fn jitter() -> f64 {
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// How long the server asked us to wait before retrying, if it said.
+///
+/// alloy's HTTP transport drops response headers, so this reads the
+/// `Retry-After` value where providers echo it into the error: Infura's
+/// `rate.backoff_seconds` in the JSON-RPC error data, or a `retry_after` /
+/// `retryAfter` field in the error data or HTTP error body.
+pub fn retry_after(err: &TransportError) -> Option<Duration> {
+    if let Some(hint) = RateLimitRetryPolicy::default().backoff_hint(err) {
+        return Some(hint);
+    }
+    let value: serde_json::Value = match err {
+        RpcError::ErrorResp(payload) => serde_json::from_str(payload.data.as_ref()?.get()).ok()?,
+        RpcError::Transport(TransportErrorKind::HttpError(http)) => {
+            serde_json::from_str(&http.body).ok()?
+        }
+        _ => return None,
+    };
+    retry_after_field(&value)
+}
+
+fn retry_after_field(value: &serde_json::Value) -> Option<Duration> {
+    let field = ["retry_after", "retryAfter", "Retry-After"]
+        .iter()
+        .find_map(|key| value.get(key))
+        .or_else(|| value.get("error").and_then(|e| e.get("retry_after")))?;
+    match field {
+        serde_json::Value::Number(n) => n.as_f64().map(Duration::from_secs_f64),
+        serde_json::Value::String(s) => s.trim().parse().ok().map(Duration::from_secs_f64),
+        _ => None,
+    }
+    .filter(|d| !d.is_zero())
+}
+
+/// Whether the node rejected the request because it doesn't offer the method
+/// at all, as opposed to failing this one call. Nodes answer with -32601, or
+/// with a generic code and a message saying so.
+pub fn method_unsupported(err: &TransportError) -> bool {
+    let Some(payload) = err.as_error_resp() else {
+        return false;
+    };
+    if payload.code == -32601 {
+        return true;
+    }
+    let message = payload.message.to_lowercase();
+    message.contains("method")
+        && [
+            "not found",
+            "not supported",
+            "unsupported",
+            "does not exist",
+            "not available",
+            "not enabled",
+        ]
+        .iter()
+        .any(|phrase| message.contains(phrase))
+}
+
+/// Runs `request` against the active endpoint up to `attempts` times, backing
+/// off between failures and honouring any retry delay the provider asks for.
+/// Each failure moves on to the next endpoint when there is more than one.
+/// Latency, retries, errors and endpoint switches are reported through `status`.
+pub(crate) async fn with_retries<'a, T, P, F, Fut>(
+    endpoints: &'a Endpoints<P>,
+    backoff: &Backoff,
+    status: &mut StatusReporter,
+    what: &str,
+    attempts: u32,
+    mut request: F,
+) -> Result<T, TransportError>
+where
+    F: FnMut(&'a P) -> Fut,
+    Fut: Future<Output = Result<T, TransportError>>,
+{
+    let chain = status.status().chain;
+    let mut retry = 0;
+    loop {
+        let started = Instant::now();
+        match request(endpoints.active()).await {
+            Ok(value) => {
+                status.record_success(started.elapsed());
+                return Ok(value);
+            }
+            Err(err) if retry + 1 < attempts => {
+                let delay = backoff.delay(retry, jitter(), retry_after(&err));
+                let failed = endpoints.active_label();
+                if endpoints.rotate() {
+                    let next = endpoints.active_label();
+                    eprintln!(
+                        "tessera [{chain}]: {what} failed on {failed} ({err}), \
+                         retrying on {next} in {delay:?}"
+                    );
+                    status.set_endpoint(next);
+                } else {
+                    eprintln!("tessera [{chain}]: {what} failed ({err}), retrying in {delay:?}");
+                }
+                status.record_retry(format!("{what}: {err}"));
+                tokio::time::sleep(delay).await;
+                retry += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct PendingGap {
+    attempts: u32,
+    retry_at: Instant,
+}
+
+/// Block numbers that failed to fetch, each with its own backoff schedule.
+pub struct GapQueue {
+    gaps: BTreeMap<u64, PendingGap>,
+    backoff: Backoff,
+    capacity: usize,
+}
+
+impl Default for GapQueue {
+    fn default() -> Self {
+        Self::new(Backoff::default(), MAX_PENDING_GAPS)
+    }
+}
+
+impl GapQueue {
+    pub fn new(backoff: Backoff, capacity: usize) -> Self {
+        Self {
+            gaps: BTreeMap::new(),
+            backoff,
+            capacity,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.gaps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.gaps.is_empty()
+    }
+
+    /// Records a failed fetch of `number` and schedules its next attempt.
+    /// Returns a gap that had to be dropped to make room, if any.
+    pub fn record_failure(&mut self, number: u64, now: Instant, jitter: f64) -> Option<u64> {
+        let attempts = self.gaps.get(&number).map_or(0, |gap| gap.attempts) + 1;
+        // Gap retries back off past the per-request attempts already spent.
+        let delay = self
+            .backoff
+            .delay(self.backoff.max_attempts + attempts - 1, jitter, None);
+        self.gaps.insert(
+            number,
+            PendingGap {
+                attempts,
+                retry_at: now + delay,
+            },
+        );
+
+        if self.gaps.len() > self.capacity {
+            return self.gaps.pop_first().map(|(dropped, _)| dropped);
+        }
+        None
+    }
+
+    /// Removes `number` after it has been fetched.
+    pub fn resolve(&mut self, number: u64) {
+        self.gaps.remove(&number);
+    }
+
+    /// Gaps whose next attempt is due, oldest first.
+    pub fn due(&self, now: Instant) -> Vec<u64> {
+        self.gaps
+            .iter()
+            .filter(|(_, gap)| gap.retry_at <= now)
+            .map(|(number, _)| *number)
+            .collect()
+    }
+
+    /// Forgets every gap numbered at or above `fork`; the reorged branch
+    /// re-delivers that range.
+    pub fn rewind(&mut self, fork: u64) {
+        self.gaps.retain(|number, _| *number < fork);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_resp(json: &str) -> TransportError {
+        TransportError::ErrorResp(serde_json::from_str(json).unwrap())
+    }
+
+    #[test]
+    fn only_missing_methods_count_as_unsupported() {
+        assert!(method_unsupported(&error_resp(
+            r#"{"code":-32601,"message":"Method not found"}"#
+        )));
+        assert!(method_unsupported(&error_resp(
+            r#"{"code":-32600,"message":"Unsupported method: eth_getBlockReceipts"}"#
+        )));
+        assert!(!method_unsupported(&error_resp(
+            r#"{"code":-32000,"message":"header not found"}"#
+        )));
+        assert!(!method_unsupported(&error_resp(
+            r#"{"code":429,"message":"rate limit exceeded"}"#
+        )));
+        assert!(!method_unsupported(&TransportErrorKind::custom_str(
+            "connection reset"
+        )));
+    }
+
+    #[test]
+    fn delay_grows_exponentially_within_jitter_and_cap() {
+        let backoff = Backoff {
+            base: Duration::from_millis(100),
+            max: Duration::from_secs(1),
+            max_attempts: 4,
+        };
+
+        assert_eq!(backoff.delay(0, 0.999_999, None).as_millis(), 99);
+        assert_eq!(backoff.delay(2, 0.0, None), Duration::from_millis(200));
+        assert_eq!(backoff.delay(2, 1.0, None), Duration::from_millis(400));
+        assert_eq!(backoff.delay(10, 1.0, None), Duration::from_secs(1));
+        assert_eq!(backoff.delay(40, 1.0, None), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn delay_honours_server_hint_up_to_cap() {
+        let backoff = Backoff::default();
+        let hint = Some(Duration::from_secs(5));
+        assert_eq!(backoff.delay(0, 0.0, hint), Duration::from_secs(5));
+        assert_eq!(
+            backoff.delay(0, 0.0, Some(Duration::from_secs(600))),
+            backoff.max
+        );
+    }
+
+    #[test]
+    fn jitter_is_a_unit_fraction() {
+        for _ in 0..100 {
+            let j = jitter();
+            assert!((0.0..1.0).contains(&j));
+        }
+    }
+
+    #[test]
+    fn retry_after_reads_error_body_fields() {
+        let value = serde_json::json!({ "retry_after": 3 });
+        assert_eq!(retry_after_field(&value), Some(Duration::from_secs(3)));
+        let value = serde_json::json!({ "retryAfter": "1.5" });
+        assert_eq!(retry_after_field(&value), Some(Duration::from_millis(1500)));
+        let value = serde_json::json!({ "message": "slow down" });
+        assert_eq!(retry_after_field(&value), None);
+    }
+
+    #[test]
+    fn retry_after_reads_http_error_body() {
+        let err = TransportErrorKind::http_error(429, r#"{"retry_after":2}"#.to_string());
+        assert_eq!(retry_after(&err), Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn gap_queue_schedules_and_resolves() {
+        let backoff = Backoff {
+            base: Duration::from_secs(1),
+            max: Duration::from_secs(60),
+            max_attempts: 2,
+        };
+        let mut gaps = GapQueue::new(backoff, 8);
+        let now = Instant::now();
+
+        gaps.record_failure(7, now, 0.0);
+        gaps.record_failure(5, now, 0.0);
+        assert!(gaps.due(now).is_empty());
+        assert_eq!(gaps.due(now + Duration::from_secs(2)), vec![5, 7]);
+
+        gaps.record_failure(5, now, 0.0);
+        assert_eq!(gaps.due(now + Duration::from_secs(2)), vec![7]);
+
+        gaps.resolve(7);
+        gaps.rewind(5);
+        assert!(gaps.is_empty());
+    }
+
+    #[test]
+    fn gap_queue_drops_oldest_when_full() {
+        let mut gaps = GapQueue::new(Backoff::default(), 2);
+        let now = Instant::now();
+        assert_eq!(gaps.record_failure(10, now, 0.0), None);
+        assert_eq!(gaps.record_failure(11, now, 0.0), None);
+        assert_eq!(gaps.record_failure(12, now, 0.0), Some(10));
+        let later = now + Duration::from_secs(3600);
+        assert_eq!(gaps.due(later), vec![11, 12]);
+    }
+}
@@ -0,0 +1,332 @@
+//! Offline function-selector database for naming and decoding calldata.
+//!
+//! A bundled list of common signatures (ERC-20/721/1155, WETH, Uniswap
+//! routers, Safe, ERC-4337, ...) is hashed into selectors at startup. Users can
+//! add their own from a text file with one signature per line:
+//!
+//! ```text
+//! # comments and blank lines are ignored
+//! transfer(address to, uint256 amount)
+//! function mint(address to, uint256 id)
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use alloy::dyn_abi::{DynSolType, DynSolValue, Specifier};
+use alloy::json_abi::Function;
+use alloy::primitives::Selector;
+
+/// Calldata words kept after the selector. Enough to decode the static
+/// arguments of most calls without bloating payloads and fixtures.
+pub const MAX_ARG_WORDS: usize = 8;
+
+/// Signatures shipped with the explorer.
+const BUNDLED_SIGNATURES: &[&str] = &[
+    // ERC-20 and WETH
+    "transfer(address to, uint256 amount)",
+    "approve(address spender, uint256 amount)",
+    "transferFrom(address from, address to, uint256 amount)",
+    "increaseAllowance(address spender, uint256 addedValue)",
+    "permit(address owner, address spender, uint256 value, uint256 deadline, uint8 v, bytes32 r, bytes32 s)",
+    "deposit()",
+    "withdraw(uint256 amount)",
+    // ERC-721 and ERC-1155
+    "safeTransferFrom(address from, address to, uint256 tokenId)",
+    "safeTransferFrom(address from, address to, uint256 tokenId, bytes data)",
+    "safeTransferFrom(address from, address to, uint256 id, uint256 amount, bytes data)",
+    "safeBatchTransferFrom(address from, address to, uint256[] ids, uint256[] amounts, bytes data)",
+    "setApprovalForAll(address operator, bool approved)",
+    "mint(address to, uint256 amount)",
+    "burn(uint256 amount)",
+    // Uniswap V2 router
+    "swapExactTokensForTokens(uint256 amountIn, uint256 amountOutMin, address[] path, address to, uint256 deadline)",
+    "swapTokensForExactTokens(uint256 amountOut, uint256 amountInMax, address[] path, address to, uint256 deadline)",
+    "swapExactETHForTokens(uint256 amountOutMin, address[] path, address to, uint256 deadline)",
+    "swapETHForExactTokens(uint256 amountOut, address[] path, address to, uint256 deadline)",
+    "swapExactTokensForETH(uint256 amountIn, uint256 amountOutMin, address[] path, address to, uint256 deadline)",
+    "swapTokensForExactETH(uint256 amountOut, uint256 amountInMax, address[] path, address to, uint256 deadline)",
+    "swapExactTokensForTokensSupportingFeeOnTransferTokens(uint256 amountIn, uint256 amountOutMin, address[] path, address to, uint256 deadline)",
+    "swapExactETHForTokensSupportingFeeOnTransferTokens(uint256 amountOutMin, address[] path, address to, uint256 deadline)",
+    "swapExactTokensForETHSupportingFeeOnTransferTokens(uint256 amountIn, uint256 amountOutMin, address[] path, address to, uint256 deadline)",
+    "addLiquidity(address tokenA, address tokenB, uint256 amountADesired, uint256 amountBDesired, uint256 amountAMin, uint256 amountBMin, address to, uint256 deadline)",
+    "addLiquidityETH(address token, uint256 amountTokenDesired, uint256 amountTokenMin, uint256 amountETHMin, address to, uint256 deadline)",
+    "removeLiquidity(address tokenA, address tokenB, uint256 liquidity, uint256 amountAMin, uint256 amountBMin, address to, uint256 deadline)",
+    "removeLiquidityETH(address token, uint256 liquidity, uint256 amountTokenMin, uint256 amountETHMin, address to, uint256 deadline)",
+    // Uniswap V3 routers
+    "exactInputSingle((address,address,uint24,address,uint256,uint256,uint256,uint160) params)",
+    "exactInputSingle((address,address,uint24,address,uint256,uint256,uint160) params)",
+    "exactInput((bytes,address,uint256,uint256,uint256) params)",
+    "exactInput((bytes,address,uint256,uint256) params)",
+    "exactOutputSingle((address,address,uint24,address,uint256,uint256,uint256,uint160) params)",
+    "exactOutput((bytes,address,uint256,uint256,uint256) params)",
+    "multicall(bytes[] data)",
+    "multicall(uint256 deadline, bytes[] data)",
+    "unwrapWETH9(uint256 amountMinimum, address recipient)",
+    "refundETH()",
+    // Uniswap Universal Router
+    "execute(bytes commands, bytes[] inputs, uint256 deadline)",
+    "execute(bytes commands, bytes[] inputs)",
+    // 1inch v5
+    "swap(address executor, (address,address,address,address,uint256,uint256,uint256) desc, bytes permit, bytes data)",
+    // Safe
+    "execTransaction(address to, uint256 value, bytes data, uint8 operation, uint256 safeTxGas, uint256 baseGas, uint256 gasPrice, address gasToken, address refundReceiver, bytes signatures)",
+    // ERC-4337 EntryPoint v0.6 and v0.7
+    "handleOps((address,uint256,bytes,bytes,uint256,uint256,uint256,uint256,uint256,bytes,bytes)[] ops, address beneficiary)",
+    "handleOps((address,uint256,bytes,bytes,bytes32,uint256,bytes32,bytes,bytes)[] ops, address beneficiary)",
+    // OP Stack bridge
+    "depositTransaction(address to, uint256 value, uint64 gasLimit, bool isCreation, bytes data)",
+    "bridgeETHTo(address to, uint32 minGasLimit, bytes extraData)",
+    "depositETH(uint32 minGasLimit, bytes extraData)",
+];
+
+/// Known functions by selector. Cloning is cheap; the table is shared.
+#[derive(bevy::prelude::Resource, Clone, Debug)]
+pub struct SelectorDb {
+    functions: Arc<HashMap<Selector, Function>>,
+}
+
+/// A call named and (partly) decoded from its selector and leading words.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedCall {
+    pub name: String,
+    /// Argument name (or type, when unnamed) and value. Arguments past the
+    /// kept words, or behind a dynamic offset there, read `…`.
+    pub args: Vec<(String, String)>,
+}
+
+impl Default for SelectorDb {
+    fn default() -> Self {
+        Self::bundled()
+    }
+}
+
+impl SelectorDb {
+    /// An empty database.
+    pub fn empty() -> Self {
+        Self {
+            functions: Arc::new(HashMap::new()),
+        }
+    }
+
+    /// The signatures shipped with the explorer.
+    pub fn bundled() -> Self {
+        let mut db = Self::empty();
+        for signature in BUNDLED_SIGNATURES {
+            db.add(signature)
+                .unwrap_or_else(|e| panic!("bad bundled signature {signature}: {e}"));
+        }
+        db
+    }
+
+    /// Adds a signature such as `transfer(address to, uint256 amount)`,
+    /// replacing any function with the same selector.
+    pub fn add(&mut self, signature: &str) -> Result<Selector, alloy::json_abi::parser::Error> {
+        let function = Function::parse(signature)?;
+        let selector = function.selector();
+        Arc::make_mut(&mut self.functions).insert(selector, function);
+        Ok(selector)
+    }
+
+    /// Adds every signature in a file (one per line, `#` comments). Lines
+    /// that don't parse are logged and skipped. Returns how many were added.
+    pub fn extend_from_file(&mut self, path: &Path) -> std::io::Result<usize> {
+        let text = std::fs::read_to_string(path)?;
+        let mut added = 0;
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match self.add(line) {
+                Ok(_) => added += 1,
+                Err(e) => eprintln!(
+                    "tessera: skipping signature {line:?} in {}: {e}",
+                    path.display()
+                ),
+            }
+        }
+        Ok(added)
+    }
+
+    pub fn len(&self) -> usize {
+        self.functions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+
+    /// Function name for a selector.
+    pub fn name(&self, selector: &Selector) -> Option<&str> {
+        self.functions.get(selector).map(|f| f.name.as_str())
+    }
+
+    /// Names the call and decodes what `args` (the calldata after the
+    /// selector, possibly truncated) holds of its arguments.
+    pub fn decode(&self, selector: &Selector, args: &[u8]) -> Option<DecodedCall> {
+        let function = self.functions.get(selector)?;
+        let types: Vec<DynSolType> = function
+            .inputs
+            .iter()
+            .map(|param| param.resolve().ok())
+            .collect::<Option<_>>()?;
+        let label = |i: usize| {
+            let param = &function.inputs[i];
+            if param.name.is_empty() {
+                types[i].sol_type_name().into_owned()
+            } else {
+                param.name.clone()
+            }
+        };
+
+        // Complete calldata decodes in one go; otherwise read each static
+        // argument from its head slot while the kept words last.
+        let values: Vec<Option<DynSolValue>> =
+            match DynSolType::Tuple(types.clone()).abi_decode_sequence(args) {
+                Ok(DynSolValue::Tuple(values)) => values.into_iter().map(Some).collect(),
+                _ => {
+                    let mut offset = 0;
+                    types
+                        .iter()
+                        .map(|ty| {
+                            let words = if ty.is_dynamic() {
+                                1
+                            } else {
+                                ty.minimum_words()
+                            };
+                            let head = args.get(offset..offset + words * 32);
+                            offset += words * 32;
+                            head.filter(|_| !ty.is_dynamic())
+                                .and_then(|head| ty.abi_decode(head).ok())
+                        })
+                        .collect()
+                }
+            };
+
+        Some(DecodedCall {
+            name: function.name.clone(),
+            args: values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| {
+                    let shown = value.as_ref().map_or_else(|| "…".to_string(), format_value);
+                    (label(i), shown)
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Compact display form of a decoded value.
+fn format_value(value: &DynSolValue) -> String {
+    match value {
+        DynSolValue::Address(address) => address.to_string(),
+        DynSolValue::Bool(b) => b.to_string(),
+        DynSolValue::Uint(n, _) => n.to_string(),
+        DynSolValue::Int(n, _) => n.to_string(),
+        DynSolValue::FixedBytes(word, size) => format!("0x{}", hex(&word[..*size])),
+        DynSolValue::Bytes(bytes) => format!("{} bytes", bytes.len()),
+        DynSolValue::String(s) => format!("{s:?}"),
+        DynSolValue::Array(items) | DynSolValue::FixedArray(items) => {
+            let items: Vec<String> = items.iter().map(format_value).collect();
+            format!("[{}]", items.join(", "))
+        }
+        DynSolValue::Tuple(items) => {
+            let items: Vec<String> = items.iter().map(format_value).collect();
+            format!("({})", items.join(", "))
+        }
+        DynSolValue::Function(function) => format!("0x{}", hex(function.as_slice())),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{address, Address, U256};
+
+    use super::*;
+
+    fn word(bytes: &[u8]) -> [u8; 32] {
+        let mut word = [0u8; 32];
+        word[32 - bytes.len()..].copy_from_slice(bytes);
+        word
+    }
+
+    #[test]
+    fn bundled_signatures_hash_to_known_selectors() {
+        let db = SelectorDb::bundled();
+        assert_eq!(db.len(), BUNDLED_SIGNATURES.len());
+        assert_eq!(
+            db.name(&Selector::from([0xa9, 0x05, 0x9c, 0xbb])),
+            Some("transfer")
+        );
+        assert_eq!(
+            db.name(&Selector::from([0x09, 0x5e, 0xa7, 0xb3])),
+            Some("approve")
+        );
+        assert_eq!(
+            db.name(&Selector::from([0x35, 0x93, 0x56, 0x4c])),
+            Some("execute")
+        );
+    }
+
+    #[test]
+    fn decodes_complete_calldata() {
+        let to = address!("00000000000000000000000000000000000000bb");
+        let mut args = word(to.as_slice()).to_vec();
+        args.extend(U256::from(1_000u64).to_be_bytes::<32>());
+
+        let call = SelectorDb::bundled()
+            .decode(&Selector::from([0xa9, 0x05, 0x9c, 0xbb]), &args)
+            .unwrap();
+        assert_eq!(call.name, "transfer");
+        assert_eq!(
+            call.args,
+            vec![
+                ("to".to_string(), to.to_string()),
+                ("amount".to_string(), "1000".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn truncated_calldata_decodes_static_heads() {
+        let mut db = SelectorDb::empty();
+        let selector = db
+            .add("route(uint256 amountIn, address[] path, address to)")
+            .unwrap();
+        // amountIn, the path offset and `to`, but not the path itself.
+        let mut args = U256::from(5u64).to_be_bytes::<32>().to_vec();
+        args.extend(word(&[0x60]));
+        args.extend(word(Address::with_last_byte(0xcc).as_slice()));
+
+        let call = db.decode(&selector, &args).unwrap();
+        let values: Vec<&str> = call.args.iter().map(|(_, v)| v.as_str()).collect();
+        assert_eq!(values[0], "5");
+        assert_eq!(values[1], "…");
+        assert_eq!(values[2], Address::with_last_byte(0xcc).to_string());
+    }
+
+    #[test]
+    fn user_file_extends_and_skips_bad_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("selectors.txt");
+        std::fs::write(
+            &path,
+            "# custom\n\nfunction harvest(uint256 pid)\nnot a signature(\n",
+        )
+        .unwrap();
+
+        let mut db = SelectorDb::empty();
+        let added = db.extend_from_file(&path).unwrap();
+
+        assert_eq!(added, 1);
+        let selector = Function::parse("harvest(uint256)").unwrap().selector();
+        assert_eq!(db.name(&selector), Some("harvest"));
+    }
+}
@@ -1,4 +1,775 @@
-// Phase 4: Solana fetcher
-// Future: solana-client → BlockPayload
-#[allow(dead_code)]
-fn _placeholder() {}
+//! Solana block fetcher: dedicated thread + JSON-RPC `getSlot`/`getBlock` → BlockPayload.
+//!
+//! Slots map onto the payload and transaction envelope as follows:
+//! - slot number → block number, blockhash / previous blockhash → hash / parent hash;
+//! - compute units consumed → resource used, against the block compute unit limit;
+//! - the priority fee per compute unit → price (in micro-lamports), and the
+//!   total fee → fee paid (in lamports);
+//! - SOL moved out of the fee payer → value, in lamports.
+//!
+//! Solana keys and signatures are longer than EVM addresses and hashes; the
+//! envelope keeps their leading 20 and 32 bytes for grouping and the Solana
+//! addendum keeps them in full. Skipped slots are never delivered, so the
+//! lane shows them as gaps; slots that fail to fetch are queued and retried
+//! in the background like missing EVM blocks.
+
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use alloy::primitives::{keccak256, Address, B256, U256};
+use alloy::rpc::client::{ClientBuilder, RpcClient};
+use alloy::transports::{TransportError, TransportErrorKind};
+use alloy_chains::Chain;
+use crossbeam_channel::{Receiver, Sender};
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::data::endpoints::{endpoint_label, Endpoints};
+use crate::data::envelope::{SolanaAddendum, TransactionEnvelope};
+use crate::data::model::BlockPayload;
+use crate::data::retry::{jitter, with_retries, Backoff, GapQueue};
+use crate::data::status::{
+    ConnectionState, FetcherStatus, StatusReporter, STATUS_CHANNEL_CAPACITY,
+};
+use crate::data::{BlockStart, ChainFetcher, FetcherConfig};
+
+/// Lane identity for Solana mainnet-beta. Solana has no EIP-155 chain ID, so
+/// this is its cluster ID (101, as in the Solana token list) with the top bit
+/// set. That is above the largest chain ID EIP-2294 allows, so no EVM chain can
+/// share the lane.
+pub const SOLANA_MAINNET: Chain = Chain::from_id_unchecked((1 << 63) | 101);
+
+/// Compute units a block may consume, used as its "gas limit".
+const BLOCK_COMPUTE_UNIT_LIMIT: u64 = 48_000_000;
+/// Lamports charged per signature before any priority fee.
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+const BACKFILL_SLOTS: u64 = 20;
+/// Largest number of slots fetched per poll. Further behind than this, the
+/// fetcher jumps ahead instead of lagging the cluster indefinitely.
+const MAX_CATCH_UP_SLOTS: u64 = 50;
+const POLL_INTERVAL: Duration = Duration::from_millis(800);
+
+/// Slot was skipped, or skipped before reaching long-term storage.
+const SLOT_SKIPPED_CODES: [i64; 2] = [-32007, -32009];
+
+const COMPUTE_BUDGET_PROGRAM: &str = "ComputeBudget111111111111111111111111111111";
+
+/// Returns true if the chain is the Solana lane.
+pub fn is_solana(chain: &Chain) -> bool {
+    *chain == SOLANA_MAINNET
+}
+
+/// Solana block fetcher using plain JSON-RPC.
+///
+/// Polls `getSlot` at `confirmed` commitment and fetches each new slot with
+/// `getBlock`. Several endpoints in the config are used for failover; other
+/// strategies behave like failover here. Starting at a timestamp isn't
+/// supported and falls back to the latest slots.
+pub struct SolanaFetcher {
+    config: FetcherConfig,
+}
+
+impl SolanaFetcher {
+    pub fn new(config: FetcherConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &FetcherConfig {
+        &self.config
+    }
+}
+
+impl ChainFetcher for SolanaFetcher {
+    fn spawn(self: Box<Self>) -> Receiver<BlockPayload> {
+        self.spawn_with_status().0
+    }
+
+    fn spawn_with_status(self: Box<Self>) -> (Receiver<BlockPayload>, Receiver<FetcherStatus>) {
+        let config = self.config;
+        let (tx, rx) = crossbeam_channel::bounded(64);
+        let (status_tx, status_rx) = crossbeam_channel::bounded(STATUS_CHANNEL_CAPACITY);
+        let status = StatusReporter::new(config.chain, status_tx);
+        thread::spawn(move || {
+            let mut status = status;
+            let rt = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(rt) => rt,
+                Err(err) => {
+                    eprintln!("tessera: failed to build tokio runtime: {err}");
+                    status.record_failure(format!("tokio runtime: {err}"));
+                    return;
+                }
+            };
+            rt.block_on(fetcher_loop(config, tx, status));
+        });
+        (rx, status_rx)
+    }
+}
+
+async fn fetcher_loop(config: FetcherConfig, tx: Sender<BlockPayload>, mut status: StatusReporter) {
+    let chain = config.chain;
+    let mut clients = Vec::new();
+    let mut last_error = String::new();
+    for url in config.rpc_urls() {
+        match ClientBuilder::default().connect(url.as_str()).await {
+            Ok(client) => clients.push((url.clone(), client)),
+            Err(err) => {
+                let label = endpoint_label(url);
+                eprintln!("tessera [{chain}]: failed to connect to RPC {label}: {err}");
+                last_error = format!("connect {label}: {err}");
+            }
+        }
+    }
+    if clients.is_empty() {
+        status.record_failure(last_error);
+        return;
+    }
+
+    let mut fetcher = SlotFetcher {
+        endpoints: Endpoints::new(clients, config.strategy),
+        chain,
+        tx,
+        backoff: Backoff::default(),
+        concurrency: config.concurrency.max(1),
+        stop_at: config.to,
+        gaps: GapQueue::default(),
+        status,
+    };
+    fetcher
+        .status
+        .set_endpoint(fetcher.endpoints.active_label());
+
+    let Ok(tip) = fetcher.latest_slot().await else {
+        fetcher.status.record_failure("getSlot failed");
+        return;
+    };
+    fetcher.status.set_tip(tip);
+    fetcher.status.set_state(ConnectionState::Syncing);
+
+    let end = fetcher.clamp_to_end(tip);
+    let start = match config.from {
+        Some(BlockStart::Number(slot)) => slot,
+        Some(BlockStart::Timestamp(_)) => {
+            eprintln!("tessera [{chain}]: start-at-timestamp isn't supported for Solana, starting near the tip");
+            end.saturating_sub(BACKFILL_SLOTS - 1)
+        }
+        None => end.saturating_sub(BACKFILL_SLOTS - 1),
+    };
+    eprintln!("tessera [{chain}]: backfilling slots {start}..={end}");
+    if fetcher.fetch_slots(start, end).await.is_err() {
+        return;
+    }
+
+    let mut last_seen = end.max(start.saturating_sub(1));
+    if !fetcher.reached_end(last_seen) {
+        eprintln!("tessera [{chain}]: backfill complete, polling for new slots");
+        fetcher.status.set_state(ConnectionState::Live);
+    }
+
+    loop {
+        if fetcher.reached_end(last_seen) && fetcher.gaps.is_empty() {
+            eprintln!("tessera [{chain}]: range complete");
+            fetcher.status.set_state(ConnectionState::Finished);
+            return;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+
+        if fetcher.retry_gaps().await.is_err() {
+            return;
+        }
+        if fetcher.reached_end(last_seen) {
+            continue;
+        }
+
+        let Ok(tip) = fetcher.latest_slot().await else {
+            continue;
+        };
+        let tip = fetcher.clamp_to_end(tip);
+        fetcher.status.set_tip(tip);
+        if tip <= last_seen {
+            continue;
+        }
+        let mut start = last_seen + 1;
+        if tip - last_seen > MAX_CATCH_UP_SLOTS && fetcher.stop_at.is_none() {
+            start = tip - (MAX_CATCH_UP_SLOTS - 1);
+            eprintln!(
+                "tessera [{chain}]: {} slots behind, skipping ahead to {start}",
+                tip - last_seen
+            );
+        }
+        if fetcher.fetch_slots(start, tip).await.is_err() {
+            return;
+        }
+        last_seen = tip;
+    }
+}
+
+/// Per-cluster fetch state. Methods returning `Result<(), ()>` fail only when
+/// the receiver is gone.
+struct SlotFetcher {
+    endpoints: Endpoints<RpcClient>,
+    chain: Chain,
+    tx: Sender<BlockPayload>,
+    backoff: Backoff,
+    /// `getBlock` requests in flight while catching up.
+    concurrency: usize,
+    /// Last slot of a historical range; nothing past it is fetched.
+    stop_at: Option<u64>,
+    /// Slots that failed every attempt and are re-fetched in the background.
+    gaps: GapQueue,
+    status: StatusReporter,
+}
+
+impl SlotFetcher {
+    fn reached_end(&self, last_seen: u64) -> bool {
+        self.stop_at.is_some_and(|stop| last_seen >= stop)
+    }
+
+    fn clamp_to_end(&self, slot: u64) -> u64 {
+        self.stop_at.map_or(slot, |stop| slot.min(stop))
+    }
+
+    async fn latest_slot(&mut self) -> Result<u64, TransportError> {
+        let result = with_retries(
+            &self.endpoints,
+            &self.backoff,
+            &mut self.status,
+            "getSlot",
+            self.backoff.max_attempts,
+            |client| client.request("getSlot", (json!({ "commitment": "confirmed" }),)),
+        )
+        .await;
+        if let Err(err) = &result {
+            eprintln!("tessera [{}]: getSlot failed: {err}", self.chain);
+            self.status.record_retry(format!("getSlot: {err}"));
+        }
+        result
+    }
+
+    /// Fetches and sends slots `start..=end` in order, with up to
+    /// `concurrency` requests in flight. Slots whose first request fails are
+    /// retried one by one, then queued as gaps.
+    async fn fetch_slots(&mut self, start: u64, end: u64) -> Result<(), ()> {
+        let client = self.endpoints.active().clone();
+        let chain = self.chain;
+        let mut slots = futures::stream::iter(start..=end)
+            .map(|slot| {
+                let client = &client;
+                async move {
+                    let started = Instant::now();
+                    let result = get_block(client, slot).await;
+                    (slot, started.elapsed(), result)
+                }
+            })
+            .buffered(self.concurrency);
+
+        while let Some((slot, latency, result)) = slots.next().await {
+            let outcome = match result {
+                Ok(block) => {
+                    self.status.record_success(latency);
+                    Ok(Some(block))
+                }
+                Err(err) => match slot_outcome(&err) {
+                    Some(outcome) => Ok(outcome),
+                    None => self.fetch_slot(slot, self.backoff.max_attempts).await,
+                },
+            };
+            // Skipped slots are left out; the lane shows them as gaps.
+            match outcome {
+                Ok(Some(block)) => self.send(block_to_payload(chain, slot, block))?,
+                Ok(None) => {}
+                Err(()) => self.record_gap(slot),
+            }
+        }
+        Ok(())
+    }
+
+    /// Queues a slot that couldn't be fetched for a later retry.
+    fn record_gap(&mut self, slot: u64) {
+        if let Some(dropped) = self.gaps.record_failure(slot, Instant::now(), jitter()) {
+            eprintln!(
+                "tessera [{}]: giving up on slot {dropped}, too many pending gaps",
+                self.chain
+            );
+        }
+        self.status.set_pending_gaps(self.gaps.len());
+    }
+
+    /// Re-fetches queued slots whose backoff has elapsed, one attempt each.
+    async fn retry_gaps(&mut self) -> Result<(), ()> {
+        for slot in self.gaps.due(Instant::now()) {
+            let Ok(block) = self.fetch_slot(slot, 1).await else {
+                self.record_gap(slot);
+                continue;
+            };
+            self.gaps.resolve(slot);
+            self.status.set_pending_gaps(self.gaps.len());
+            if let Some(block) = block {
+                eprintln!("tessera [{}]: filled gap at slot {slot}", self.chain);
+                self.send(block_to_payload(self.chain, slot, block))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetches one slot, making up to `attempts` attempts. `Ok(None)` means
+    /// the slot was skipped.
+    async fn fetch_slot(&mut self, slot: u64, attempts: u32) -> Result<Option<SolanaBlock>, ()> {
+        let result = with_retries(
+            &self.endpoints,
+            &self.backoff,
+            &mut self.status,
+            &format!("slot {slot}"),
+            attempts,
+            |client| async move {
+                match get_block(client, slot).await {
+                    Ok(block) => Ok(Some(block)),
+                    Err(err) => match slot_outcome(&err) {
+                        Some(outcome) => Ok(outcome),
+                        None => Err(err),
+                    },
+                }
+            },
+        )
+        .await;
+        result.map_err(|err| {
+            eprintln!(
+                "tessera [{}]: failed to fetch slot {slot}: {err}",
+                self.chain
+            );
+        })
+    }
+
+    fn send(&mut self, payload: BlockPayload) -> Result<(), ()> {
+        eprintln!(
+            "tessera [{}]: slot {} ({} txs, {} CU)",
+            self.chain, payload.number, payload.tx_count, payload.gas_used
+        );
+        self.status
+            .record_delivered(payload.number, self.gaps.len());
+        self.tx.send(payload).map_err(|_| ())
+    }
+}
+
+async fn get_block(client: &RpcClient, slot: u64) -> Result<SolanaBlock, TransportError> {
+    let config = json!({
+        "encoding": "json",
+        "transactionDetails": "full",
+        "rewards": false,
+        "commitment": "confirmed",
+        "maxSupportedTransactionVersion": 0,
+    });
+    let block: Option<SolanaBlock> = client.request("getBlock", (slot, config)).await?;
+    block.ok_or_else(|| TransportErrorKind::custom_str("block not available"))
+}
+
+/// Classifies a `getBlock` error: `Some(None)` for a skipped slot, `None` for
+/// a failure worth retrying.
+fn slot_outcome<T>(err: &TransportError) -> Option<Option<T>> {
+    let code = err.as_error_resp()?.code;
+    SLOT_SKIPPED_CODES.contains(&code).then_some(None)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SolanaBlock {
+    blockhash: String,
+    previous_blockhash: String,
+    block_time: Option<u64>,
+    #[serde(default)]
+    transactions: Vec<SolanaTransaction>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SolanaTransaction {
+    transaction: TransactionBody,
+    meta: Option<TransactionMeta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TransactionBody {
+    signatures: Vec<String>,
+    message: Message,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Message {
+    account_keys: Vec<String>,
+    #[serde(default)]
+    instructions: Vec<Instruction>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Instruction {
+    program_id_index: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionMeta {
+    fee: u64,
+    err: Option<serde_json::Value>,
+    compute_units_consumed: Option<u64>,
+    #[serde(default)]
+    pre_balances: Vec<u64>,
+    #[serde(default)]
+    post_balances: Vec<u64>,
+    log_messages: Option<Vec<String>>,
+}
+
+fn block_to_payload(chain: Chain, slot: u64, block: SolanaBlock) -> BlockPayload {
+    let transactions: Vec<TransactionEnvelope> = block
+        .transactions
+        .iter()
+        .enumerate()
+        .map(|(i, tx)| tx_to_envelope(i, tx))
+        .collect();
+    let timestamp = block.block_time.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs())
+    });
+
+    BlockPayload {
+        chain,
+        number: slot,
+        hash: decode_b256(&block.blockhash),
+        parent_hash: decode_b256(&block.previous_blockhash),
+        gas_used: transactions.iter().map(|tx| tx.resource_limit).sum(),
+        gas_limit: BLOCK_COMPUTE_UNIT_LIMIT,
+        timestamp,
+        tx_count: transactions.len() as u32,
+        base_fee_per_gas: None,
+        blob_gas_used: None,
+        fee_recipient: None,
+        extra_data: Default::default(),
+        transactions,
+        l1_origin_number: None,
+        reorg: None,
+    }
+}
+
+fn tx_to_envelope(index: usize, tx: &SolanaTransaction) -> TransactionEnvelope {
+    let message = &tx.transaction.message;
+    let signatures = tx.transaction.signatures.len() as u64;
+    let signature = tx.transaction.signatures.first();
+    let fee_payer = message.account_keys.first();
+    // The invoked program stands in for the recipient, skipping compute budget setup.
+    let program = message
+        .instructions
+        .iter()
+        .filter_map(|ix| message.account_keys.get(ix.program_id_index))
+        .find(|key| key.as_str() != COMPUTE_BUDGET_PROGRAM);
+
+    let mut envelope = TransactionEnvelope {
+        id: signature.map_or(B256::ZERO, |sig| decode_b256(sig)),
+        index,
+        resource_limit: 0,
+        resource_used: None,
+        price: 0,
+        fee_paid: None,
+        value: U256::ZERO,
+        sender: fee_payer.map_or(Address::ZERO, |key| decode_address(key)),
+        recipient: program.map(|key| decode_address(key)),
+        success: None,
+        evm: None,
+        op_stack: None,
+        solana: None,
+    };
+    let mut addendum = SolanaAddendum {
+        signature: signature.cloned().unwrap_or_default(),
+        fee_payer: fee_payer.cloned().unwrap_or_default(),
+        program: program.cloned(),
+        ..Default::default()
+    };
+
+    if let Some(meta) = &tx.meta {
+        let compute_units = meta.compute_units_consumed.unwrap_or(0);
+        let priority_fee = meta.fee.saturating_sub(signatures * LAMPORTS_PER_SIGNATURE);
+        // A failed transaction still pays its fee but moves nothing.
+        let moved = match (meta.pre_balances.first(), meta.post_balances.first()) {
+            (Some(pre), Some(post)) if meta.err.is_none() => {
+                pre.saturating_sub(*post).saturating_sub(meta.fee)
+            }
+            _ => 0,
+        };
+        // Solana charges no fee for unused compute, so the limit shown is
+        // what was consumed.
+        envelope.resource_limit = compute_units;
+        envelope.resource_used = Some(compute_units);
+        envelope.price = micro_lamports_per_cu(priority_fee, compute_units);
+        envelope.fee_paid = Some(u128::from(meta.fee));
+        envelope.value = U256::from(moved);
+        envelope.success = Some(meta.err.is_none());
+        addendum.priority_fee = priority_fee;
+        addendum.log_messages = meta.log_messages.as_ref().map_or(0, Vec::len);
+    }
+
+    envelope.solana = Some(addendum);
+    envelope
+}
+
+/// Fee spread over the compute units consumed, in micro-lamports per CU (the
+/// unit priority fees are bid in).
+fn micro_lamports_per_cu(lamports: u64, compute_units: u64) -> u128 {
+    if compute_units == 0 {
+        return 0;
+    }
+    u128::from(lamports) * 1_000_000 / u128::from(compute_units)
+}
+
+/// Leading 32 bytes of a base58 hash or signature.
+fn decode_b256(encoded: &str) -> B256 {
+    let bytes = bs58::decode(encoded).into_vec().unwrap_or_default();
+    let mut out = [0u8; 32];
+    let len = bytes.len().min(32);
+    out[..len].copy_from_slice(&bytes[..len]);
+    B256::from(out)
+}
+
+/// Stand-in 20-byte address for a base58 public key: the last 20 bytes of
+/// the key's Keccak-256 hash, as EVM addresses are derived. The mapping is
+/// lossy and only groups transactions by key; the full key stays in the
+/// [`SolanaAddendum`] for display.
+fn decode_address(encoded: &str) -> Address {
+    Address::from_slice(&keccak256(decode_b256(encoded))[12..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `getBlock` response in mainnet-beta's shape (`json` encoding, no
+    /// rewards), trimmed to a vote, a transfer that failed after paying a
+    /// priority fee, and a plain transfer. Balances, fees and compute units
+    /// agree with each other the way the cluster reports them.
+    const RECORDED_BLOCK: &str = r#"{
+        "blockHeight": 290113532,
+        "blockTime": 1727712000,
+        "blockhash": "3Eq21vXNB5s86c62bVuUfTeaMif1N2kUqRPBmGRJhyTA",
+        "parentSlot": 291700019,
+        "previousBlockhash": "mfcyqEXB3DnHXki6KjjmZck6YjmZLvpAByy2fj4nh6B",
+        "transactions": [
+            {
+                "meta": {
+                    "computeUnitsConsumed": 2100,
+                    "err": null,
+                    "fee": 5000,
+                    "innerInstructions": [],
+                    "logMessages": [
+                        "Program Vote111111111111111111111111111111111111111 invoke [1]",
+                        "Program Vote111111111111111111111111111111111111111 success"
+                    ],
+                    "postBalances": [999995000, 1],
+                    "postTokenBalances": [],
+                    "preBalances": [1000000000, 1],
+                    "preTokenBalances": [],
+                    "status": { "Ok": null }
+                },
+                "transaction": {
+                    "message": {
+                        "accountKeys": [
+                            "5ZiE3vAkrdXBgyFL7KqG3RoEGBws4CjRcXVbABDLZTgx",
+                            "Vote111111111111111111111111111111111111111"
+                        ],
+                        "header": {
+                            "numReadonlySignedAccounts": 0,
+                            "numReadonlyUnsignedAccounts": 1,
+                            "numRequiredSignatures": 1
+                        },
+                        "instructions": [{ "accounts": [0], "data": "", "programIdIndex": 1, "stackHeight": null }],
+                        "recentBlockhash": "mfcyqEXB3DnHXki6KjjmZck6YjmZLvpAByy2fj4nh6B"
+                    },
+                    "signatures": ["5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW"]
+                },
+                "version": "legacy"
+            },
+            {
+                "meta": {
+                    "computeUnitsConsumed": 450,
+                    "err": { "InstructionError": [1, { "Custom": 1 }] },
+                    "fee": 50000,
+                    "innerInstructions": [],
+                    "logMessages": [
+                        "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+                        "Program ComputeBudget111111111111111111111111111111 success",
+                        "Program 11111111111111111111111111111111 invoke [1]",
+                        "Transfer: insufficient lamports 1999950000, need 5000000000",
+                        "Program 11111111111111111111111111111111 failed: custom program error: 0x1"
+                    ],
+                    "postBalances": [1999950000, 0, 1, 1],
+                    "postTokenBalances": [],
+                    "preBalances": [2000000000, 0, 1, 1],
+                    "preTokenBalances": [],
+                    "status": { "Err": { "InstructionError": [1, { "Custom": 1 }] } }
+                },
+                "transaction": {
+                    "message": {
+                        "accountKeys": [
+                            "5ZiE3vAkrdXBgyFL7KqG3RoEGBws4CjRcXVbABDLZTgx",
+                            "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+                            "ComputeBudget111111111111111111111111111111",
+                            "11111111111111111111111111111111"
+                        ],
+                        "header": {
+                            "numReadonlySignedAccounts": 0,
+                            "numReadonlyUnsignedAccounts": 2,
+                            "numRequiredSignatures": 1
+                        },
+                        "instructions": [
+                            { "accounts": [], "data": "3gJqkocMWaMm", "programIdIndex": 2, "stackHeight": null },
+                            { "accounts": [0, 1], "data": "3Bxs4h24hBtQy9rw", "programIdIndex": 3, "stackHeight": null }
+                        ],
+                        "recentBlockhash": "mfcyqEXB3DnHXki6KjjmZck6YjmZLvpAByy2fj4nh6B"
+                    },
+                    "signatures": ["4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T5a8yTPxCFQxBHxHUNVYYA8XR8Hb5pNaEDiUF5JrkMVdf"]
+                },
+                "version": 0
+            },
+            {
+                "meta": {
+                    "computeUnitsConsumed": 150,
+                    "err": null,
+                    "fee": 5000,
+                    "innerInstructions": [],
+                    "logMessages": [
+                        "Program 11111111111111111111111111111111 invoke [1]",
+                        "Program 11111111111111111111111111111111 success"
+                    ],
+                    "postBalances": [2749995000, 250000000, 1],
+                    "postTokenBalances": [],
+                    "preBalances": [3000000000, 0, 1],
+                    "preTokenBalances": [],
+                    "status": { "Ok": null }
+                },
+                "transaction": {
+                    "message": {
+                        "accountKeys": [
+                            "FehDpXXpFcGRJSpZfh8QZZ2YJGLTd6C77hwgadPqxTrF",
+                            "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+                            "11111111111111111111111111111111"
+                        ],
+                        "header": {
+                            "numReadonlySignedAccounts": 0,
+                            "numReadonlyUnsignedAccounts": 1,
+                            "numRequiredSignatures": 1
+                        },
+                        "instructions": [{ "accounts": [0, 1], "data": "3Bxs4NN8M2Yn4TLb", "programIdIndex": 2, "stackHeight": null }],
+                        "recentBlockhash": "mfcyqEXB3DnHXki6KjjmZck6YjmZLvpAByy2fj4nh6B"
+                    },
+                    "signatures": ["35gmzGgGTYLYPLYsphkchNF56jpbHs6B6EhyNUWyKo88peWC8DneCHPg7Kghk1u8UipZCsDGuacWhNhgjgwATLpF"]
+                },
+                "version": "legacy"
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn recorded_block_maps_to_payload() {
+        let block: SolanaBlock = serde_json::from_str(RECORDED_BLOCK).expect("parse block");
+        let payload = block_to_payload(SOLANA_MAINNET, 291_700_020, block);
+
+        assert_eq!(payload.chain, SOLANA_MAINNET);
+        assert_eq!(payload.number, 291_700_020);
+        assert_eq!(payload.timestamp, 1_727_712_000);
+        assert_eq!(payload.tx_count, 3);
+        assert_eq!(payload.gas_used, 2_700);
+        assert_eq!(payload.gas_limit, BLOCK_COMPUTE_UNIT_LIMIT);
+        assert_ne!(payload.hash, B256::ZERO);
+        assert_ne!(payload.parent_hash, payload.hash);
+
+        let vote = &payload.transactions[0];
+        assert_eq!(vote.price, 0);
+        assert!(!vote.reverted());
+        assert!(vote.value.is_zero());
+
+        let failed = &payload.transactions[1];
+        assert_eq!(failed.resource_used_or_limit(), 450);
+        // 45,000 lamports of priority fee over 450 CU.
+        assert_eq!(failed.price, 100_000_000);
+        assert_eq!(failed.fee_paid, Some(50_000));
+        assert!(failed.reverted());
+        // Only the fee left the payer; the transfer itself didn't happen.
+        assert!(failed.value.is_zero());
+        assert_eq!(
+            failed.recipient,
+            Some(decode_address("11111111111111111111111111111111"))
+        );
+        assert_eq!(failed.sender, vote.sender);
+
+        let solana = failed.solana.as_ref().expect("Solana addendum");
+        assert_eq!(solana.priority_fee, 45_000);
+        assert_eq!(solana.log_messages, 5);
+        assert_eq!(
+            failed.recipient_display().as_deref(),
+            Some("11111111111111111111111111111111")
+        );
+        assert_eq!(
+            failed.sender_display(),
+            "5ZiE3vAkrdXBgyFL7KqG3RoEGBws4CjRcXVbABDLZTgx"
+        );
+
+        let transfer = &payload.transactions[2];
+        assert_eq!(transfer.resource_used_or_limit(), 150);
+        assert_eq!(transfer.price, 0);
+        assert_eq!(transfer.fee_paid, Some(5_000));
+        assert!(!transfer.reverted());
+        assert_eq!(transfer.value, U256::from(250_000_000));
+        assert_ne!(transfer.sender, vote.sender);
+    }
+
+    #[test]
+    fn failed_transactions_move_no_value() {
+        let mut block: SolanaBlock = serde_json::from_str(RECORDED_BLOCK).expect("parse block");
+        // Even if the payer's balance fell by more than the fee, a failed
+        // transaction reports no value moved.
+        let meta = block.transactions[1].meta.as_mut().unwrap();
+        meta.post_balances[0] = 1_900_000_000;
+        let payload = block_to_payload(SOLANA_MAINNET, 291_700_020, block);
+        assert!(payload.transactions[1].value.is_zero());
+    }
+
+    #[test]
+    fn solana_lane_is_outside_the_evm_chain_id_range() {
+        // EIP-2294 caps EVM chain IDs at floor(u64::MAX / 2) - 36.
+        assert!(SOLANA_MAINNET.id() > u64::MAX / 2 - 36);
+        assert!(!is_solana(&Chain::from_id(101)));
+    }
+
+    #[test]
+    fn skipped_slot_errors_are_not_retried() {
+        let skipped = TransportError::ErrorResp(
+            serde_json::from_str(
+                r#"{"code":-32007,"message":"Slot 291700021 was skipped, or missing due to ledger jump to recent snapshot"}"#,
+            )
+            .unwrap(),
+        );
+        assert!(matches!(slot_outcome::<()>(&skipped), Some(None)));
+
+        let unavailable = TransportError::ErrorResp(
+            serde_json::from_str(
+                r#"{"code":-32004,"message":"Block not available for slot 291700022"}"#,
+            )
+            .unwrap(),
+        );
+        assert!(slot_outcome::<()>(&unavailable).is_none());
+    }
+
+    #[test]
+    fn base58_keys_hash_to_stand_in_addresses() {
+        // The System Program's key is all zeroes; its stand-in must not be
+        // the zero address, or every transfer would cluster under it.
+        let system_program = decode_address("11111111111111111111111111111111");
+        assert_ne!(system_program, Address::ZERO);
+        assert_ne!(decode_address(COMPUTE_BUDGET_PROGRAM), system_program);
+        let hash = decode_b256("3Eq21vXNB5s86c62bVuUfTeaMif1N2kUqRPBmGRJhyTA");
+        assert_ne!(hash, B256::ZERO);
+        assert_eq!(decode_b256("not base58 0OIl"), B256::ZERO);
+    }
+}
@@ -0,0 +1,263 @@
+//! Fetcher health reporting: connection state, errors, lag and latency.
+
+use std::fmt;
+use std::time::Duration;
+
+use alloy_chains::Chain;
+use crossbeam_channel::Sender;
+
+/// Capacity of each fetcher's status channel. Updates are dropped rather
+/// than blocking the fetcher when nobody drains them.
+pub const STATUS_CHANNEL_CAPACITY: usize = 64;
+
+/// What a fetcher is currently doing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// Connecting to the RPC endpoint.
+    Connecting,
+    /// Backfilling the initial block range.
+    Syncing,
+    /// Following the chain tip.
+    Live,
+    /// The last request failed; the fetcher is backing off and retrying.
+    Retrying,
+    /// A historical range was fully fetched.
+    Finished,
+    /// The fetcher gave up and stopped.
+    Failed,
+}
+
+impl fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Self::Connecting => "connecting",
+            Self::Syncing => "syncing",
+            Self::Live => "live",
+            Self::Retrying => "retrying",
+            Self::Finished => "finished",
+            Self::Failed => "failed",
+        };
+        f.write_str(label)
+    }
+}
+
+/// Health snapshot published by a fetcher alongside its blocks.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FetcherStatus {
+    pub chain: Chain,
+    pub state: ConnectionState,
+    /// Most recent request error, kept after recovery for context.
+    pub last_error: Option<String>,
+    /// Blocks between the last one delivered and the known tip.
+    pub blocks_behind: u64,
+    /// Round trip of the most recent successful request.
+    pub latency: Option<Duration>,
+    /// Retries made since the fetcher started.
+    pub retries: u64,
+    /// Missing blocks waiting to be re-fetched.
+    pub pending_gaps: usize,
+    /// Host of the endpoint currently serving requests.
+    pub endpoint: Option<String>,
+    /// Blocks whose hash differed between endpoints (quorum strategy).
+    pub disagreements: u64,
+    /// Most recent endpoint disagreement.
+    pub last_disagreement: Option<String>,
+}
+
+impl FetcherStatus {
+    pub fn new(chain: Chain) -> Self {
+        Self {
+            chain,
+            state: ConnectionState::Connecting,
+            last_error: None,
+            blocks_behind: 0,
+            latency: None,
+            retries: 0,
+            pending_gaps: 0,
+            endpoint: None,
+            disagreements: 0,
+            last_disagreement: None,
+        }
+    }
+}
+
+/// Fetcher-side helper that tracks a [`FetcherStatus`] and publishes it.
+pub struct StatusReporter {
+    tx: Sender<FetcherStatus>,
+    status: FetcherStatus,
+    /// State to return to once a retried request succeeds.
+    resume: ConnectionState,
+    tip: u64,
+    delivered: Option<u64>,
+}
+
+impl StatusReporter {
+    pub fn new(chain: Chain, tx: Sender<FetcherStatus>) -> Self {
+        let reporter = Self {
+            tx,
+            status: FetcherStatus::new(chain),
+            resume: ConnectionState::Connecting,
+            tip: 0,
+            delivered: None,
+        };
+        reporter.publish();
+        reporter
+    }
+
+    pub fn status(&self) -> &FetcherStatus {
+        &self.status
+    }
+
+    /// Moves to `state` and publishes the change.
+    pub fn set_state(&mut self, state: ConnectionState) {
+        self.resume = state;
+        self.status.state = state;
+        self.publish();
+    }
+
+    /// Records a successful request and leaves the retrying state.
+    pub fn record_success(&mut self, latency: Duration) {
+        self.status.latency = Some(latency);
+        if self.status.state == ConnectionState::Retrying {
+            self.status.state = self.resume;
+            self.publish();
+        }
+    }
+
+    /// Records a failed request that will be retried.
+    pub fn record_retry(&mut self, err: impl fmt::Display) {
+        self.status.retries += 1;
+        self.status.last_error = Some(err.to_string());
+        self.status.state = ConnectionState::Retrying;
+        self.publish();
+    }
+
+    /// Records an error the fetcher can't recover from and stops reporting
+    /// progress.
+    pub fn record_failure(&mut self, err: impl fmt::Display) {
+        self.status.last_error = Some(err.to_string());
+        self.set_state(ConnectionState::Failed);
+    }
+
+    /// Records which endpoint is serving requests. Published with the next
+    /// update rather than on its own, since round-robin changes it per block.
+    pub fn set_endpoint(&mut self, label: String) {
+        self.status.endpoint = Some(label);
+    }
+
+    /// Records endpoints reporting different hashes for the same block.
+    pub fn record_disagreement(&mut self, detail: impl fmt::Display) {
+        self.status.disagreements += 1;
+        self.status.last_disagreement = Some(detail.to_string());
+        self.publish();
+    }
+
+    /// Raises the known chain tip.
+    pub fn set_tip(&mut self, tip: u64) {
+        self.tip = self.tip.max(tip);
+        self.update_lag();
+    }
+
+    /// Records a delivered block and publishes the new lag.
+    pub fn record_delivered(&mut self, number: u64, pending_gaps: usize) {
+        self.delivered = Some(self.delivered.map_or(number, |d| d.max(number)));
+        self.status.pending_gaps = pending_gaps;
+        self.update_lag();
+        self.publish();
+    }
+
+    pub fn set_pending_gaps(&mut self, pending_gaps: usize) {
+        if self.status.pending_gaps != pending_gaps {
+            self.status.pending_gaps = pending_gaps;
+            self.publish();
+        }
+    }
+
+    fn update_lag(&mut self) {
+        self.status.blocks_behind = match self.delivered {
+            Some(delivered) => self.tip.saturating_sub(delivered),
+            None => self.tip,
+        };
+    }
+
+    /// Sends the current snapshot. Never blocks; a full or closed channel
+    /// just drops the update.
+    pub fn publish(&self) {
+        let _ = self.tx.try_send(self.status.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_returns_to_previous_state_on_success() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut reporter = StatusReporter::new(Chain::mainnet(), tx);
+        reporter.set_state(ConnectionState::Live);
+        reporter.record_retry("HTTP error 429");
+        assert_eq!(reporter.status().state, ConnectionState::Retrying);
+        assert_eq!(reporter.status().retries, 1);
+
+        reporter.record_success(Duration::from_millis(80));
+        let status = reporter.status();
+        assert_eq!(status.state, ConnectionState::Live);
+        assert_eq!(status.latency, Some(Duration::from_millis(80)));
+        assert_eq!(status.last_error.as_deref(), Some("HTTP error 429"));
+
+        let states: Vec<ConnectionState> = rx.try_iter().map(|s| s.state).collect();
+        assert_eq!(
+            states,
+            vec![
+                ConnectionState::Connecting,
+                ConnectionState::Live,
+                ConnectionState::Retrying,
+                ConnectionState::Live,
+            ]
+        );
+    }
+
+    #[test]
+    fn lag_tracks_tip_and_delivered_blocks() {
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut reporter = StatusReporter::new(Chain::mainnet(), tx);
+        reporter.set_tip(120);
+        reporter.record_delivered(100, 0);
+        assert_eq!(reporter.status().blocks_behind, 20);
+
+        reporter.record_delivered(95, 1);
+        assert_eq!(reporter.status().blocks_behind, 20);
+        assert_eq!(reporter.status().pending_gaps, 1);
+
+        reporter.set_tip(110);
+        reporter.record_delivered(120, 0);
+        assert_eq!(reporter.status().blocks_behind, 0);
+    }
+
+    #[test]
+    fn endpoint_rides_along_and_disagreements_publish() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut reporter = StatusReporter::new(Chain::mainnet(), tx);
+        reporter.set_endpoint("rpc-b.example.com".to_string());
+        assert_eq!(rx.try_iter().count(), 1);
+
+        reporter.record_disagreement("block 7: rpc-a.example.com");
+        let published: Vec<FetcherStatus> = rx.try_iter().collect();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].endpoint.as_deref(), Some("rpc-b.example.com"));
+        assert_eq!(published[0].disagreements, 1);
+        assert_eq!(
+            published[0].last_disagreement.as_deref(),
+            Some("block 7: rpc-a.example.com")
+        );
+    }
+
+    #[test]
+    fn publish_never_blocks_when_receiver_is_gone() {
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        drop(rx);
+        let mut reporter = StatusReporter::new(Chain::mainnet(), tx);
+        reporter.set_state(ConnectionState::Live);
+    }
+}
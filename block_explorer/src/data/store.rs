@@ -0,0 +1,637 @@
+//! Local block store (`store` feature).
+//!
+//! Ingested [`BlockPayload`]s are written to a redb database keyed by chain
+//! id and block number, so a restart can warm the scene from disk before the
+//! fetchers catch up, and stored blocks can be replayed in place of RPC.
+//! Writes happen on a background thread ([`StoreWriter`]), which also prunes
+//! the store to its [`Retention`].
+
+use std::collections::{HashMap, VecDeque};
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use alloy::primitives::B256;
+use alloy_chains::Chain;
+use crossbeam_channel::{Receiver, Sender};
+use redb::{Database, ReadableTable, ReadableTableMetadata, TableDefinition};
+
+use crate::data::model::{BlockPayload, ReorgNotice};
+
+/// `(chain id, block number)` → `(timestamp, JSON payload)`.
+const BLOCKS: TableDefinition<(u64, u64), (u64, &[u8])> = TableDefinition::new("blocks");
+
+/// Stored blocks per chain shown while the fetchers catch up.
+pub(crate) const WARM_BLOCKS: usize = 20;
+/// Stored blocks older than this are left out of the warm start; they would
+/// fall out of the scene's window as soon as live blocks arrive.
+pub(crate) const WARM_MAX_AGE: Duration = Duration::from_secs(120);
+/// Blocks written between retention passes.
+const PRUNE_EVERY: usize = 256;
+/// Blocks read at a time per chain during replay.
+const REPLAY_PAGE: usize = 32;
+
+/// How much history the store keeps per chain. Unset limits keep everything.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Retention {
+    /// Newest blocks kept per chain.
+    pub max_blocks: Option<u64>,
+    /// Blocks older than this, measured back from the chain's newest stored
+    /// block, are dropped.
+    pub max_age: Option<Duration>,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self {
+            max_blocks: Some(10_000),
+            max_age: None,
+        }
+    }
+}
+
+/// First and last stored block of a chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StoredSpan {
+    pub first: u64,
+    pub last: u64,
+    pub count: u64,
+}
+
+/// Handle to the block database. Cheap to clone; clones share the database.
+#[derive(Clone)]
+pub struct BlockStore {
+    db: Arc<Database>,
+}
+
+impl BlockStore {
+    /// Opens the store at `path`, creating it if needed.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let db = Database::create(path).map_err(|e| e.to_string())?;
+        let txn = db.begin_write().map_err(|e| e.to_string())?;
+        txn.open_table(BLOCKS).map_err(|e| e.to_string())?;
+        txn.commit().map_err(|e| e.to_string())?;
+        Ok(Self { db: Arc::new(db) })
+    }
+
+    /// Writes payloads in one transaction, replacing blocks with the same
+    /// number. A payload carrying a [`ReorgNotice`] first drops every stored
+    /// block of its chain at or above the fork.
+    pub fn insert(&self, payloads: &[BlockPayload]) -> Result<(), String> {
+        let txn = self.db.begin_write().map_err(|e| e.to_string())?;
+        {
+            let mut table = txn.open_table(BLOCKS).map_err(|e| e.to_string())?;
+            for payload in payloads {
+                let chain = payload.chain.id();
+                if let Some(fork) = payload.reorg_fork() {
+                    table
+                        .retain_in((chain, fork)..=(chain, u64::MAX), |_, _| false)
+                        .map_err(|e| e.to_string())?;
+                }
+                let json = serde_json::to_vec(payload).map_err(|e| e.to_string())?;
+                table
+                    .insert(
+                        (chain, payload.number),
+                        (payload.timestamp, json.as_slice()),
+                    )
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        txn.commit().map_err(|e| e.to_string())
+    }
+
+    /// The newest `count` blocks of a chain, oldest first.
+    pub fn latest(&self, chain: Chain, count: usize) -> Result<Vec<BlockPayload>, String> {
+        let txn = self.db.begin_read().map_err(|e| e.to_string())?;
+        let table = txn.open_table(BLOCKS).map_err(|e| e.to_string())?;
+        let id = chain.id();
+        let mut payloads = table
+            .range((id, 0)..=(id, u64::MAX))
+            .map_err(|e| e.to_string())?
+            .rev()
+            .take(count)
+            .map(|entry| decode(entry.map_err(|e| e.to_string())?.1.value().1))
+            .collect::<Result<Vec<_>, _>>()?;
+        payloads.reverse();
+        Ok(payloads)
+    }
+
+    /// Stored blocks of a chain within `numbers`, oldest first.
+    pub fn range(
+        &self,
+        chain: Chain,
+        numbers: RangeInclusive<u64>,
+    ) -> Result<Vec<BlockPayload>, String> {
+        self.page(chain, *numbers.start(), *numbers.end(), usize::MAX)
+    }
+
+    /// Up to `limit` blocks of a chain numbered `from..=to`, oldest first.
+    fn page(
+        &self,
+        chain: Chain,
+        from: u64,
+        to: u64,
+        limit: usize,
+    ) -> Result<Vec<BlockPayload>, String> {
+        let txn = self.db.begin_read().map_err(|e| e.to_string())?;
+        let table = txn.open_table(BLOCKS).map_err(|e| e.to_string())?;
+        let id = chain.id();
+        table
+            .range((id, from)..=(id, to))
+            .map_err(|e| e.to_string())?
+            .take(limit)
+            .map(|entry| decode(entry.map_err(|e| e.to_string())?.1.value().1))
+            .collect()
+    }
+
+    /// Chains with at least one stored block.
+    pub fn chains(&self) -> Result<Vec<Chain>, String> {
+        let txn = self.db.begin_read().map_err(|e| e.to_string())?;
+        let table = txn.open_table(BLOCKS).map_err(|e| e.to_string())?;
+        let mut chains = Vec::new();
+        let mut next = 0u64;
+        loop {
+            let first = table
+                .range((next, 0)..)
+                .map_err(|e| e.to_string())?
+                .next()
+                .transpose()
+                .map_err(|e| e.to_string())?;
+            let Some((key, _)) = first else {
+                break;
+            };
+            let (id, _) = key.value();
+            chains.push(Chain::from_id(id));
+            match id.checked_add(1) {
+                Some(id) => next = id,
+                None => break,
+            }
+        }
+        Ok(chains)
+    }
+
+    /// First and last stored block of a chain, if any.
+    pub fn span(&self, chain: Chain) -> Result<Option<StoredSpan>, String> {
+        let txn = self.db.begin_read().map_err(|e| e.to_string())?;
+        let table = txn.open_table(BLOCKS).map_err(|e| e.to_string())?;
+        let id = chain.id();
+        let mut range = table
+            .range((id, 0)..=(id, u64::MAX))
+            .map_err(|e| e.to_string())?;
+        let Some(first) = range.next().transpose().map_err(|e| e.to_string())? else {
+            return Ok(None);
+        };
+        let first = first.0.value().1;
+        let last = match range.next_back().transpose().map_err(|e| e.to_string())? {
+            Some(last) => last.0.value().1,
+            None => first,
+        };
+        let count = table
+            .range((id, first)..=(id, last))
+            .map_err(|e| e.to_string())?
+            .count() as u64;
+        Ok(Some(StoredSpan { first, last, count }))
+    }
+
+    /// Total stored blocks across chains.
+    pub fn len(&self) -> Result<u64, String> {
+        let txn = self.db.begin_read().map_err(|e| e.to_string())?;
+        let table = txn.open_table(BLOCKS).map_err(|e| e.to_string())?;
+        table.len().map_err(|e| e.to_string())
+    }
+
+    pub fn is_empty(&self) -> Result<bool, String> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Drops blocks outside the retention limits. Returns how many were
+    /// removed.
+    pub fn prune(&self, retention: &Retention) -> Result<u64, String> {
+        let chains = self.chains()?;
+        let before = self.len()?;
+        let txn = self.db.begin_write().map_err(|e| e.to_string())?;
+        {
+            let mut table = txn.open_table(BLOCKS).map_err(|e| e.to_string())?;
+            for chain in chains {
+                let id = chain.id();
+                let newest = table
+                    .range((id, 0)..=(id, u64::MAX))
+                    .map_err(|e| e.to_string())?
+                    .next_back()
+                    .transpose()
+                    .map_err(|e| e.to_string())?
+                    .map(|(key, value)| (key.value().1, value.value().0));
+                let Some((last_number, last_timestamp)) = newest else {
+                    continue;
+                };
+                let min_number = retention
+                    .max_blocks
+                    .map_or(0, |keep| last_number.saturating_sub(keep.saturating_sub(1)));
+                let min_timestamp = retention
+                    .max_age
+                    .map_or(0, |age| last_timestamp.saturating_sub(age.as_secs()));
+                table
+                    .retain_in((id, 0)..=(id, u64::MAX), |(_, number), (timestamp, _)| {
+                        number >= min_number && timestamp >= min_timestamp
+                    })
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        txn.commit().map_err(|e| e.to_string())?;
+        Ok(before.saturating_sub(self.len()?))
+    }
+}
+
+fn decode(json: &[u8]) -> Result<BlockPayload, String> {
+    serde_json::from_slice(json).map_err(|e| format!("corrupt stored block: {e}"))
+}
+
+/// Bevy resource through which ingested payloads reach the store's writer
+/// thread. Only present while blocks come from live RPC, so fixtures and
+/// store replays never end up in the stored history.
+#[derive(bevy::prelude::Resource)]
+pub struct StoreWriter(Sender<BlockPayload>);
+
+impl StoreWriter {
+    /// Spawns the writer thread. It batches whatever payloads are queued,
+    /// and prunes to `retention` every few hundred blocks.
+    pub fn spawn(store: BlockStore, retention: Retention) -> Self {
+        let (tx, rx) = crossbeam_channel::unbounded::<BlockPayload>();
+        std::thread::spawn(move || {
+            let mut since_prune = 0;
+            while let Ok(first) = rx.recv() {
+                let mut batch = vec![first];
+                batch.extend(rx.try_iter().take(63));
+                if let Err(e) = store.insert(&batch) {
+                    eprintln!("tessera: block store write failed: {e}");
+                }
+                since_prune += batch.len();
+                if since_prune >= PRUNE_EVERY {
+                    since_prune = 0;
+                    if let Err(e) = store.prune(&retention) {
+                        eprintln!("tessera: block store prune failed: {e}");
+                    }
+                }
+            }
+        });
+        Self(tx)
+    }
+
+    /// Queues a payload for writing.
+    pub fn send(&self, payload: BlockPayload) {
+        // The writer only stops when the app does.
+        let _ = self.0.send(payload);
+    }
+}
+
+/// Stored block ranges per chain for the timeline, refreshed every few
+/// seconds.
+#[derive(bevy::prelude::Resource)]
+pub struct StoreHistory {
+    pub store: BlockStore,
+    pub spans: Vec<(Chain, StoredSpan)>,
+    refreshed: Option<Instant>,
+}
+
+impl StoreHistory {
+    pub fn new(store: BlockStore) -> Self {
+        Self {
+            store,
+            spans: Vec::new(),
+            refreshed: None,
+        }
+    }
+
+    /// Re-reads the spans if the last read is older than `every`.
+    pub fn refresh(&mut self, every: Duration) {
+        if self.refreshed.is_some_and(|at| at.elapsed() < every) {
+            return;
+        }
+        self.refreshed = Some(Instant::now());
+        let spans = self.store.chains().and_then(|chains| {
+            let mut spans = Vec::new();
+            for chain in chains {
+                if let Some(span) = self.store.span(chain)? {
+                    spans.push((chain, span));
+                }
+            }
+            Ok(spans)
+        });
+        match spans {
+            Ok(spans) => self.spans = spans,
+            Err(e) => eprintln!("tessera: failed to read block store: {e}"),
+        }
+    }
+}
+
+/// Sends the newest stored blocks of `chains` produced at or after `since`,
+/// in timestamp order, ahead of the live payloads. Live blocks already sent
+/// from the store are dropped. One that differs from its stored copy replaces
+/// the stored blocks from its number up with a [`ReorgNotice`]; one whose
+/// parent differs from the stored block below it replaces them from that
+/// parent up. Blocks filling holes in the stored window pass through.
+pub(crate) fn warm_receiver(
+    store: &BlockStore,
+    chains: &[Chain],
+    per_chain: usize,
+    since: u64,
+    live: Receiver<BlockPayload>,
+) -> Receiver<BlockPayload> {
+    let mut stored = Vec::new();
+    for chain in chains {
+        match store.latest(*chain, per_chain) {
+            Ok(payloads) => stored.extend(payloads.into_iter().filter(|p| p.timestamp >= since)),
+            Err(e) => eprintln!("tessera: failed to read block store: {e}"),
+        }
+    }
+    stored.sort_by_key(|payload| payload.timestamp);
+    if !stored.is_empty() {
+        eprintln!("tessera: warming scene with {} stored blocks", stored.len());
+    }
+
+    let (tx, rx) = crossbeam_channel::bounded(64);
+    std::thread::spawn(move || {
+        let mut sent: HashMap<(Chain, u64), B256> = HashMap::new();
+        for payload in stored {
+            sent.insert((payload.chain, payload.number), payload.hash);
+            if tx.send(payload).is_err() {
+                return;
+            }
+        }
+        while let Ok(mut payload) = live.recv() {
+            let key = (payload.chain, payload.number);
+            let stored_hash = sent.get(&key).copied();
+            if payload.reorg.is_none() && stored_hash == Some(payload.hash) {
+                sent.remove(&key);
+                continue;
+            }
+            // A block filling a hole in the stored window replaces nothing;
+            // only a different hash or a different parent means a fork. A
+            // different parent orphans the stored parent as well.
+            let parent_number = payload.number.checked_sub(1);
+            let parent = parent_number.and_then(|number| sent.get(&(payload.chain, number)));
+            let fork = if let Some(fork) = payload.reorg_fork() {
+                Some(fork)
+            } else if stored_hash.is_some() {
+                Some(payload.number)
+            } else if parent.is_some_and(|parent| *parent != payload.parent_hash) {
+                parent_number
+            } else {
+                None
+            };
+            if let Some(fork) = fork {
+                let before = sent.len();
+                sent.retain(|(chain, number), _| *chain != payload.chain || *number < fork);
+                let replaced = (before - sent.len()) as u64;
+                if replaced > 0 && payload.reorg.is_none() {
+                    payload.reorg = Some(ReorgNotice {
+                        depth: replaced,
+                        fork: (fork < payload.number).then_some(fork),
+                    });
+                }
+            }
+            if tx.send(payload).is_err() {
+                return;
+            }
+        }
+    });
+    rx
+}
+
+/// Replays every stored block in timestamp order, reading each chain a page
+/// at a time. Paced like fixture replay.
+pub(crate) fn replay_receiver(store: BlockStore) -> Receiver<BlockPayload> {
+    let (tx, rx) = crossbeam_channel::bounded(64);
+    std::thread::spawn(move || {
+        let chains = match store.chains() {
+            Ok(chains) => chains,
+            Err(e) => {
+                eprintln!("tessera: failed to read block store: {e}");
+                return;
+            }
+        };
+        // Per chain: next block number to read and the blocks read so far.
+        let mut cursors: Vec<(Chain, Option<u64>, VecDeque<BlockPayload>)> = chains
+            .into_iter()
+            .map(|chain| (chain, Some(0), VecDeque::new()))
+            .collect();
+        loop {
+            for (chain, next, buffer) in &mut cursors {
+                let Some(from) = next.filter(|_| buffer.is_empty()) else {
+                    continue;
+                };
+                match store.page(*chain, from, u64::MAX, REPLAY_PAGE) {
+                    Ok(page) => {
+                        *next = page
+                            .last()
+                            .filter(|_| page.len() == REPLAY_PAGE)
+                            .and_then(|last| last.number.checked_add(1));
+                        buffer.extend(page);
+                    }
+                    Err(e) => {
+                        eprintln!("tessera: failed to read block store: {e}");
+                        *next = None;
+                    }
+                }
+            }
+            let Some(buffer) = cursors
+                .iter_mut()
+                .filter_map(|(_, _, buffer)| Some((buffer.front()?.timestamp, buffer)))
+                .min_by_key(|(timestamp, _)| *timestamp)
+                .map(|(_, buffer)| buffer)
+            else {
+                return;
+            };
+            let payload = buffer.pop_front().expect("buffer has a front");
+            if tx.send(payload).is_err() {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    });
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store() -> (BlockStore, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = BlockStore::open(&dir.path().join("blocks.redb")).expect("open store");
+        (store, dir)
+    }
+
+    /// A block on a competing branch: same number, different hash.
+    fn forked(chain: Chain, number: u64) -> BlockPayload {
+        BlockPayload {
+            hash: B256::repeat_byte(0xf0),
+            ..BlockPayload::for_test(chain, number)
+        }
+    }
+
+    fn numbers(payloads: &[BlockPayload]) -> Vec<u64> {
+        payloads.iter().map(|p| p.number).collect()
+    }
+
+    #[test]
+    fn stores_blocks_per_chain_and_rolls_back_on_reorg() {
+        let (store, _dir) = temp_store();
+        let blocks: Vec<BlockPayload> = (1..=5)
+            .map(|n| BlockPayload::for_test(Chain::mainnet(), n))
+            .chain([BlockPayload::for_test(Chain::base_mainnet(), 3)])
+            .collect();
+        store.insert(&blocks).unwrap();
+
+        assert_eq!(
+            store.chains().unwrap(),
+            vec![Chain::mainnet(), Chain::base_mainnet()]
+        );
+        assert_eq!(numbers(&store.latest(Chain::mainnet(), 2).unwrap()), [4, 5]);
+        assert_eq!(
+            numbers(&store.range(Chain::mainnet(), 2..=3).unwrap()),
+            [2, 3]
+        );
+
+        let mut fork = forked(Chain::mainnet(), 4);
+        fork.reorg = Some(ReorgNotice {
+            depth: 2,
+            fork: None,
+        });
+        store.insert(&[fork]).unwrap();
+        let latest = store.latest(Chain::mainnet(), 10).unwrap();
+        assert_eq!(numbers(&latest), [1, 2, 3, 4]);
+        assert_eq!(latest[3].hash, B256::repeat_byte(0xf0));
+        assert_eq!(
+            store.span(Chain::mainnet()).unwrap(),
+            Some(StoredSpan {
+                first: 1,
+                last: 4,
+                count: 4
+            })
+        );
+    }
+
+    #[test]
+    fn prunes_by_count_and_age() {
+        let (store, _dir) = temp_store();
+        let blocks: Vec<BlockPayload> = (1..=10)
+            .map(|n| BlockPayload::for_test(Chain::mainnet(), n))
+            .collect();
+        store.insert(&blocks).unwrap();
+
+        let removed = store
+            .prune(&Retention {
+                max_blocks: Some(6),
+                max_age: None,
+            })
+            .unwrap();
+        assert_eq!(removed, 4);
+        // Blocks are 12s apart: 36s back from block 10 reaches block 7.
+        store
+            .prune(&Retention {
+                max_blocks: None,
+                max_age: Some(Duration::from_secs(36)),
+            })
+            .unwrap();
+        assert_eq!(
+            numbers(&store.latest(Chain::mainnet(), 10).unwrap()),
+            [7, 8, 9, 10]
+        );
+    }
+
+    #[test]
+    fn warm_start_skips_known_blocks_and_reorgs_changed_ones() {
+        let (store, _dir) = temp_store();
+        let blocks: Vec<BlockPayload> = (1..=4)
+            .map(|n| BlockPayload::for_test(Chain::mainnet(), n))
+            .collect();
+        store.insert(&blocks).unwrap();
+
+        let (live_tx, live_rx) = crossbeam_channel::unbounded();
+        live_tx
+            .send(BlockPayload::for_test(Chain::mainnet(), 3))
+            .unwrap();
+        live_tx.send(forked(Chain::mainnet(), 4)).unwrap();
+        live_tx
+            .send(BlockPayload::for_test(Chain::mainnet(), 5))
+            .unwrap();
+        drop(live_tx);
+
+        let rx = warm_receiver(&store, &[Chain::mainnet()], 3, 0, live_rx);
+        let received: Vec<BlockPayload> = rx.iter().collect();
+        assert_eq!(numbers(&received), [2, 3, 4, 4, 5]);
+        assert_eq!(
+            received[3].reorg,
+            Some(ReorgNotice {
+                depth: 1,
+                fork: None
+            })
+        );
+        assert!(received[4].reorg.is_none());
+    }
+
+    #[test]
+    fn warm_start_fills_holes_without_reorgs() {
+        let (store, _dir) = temp_store();
+        let blocks: Vec<BlockPayload> = [1, 2, 4, 5]
+            .map(|n| BlockPayload::for_test(Chain::mainnet(), n))
+            .to_vec();
+        store.insert(&blocks).unwrap();
+
+        let (live_tx, live_rx) = crossbeam_channel::unbounded();
+        live_tx
+            .send(BlockPayload::for_test(Chain::mainnet(), 3))
+            .unwrap();
+        live_tx
+            .send(BlockPayload::for_test(Chain::mainnet(), 4))
+            .unwrap();
+        drop(live_tx);
+
+        let rx = warm_receiver(&store, &[Chain::mainnet()], 10, 0, live_rx);
+        let received: Vec<BlockPayload> = rx.iter().collect();
+        assert_eq!(numbers(&received), [1, 2, 4, 5, 3]);
+        assert!(received.iter().all(|payload| payload.reorg.is_none()));
+    }
+
+    #[test]
+    fn warm_start_reorgs_from_a_hole_fillers_mismatched_parent() {
+        let (store, _dir) = temp_store();
+        let blocks: Vec<BlockPayload> = [1, 2, 4, 5]
+            .map(|n| BlockPayload::for_test(Chain::mainnet(), n))
+            .to_vec();
+        store.insert(&blocks).unwrap();
+
+        let (live_tx, live_rx) = crossbeam_channel::unbounded();
+        live_tx
+            .send(BlockPayload {
+                parent_hash: B256::repeat_byte(0xf0),
+                ..BlockPayload::for_test(Chain::mainnet(), 3)
+            })
+            .unwrap();
+        drop(live_tx);
+
+        let rx = warm_receiver(&store, &[Chain::mainnet()], 10, 0, live_rx);
+        let received: Vec<BlockPayload> = rx.iter().collect();
+        assert_eq!(numbers(&received), [1, 2, 4, 5, 3]);
+        assert_eq!(
+            received[4].reorg,
+            Some(ReorgNotice {
+                depth: 3,
+                fork: Some(2)
+            })
+        );
+        assert_eq!(received[4].reorg_fork(), Some(2));
+
+        // The store drops the orphaned parent along with the blocks above it.
+        store.insert(&received[4..]).unwrap();
+        assert_eq!(
+            numbers(&store.latest(Chain::mainnet(), 10).unwrap()),
+            [1, 3]
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! Headless access to the ingestion layer: blocks as an async `Stream` or a
+//! blocking iterator, with no Bevy app, window or GPU involved.
+
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crossbeam_channel::Receiver;
+use futures::channel::mpsc;
+use futures::executor::BlockingStream;
+use futures::{SinkExt, Stream};
+
+use crate::data::channel::{config_fetchers, fixture_receiver, spawn_fetchers};
+use crate::data::model::BlockPayload;
+use crate::data::status::FetcherStatus;
+use crate::data::{ChainFetcher, FetcherConfig};
+
+/// Payloads buffered between the fetcher threads and an async consumer.
+const STREAM_BUFFER: usize = 64;
+
+/// Blocks from one or more fetchers (or a fixture), fanned into one sequence.
+///
+/// Poll it as a [`Stream`] from async code, or iterate it (blocking) with
+/// `for block in stream`. Iterating after polling picks up where the stream
+/// left off. Once it is dropped, each fetcher stops on its next delivery,
+/// which for a polling fetcher can be up to a block interval later.
+///
+/// ```no_run
+/// use block_explorer::{BlockStream, FetcherConfig};
+/// use futures::StreamExt;
+///
+/// # async fn run(config: FetcherConfig) {
+/// let mut blocks = BlockStream::new(vec![config]);
+/// while let Some(block) = blocks.next().await {
+///     println!("{} #{}", block.chain, block.number);
+/// }
+/// # }
+/// ```
+pub struct BlockStream {
+    /// Taken over by the bridge once polled as a stream.
+    blocks: Option<Receiver<BlockPayload>>,
+    statuses: Vec<Receiver<FetcherStatus>>,
+    bridged: Option<mpsc::Receiver<BlockPayload>>,
+}
+
+impl BlockStream {
+    /// Spawns the built-in fetcher for each config, with the same backfill,
+    /// polling and range behaviour as the explorer.
+    pub fn new(configs: Vec<FetcherConfig>) -> Self {
+        Self::from_fetchers(config_fetchers(configs))
+    }
+
+    /// Spawns custom (or a mix of built-in and custom) fetchers.
+    pub fn from_fetchers(fetchers: Vec<Box<dyn ChainFetcher>>) -> Self {
+        let (blocks, statuses) = spawn_fetchers(fetchers);
+        Self {
+            blocks: Some(blocks),
+            statuses,
+            bridged: None,
+        }
+    }
+
+    /// Replays a recorded fixture file at the explorer's pacing.
+    pub fn from_fixture(path: &Path) -> Self {
+        Self {
+            blocks: Some(fixture_receiver(path)),
+            statuses: Vec::new(),
+            bridged: None,
+        }
+    }
+
+    /// One health stream per fetcher (empty for fixtures).
+    pub fn statuses(&self) -> &[Receiver<FetcherStatus>] {
+        &self.statuses
+    }
+}
+
+impl Stream for BlockStream {
+    type Item = BlockPayload;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<BlockPayload>> {
+        let this = &mut *self;
+        let bridged = match (&mut this.bridged, this.blocks.take()) {
+            (Some(bridged), _) => bridged,
+            (bridged, Some(blocks)) => bridged.insert(bridge(blocks)),
+            (None, None) => unreachable!("blocks are only taken by the bridge"),
+        };
+        Pin::new(bridged).poll_next(cx)
+    }
+}
+
+impl IntoIterator for BlockStream {
+    type Item = BlockPayload;
+    type IntoIter = BlockIter;
+
+    /// Blocks on each payload; ends once every fetcher has finished.
+    fn into_iter(self) -> Self::IntoIter {
+        match (self.blocks, self.bridged) {
+            (Some(blocks), _) => BlockIter::Channel(blocks.into_iter()),
+            (None, Some(bridged)) => {
+                BlockIter::Bridged(futures::executor::block_on_stream(bridged))
+            }
+            (None, None) => unreachable!("blocks are only taken by the bridge"),
+        }
+    }
+}
+
+/// Blocking iterator over a [`BlockStream`].
+pub enum BlockIter {
+    /// Straight from the fetchers' channel.
+    Channel(crossbeam_channel::IntoIter<BlockPayload>),
+    /// From the bridge started when the stream was polled.
+    Bridged(BlockingStream<mpsc::Receiver<BlockPayload>>),
+}
+
+impl Iterator for BlockIter {
+    type Item = BlockPayload;
+
+    fn next(&mut self) -> Option<BlockPayload> {
+        match self {
+            Self::Channel(blocks) => blocks.next(),
+            Self::Bridged(blocks) => blocks.next(),
+        }
+    }
+}
+
+/// Forwards the fetchers' channel into an async one on a dedicated thread, so
+/// polling never blocks the executor.
+fn bridge(blocks: Receiver<BlockPayload>) -> mpsc::Receiver<BlockPayload> {
+    let (mut tx, rx) = mpsc::channel(STREAM_BUFFER);
+    std::thread::spawn(move || {
+        for payload in blocks {
+            if futures::executor::block_on(tx.send(payload)).is_err() {
+                return;
+            }
+        }
+    });
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_chains::Chain;
+    use futures::StreamExt;
+
+    use super::*;
+    use crate::data::test_support::ReplayFetcher;
+
+    fn replay(numbers: &[u64]) -> BlockStream {
+        let payloads = numbers
+            .iter()
+            .map(|n| BlockPayload::for_test(Chain::mainnet(), *n))
+            .collect();
+        BlockStream::from_fetchers(vec![Box::new(ReplayFetcher(payloads))])
+    }
+
+    #[test]
+    fn stream_yields_blocks_until_fetchers_finish() {
+        let numbers: Vec<u64> =
+            futures::executor::block_on(replay(&[1, 2, 3]).map(|p| p.number).collect());
+        assert_eq!(numbers, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn blocking_iterator_yields_the_same_blocks() {
+        let stream = replay(&[4, 5]);
+        assert_eq!(stream.statuses().len(), 1);
+        let numbers: Vec<u64> = stream.into_iter().map(|p| p.number).collect();
+        assert_eq!(numbers, vec![4, 5]);
+    }
+
+    #[test]
+    fn iterating_after_polling_yields_the_rest() {
+        let mut stream = replay(&[1, 2, 3]);
+        let first = futures::executor::block_on(stream.next()).unwrap();
+        assert_eq!(first.number, 1);
+        let rest: Vec<u64> = stream.into_iter().map(|p| p.number).collect();
+        assert_eq!(rest, vec![2, 3]);
+    }
+
+    #[test]
+    fn fixture_replays_headlessly() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fixture.json");
+        std::fs::write(
+            &path,
+            serde_json::to_string(&vec![BlockPayload::for_test(Chain::mainnet(), 9)]).unwrap(),
+        )
+        .unwrap();
+
+        let blocks: Vec<BlockPayload> = BlockStream::from_fixture(&path).into_iter().collect();
+
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].number, 9);
+    }
+}
@@ -0,0 +1,18 @@
+//! Helpers shared by the data module's unit tests.
+
+use crossbeam_channel::Receiver;
+
+use crate::data::{BlockPayload, ChainFetcher};
+
+/// Replays a fixed list of payloads, like a custom SDK data source would.
+pub(crate) struct ReplayFetcher(pub Vec<BlockPayload>);
+
+impl ChainFetcher for ReplayFetcher {
+    fn spawn(self: Box<Self>) -> Receiver<BlockPayload> {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        for payload in self.0 {
+            tx.send(payload).unwrap();
+        }
+        rx
+    }
+}
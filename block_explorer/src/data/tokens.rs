@@ -0,0 +1,423 @@
+//! Token movements decoded from receipt logs, and a local token registry.
+//!
+//! ERC-20 and ERC-721 share the `Transfer(address,address,uint256)` event;
+//! ERC-721 indexes the token id as a fourth topic where ERC-20 puts the amount
+//! in the data. Symbols and decimals come from a bundled list of well-known
+//! tokens, which users can extend from JSON or TOML files:
+//!
+//! ```json
+//! [{ "chain": 1, "address": "0x6b17...1d0f", "symbol": "DAI", "decimals": 18 }]
+//! ```
+//!
+//! ```toml
+//! [[token]]
+//! chain = 1
+//! address = "0x6b17...1d0f"
+//! symbol = "DAI"
+//! decimals = 18
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use alloy::primitives::{address, b256, Address, B256, U256};
+use alloy_chains::{Chain, NamedChain};
+use serde::{Deserialize, Serialize};
+
+use crate::data::registry_file;
+
+/// `keccak256("Transfer(address,address,uint256)")`.
+pub const TRANSFER_TOPIC: B256 =
+    b256!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+
+/// Which token standard emitted a transfer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenStandard {
+    Erc20,
+    Erc721,
+}
+
+/// One token movement within a transaction.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TokenTransfer {
+    /// Contract that emitted the event.
+    pub token: Address,
+    pub from: Address,
+    pub to: Address,
+    /// Raw amount (ERC-20) or token id (ERC-721).
+    pub amount: U256,
+    pub standard: TokenStandard,
+}
+
+impl TokenTransfer {
+    /// Decodes a `Transfer` log, or `None` for any other event.
+    pub fn from_log(token: Address, topics: &[B256], data: &[u8]) -> Option<Self> {
+        if topics.first() != Some(&TRANSFER_TOPIC) {
+            return None;
+        }
+        let from = Address::from_word(*topics.get(1)?);
+        let to = Address::from_word(*topics.get(2)?);
+        let (amount, standard) = match (topics.len(), data.len()) {
+            (3, 32) => (U256::from_be_slice(data), TokenStandard::Erc20),
+            (4, 0) => (U256::from_be_bytes(topics[3].0), TokenStandard::Erc721),
+            _ => return None,
+        };
+        Some(Self {
+            token,
+            from,
+            to,
+            amount,
+            standard,
+        })
+    }
+}
+
+/// Display metadata for a token.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenInfo {
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+impl TokenInfo {
+    /// Converts a raw amount into whole tokens.
+    pub fn whole_amount(&self, amount: U256) -> f64 {
+        f64::from(amount) / 10f64.powi(self.decimals.into())
+    }
+}
+
+/// Well-known tokens: chain, address, symbol, decimals. The contract registry
+/// labels the same addresses, so this is the one list of bundled tokens.
+pub(crate) const BUNDLED_TOKENS: &[(NamedChain, Address, &str, u8)] = &[
+    (
+        NamedChain::Mainnet,
+        address!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"),
+        "USDC",
+        6,
+    ),
+    (
+        NamedChain::Mainnet,
+        address!("dac17f958d2ee523a2206206994597c13d831ec7"),
+        "USDT",
+        6,
+    ),
+    (
+        NamedChain::Mainnet,
+        address!("6b175474e89094c44da98b954eedeac495271d0f"),
+        "DAI",
+        18,
+    ),
+    (
+        NamedChain::Mainnet,
+        address!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"),
+        "WETH",
+        18,
+    ),
+    (
+        NamedChain::Mainnet,
+        address!("2260fac5e5542a773aa44fbcfedf7c193bc2c599"),
+        "WBTC",
+        8,
+    ),
+    (
+        NamedChain::Mainnet,
+        address!("514910771af9ca656af840dff83e8264ecf986ca"),
+        "LINK",
+        18,
+    ),
+    (
+        NamedChain::Mainnet,
+        address!("1f9840a85d5af5bf1d1762f925bdaddc4201f984"),
+        "UNI",
+        18,
+    ),
+    (
+        NamedChain::Mainnet,
+        address!("7f39c581f595b53c5cb19bd0b3f8da6c935e2ca0"),
+        "wstETH",
+        18,
+    ),
+    (
+        NamedChain::Mainnet,
+        address!("7d1afa7b718fb893db30a3abc0cfc608aacfebb0"),
+        "MATIC",
+        18,
+    ),
+    (
+        NamedChain::Base,
+        address!("833589fcd6edb6e08f4c7c32d4f71b54bda02913"),
+        "USDC",
+        6,
+    ),
+    (
+        NamedChain::Base,
+        address!("d9aaec86b65d86f6a7b5b1b0c42ffa531710b6ca"),
+        "USDbC",
+        6,
+    ),
+    (
+        NamedChain::Base,
+        address!("4200000000000000000000000000000000000006"),
+        "WETH",
+        18,
+    ),
+    (
+        NamedChain::Base,
+        address!("50c5725949a6f0c72e6c4a641f24049a917db0cb"),
+        "DAI",
+        18,
+    ),
+    (
+        NamedChain::Optimism,
+        address!("0b2c639c533813f4aa9d7837caf62653d097ff85"),
+        "USDC",
+        6,
+    ),
+    (
+        NamedChain::Optimism,
+        address!("4200000000000000000000000000000000000006"),
+        "WETH",
+        18,
+    ),
+    (
+        NamedChain::Optimism,
+        address!("4200000000000000000000000000000000000042"),
+        "OP",
+        18,
+    ),
+    (
+        NamedChain::Arbitrum,
+        address!("af88d065e77c8cc2239327c5edb3a432268e5831"),
+        "USDC",
+        6,
+    ),
+    (
+        NamedChain::Arbitrum,
+        address!("82af49447d8a07e3bd95bd0d56f35241523fbab1"),
+        "WETH",
+        18,
+    ),
+    (
+        NamedChain::Arbitrum,
+        address!("912ce59144191c1204e64559fe8253a0e49e6548"),
+        "ARB",
+        18,
+    ),
+];
+
+/// Token symbols and decimals by chain and address.
+#[derive(bevy::prelude::Resource, Clone, Debug)]
+pub struct TokenRegistry {
+    tokens: HashMap<(Chain, Address), TokenInfo>,
+}
+
+/// Entry in a user token file.
+#[derive(Deserialize)]
+struct TokenEntry {
+    chain: u64,
+    address: Address,
+    symbol: String,
+    decimals: u8,
+}
+
+impl Default for TokenRegistry {
+    fn default() -> Self {
+        Self::bundled()
+    }
+}
+
+impl TokenRegistry {
+    pub fn empty() -> Self {
+        Self {
+            tokens: HashMap::new(),
+        }
+    }
+
+    /// The tokens shipped with the explorer.
+    pub fn bundled() -> Self {
+        let mut registry = Self::empty();
+        for (chain, address, symbol, decimals) in BUNDLED_TOKENS {
+            registry.insert(
+                Chain::from_named(*chain),
+                *address,
+                TokenInfo {
+                    symbol: symbol.to_string(),
+                    decimals: *decimals,
+                },
+            );
+        }
+        registry
+    }
+
+    pub fn insert(&mut self, chain: Chain, token: Address, info: TokenInfo) {
+        self.tokens.insert((chain, token), info);
+    }
+
+    /// Adds the tokens in a JSON or TOML file (by extension), replacing bundled entries for the same
+    /// chain and address. Returns how many were read.
+    pub fn extend_from_file(&mut self, path: &Path) -> Result<usize, String> {
+        let entries: Vec<TokenEntry> = registry_file::read_entries(path, "token")?;
+        let count = entries.len();
+        for entry in entries {
+            self.insert(
+                Chain::from_id(entry.chain),
+                entry.address,
+                TokenInfo {
+                    symbol: entry.symbol,
+                    decimals: entry.decimals,
+                },
+            );
+        }
+        Ok(count)
+    }
+
+    pub fn get(&self, chain: Chain, token: &Address) -> Option<&TokenInfo> {
+        self.tokens.get(&(chain, *token))
+    }
+
+    /// Human-readable amount: `1.3K USDC`, `BAYC #42` for NFTs, or the raw
+    /// amount and an abbreviated address for unknown tokens.
+    pub fn format_transfer(&self, chain: Chain, transfer: &TokenTransfer) -> String {
+        let info = self.get(chain, &transfer.token);
+        let symbol = info.map_or_else(
+            || {
+                let s = transfer.token.to_string();
+                format!("{}..{}", &s[..6], &s[s.len() - 4..])
+            },
+            |info| info.symbol.clone(),
+        );
+        match (transfer.standard, info) {
+            (TokenStandard::Erc721, _) => format!("{symbol} #{}", transfer.amount),
+            (TokenStandard::Erc20, Some(info)) => {
+                format!(
+                    "{} {symbol}",
+                    format_whole(info.whole_amount(transfer.amount))
+                )
+            }
+            (TokenStandard::Erc20, None) => format!("{} {symbol}", transfer.amount),
+        }
+    }
+}
+
+fn format_whole(amount: f64) -> String {
+    if amount >= 1_000_000.0 {
+        format!("{:.2}M", amount / 1_000_000.0)
+    } else if amount >= 1_000.0 {
+        format!("{:.1}K", amount / 1_000.0)
+    } else if amount >= 1.0 {
+        format!("{amount:.2}")
+    } else {
+        format!("{amount:.6}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn topic(address: Address) -> B256 {
+        address.into_word()
+    }
+
+    #[test]
+    fn decodes_erc20_and_erc721_transfers() {
+        let usdc = address!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48");
+        let from = Address::with_last_byte(1);
+        let to = Address::with_last_byte(2);
+        let amount = U256::from(2_500_000u64);
+
+        let erc20 = TokenTransfer::from_log(
+            usdc,
+            &[TRANSFER_TOPIC, topic(from), topic(to)],
+            &amount.to_be_bytes::<32>(),
+        )
+        .unwrap();
+        assert_eq!(erc20.standard, TokenStandard::Erc20);
+        assert_eq!((erc20.from, erc20.to, erc20.amount), (from, to, amount));
+
+        let nft = TokenTransfer::from_log(
+            Address::with_last_byte(9),
+            &[
+                TRANSFER_TOPIC,
+                topic(from),
+                topic(to),
+                B256::with_last_byte(42),
+            ],
+            &[],
+        )
+        .unwrap();
+        assert_eq!(nft.standard, TokenStandard::Erc721);
+        assert_eq!(nft.amount, U256::from(42));
+
+        // Approval shares the shape but not the topic.
+        assert!(
+            TokenTransfer::from_log(usdc, &[B256::ZERO, topic(from), topic(to)], &[0; 32])
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn registry_formats_known_and_unknown_tokens() {
+        let registry = TokenRegistry::bundled();
+        let transfer = TokenTransfer {
+            token: address!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"),
+            from: Address::ZERO,
+            to: Address::ZERO,
+            amount: U256::from(1_250_500_000u64),
+            standard: TokenStandard::Erc20,
+        };
+        assert_eq!(
+            registry.format_transfer(Chain::mainnet(), &transfer),
+            "1.3K USDC"
+        );
+        // Same address on another chain is unknown.
+        assert!(registry
+            .format_transfer(Chain::base_mainnet(), &transfer)
+            .ends_with("..eB48"));
+    }
+
+    #[test]
+    fn user_file_adds_tokens() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tokens.json");
+        std::fs::write(
+            &path,
+            r#"[{"chain": 8453, "address": "0x0000000000000000000000000000000000000abc", "symbol": "TST", "decimals": 2}]"#,
+        )
+        .unwrap();
+
+        let mut registry = TokenRegistry::empty();
+        let added = registry.extend_from_file(&path).unwrap();
+
+        assert_eq!(added, 1);
+        let token = address!("0000000000000000000000000000000000000abc");
+        let info = registry.get(Chain::base_mainnet(), &token).unwrap();
+        assert_eq!(info.symbol, "TST");
+        assert_eq!(info.whole_amount(U256::from(150)), 1.5);
+    }
+
+    #[test]
+    fn user_file_can_be_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tokens.toml");
+        std::fs::write(
+            &path,
+            r#"
+[[token]]
+chain = 1
+address = "0x6b175474e89094c44da98b954eedeac495271d0f"
+symbol = "MCD"
+decimals = 18
+"#,
+        )
+        .unwrap();
+
+        let mut registry = TokenRegistry::bundled();
+        let added = registry.extend_from_file(&path).unwrap();
+
+        assert_eq!(added, 1);
+        let dai = address!("6b175474e89094c44da98b954eedeac495271d0f");
+        assert_eq!(registry.get(Chain::mainnet(), &dai).unwrap().symbol, "MCD");
+    }
+}
@@ -0,0 +1,295 @@
+//! Internal call trees from `debug_traceBlockByNumber` with the `callTracer`.
+//!
+//! Only the top-level value of a transaction shows up in the block; ETH that
+//! a router forwards to a pool, or a pool pays out to a recipient, moves in
+//! internal calls. The tracer reports each transaction's call tree, which is
+//! kept (minus calldata beyond the selector) on the envelope.
+
+use alloy::primitives::{Address, Bytes, Selector, B256, U256};
+use serde::{Deserialize, Serialize};
+
+/// Most frames kept per transaction. Busy contracts can make thousands of
+/// calls; the tree is cut off depth-first beyond this.
+pub const MAX_CALL_FRAMES: usize = 256;
+
+/// Opcode that opened a call frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CallKind {
+    Call,
+    StaticCall,
+    DelegateCall,
+    CallCode,
+    Create,
+    Create2,
+    SelfDestruct,
+    #[serde(other)]
+    Other,
+}
+
+impl CallKind {
+    /// Whether `value` on a frame of this kind moves ETH between accounts.
+    /// Delegate calls echo their caller's value without moving it again.
+    pub fn moves_value(self) -> bool {
+        matches!(
+            self,
+            Self::Call | Self::Create | Self::Create2 | Self::SelfDestruct
+        )
+    }
+}
+
+/// One call in a transaction's call tree.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CallFrame {
+    pub kind: CallKind,
+    pub from: Address,
+    /// `None` when a creation failed before an address was assigned.
+    #[serde(default)]
+    pub to: Option<Address>,
+    /// Wei sent with the call.
+    #[serde(default)]
+    pub value: U256,
+    #[serde(default)]
+    pub gas_used: u64,
+    #[serde(default)]
+    pub selector: Option<Selector>,
+    /// Revert reason or error; state changes in this frame and below were undone.
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<CallFrame>,
+}
+
+/// ETH moved by an internal call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InternalTransfer {
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+    /// Nesting level; the transaction's own call is depth 0.
+    pub depth: usize,
+}
+
+impl CallFrame {
+    /// Frames in the tree, this one included.
+    pub fn frame_count(&self) -> usize {
+        1 + self.calls.iter().map(Self::frame_count).sum::<usize>()
+    }
+
+    /// Visits every frame depth-first with its nesting level.
+    pub fn walk<'a>(&'a self, visit: &mut impl FnMut(&'a CallFrame, usize)) {
+        self.walk_at(0, visit);
+    }
+
+    fn walk_at<'a>(&'a self, depth: usize, visit: &mut impl FnMut(&'a CallFrame, usize)) {
+        visit(self, depth);
+        for call in &self.calls {
+            call.walk_at(depth + 1, visit);
+        }
+    }
+
+    /// ETH transfers made by nested calls that took effect. The root frame is
+    /// left out, since its value is the transaction's own, and reverted
+    /// subtrees are skipped.
+    pub fn internal_transfers(&self) -> Vec<InternalTransfer> {
+        let mut transfers = Vec::new();
+        for call in &self.calls {
+            call.collect_transfers(1, &mut transfers);
+        }
+        transfers
+    }
+
+    fn collect_transfers(&self, depth: usize, transfers: &mut Vec<InternalTransfer>) {
+        if self.error.is_some() {
+            return;
+        }
+        if let (true, false, Some(to)) = (self.kind.moves_value(), self.value.is_zero(), self.to) {
+            transfers.push(InternalTransfer {
+                from: self.from,
+                to,
+                value: self.value,
+                depth,
+            });
+        }
+        for call in &self.calls {
+            call.collect_transfers(depth + 1, transfers);
+        }
+    }
+}
+
+/// One entry of a `debug_traceBlockByNumber` response. Older nodes omit the
+/// hash, in which case entries follow block order.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcTraceResult {
+    #[serde(default)]
+    pub tx_hash: Option<B256>,
+    /// Absent when tracing this transaction failed.
+    #[serde(default)]
+    pub result: Option<RpcCallFrame>,
+}
+
+/// `callTracer` frame as nodes return it.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcCallFrame {
+    #[serde(rename = "type")]
+    kind: CallKind,
+    from: Address,
+    #[serde(default)]
+    to: Option<Address>,
+    #[serde(default)]
+    value: Option<U256>,
+    #[serde(default, with = "alloy::serde::quantity::opt")]
+    gas_used: Option<u64>,
+    #[serde(default)]
+    input: Bytes,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    revert_reason: Option<String>,
+    #[serde(default)]
+    calls: Vec<RpcCallFrame>,
+}
+
+impl RpcCallFrame {
+    /// Converts the tree, keeping at most [`MAX_CALL_FRAMES`] frames.
+    pub fn into_frame(self) -> CallFrame {
+        let mut budget = MAX_CALL_FRAMES;
+        self.into_frame_within(&mut budget)
+    }
+
+    fn into_frame_within(self, budget: &mut usize) -> CallFrame {
+        *budget = budget.saturating_sub(1);
+        let mut calls = Vec::new();
+        for call in self.calls {
+            if *budget == 0 {
+                break;
+            }
+            calls.push(call.into_frame_within(budget));
+        }
+        CallFrame {
+            kind: self.kind,
+            from: self.from,
+            to: self.to,
+            value: self.value.unwrap_or_default(),
+            gas_used: self.gas_used.unwrap_or_default(),
+            selector: self.input.get(..4).map(Selector::from_slice),
+            error: match (self.error, self.revert_reason) {
+                (Some(error), Some(reason)) => Some(format!("{error}: {reason}")),
+                (error, reason) => error.or(reason),
+            },
+            calls,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROUTER_SWAP: &str = r#"[{
+        "txHash": "0x0000000000000000000000000000000000000000000000000000000000000001",
+        "result": {
+            "type": "CALL",
+            "from": "0x00000000000000000000000000000000000000aa",
+            "to": "0x00000000000000000000000000000000000000bb",
+            "value": "0xde0b6b3a7640000",
+            "gas": "0x30000",
+            "gasUsed": "0x20000",
+            "input": "0x7ff36ab5000000",
+            "calls": [
+                {
+                    "type": "CALL",
+                    "from": "0x00000000000000000000000000000000000000bb",
+                    "to": "0x00000000000000000000000000000000000000cc",
+                    "value": "0xde0b6b3a7640000",
+                    "gas": "0x10000",
+                    "gasUsed": "0x5000",
+                    "input": "0xd0e30db0",
+                    "calls": [{
+                        "type": "CALL",
+                        "from": "0x00000000000000000000000000000000000000cc",
+                        "to": "0x00000000000000000000000000000000000000dd",
+                        "value": "0x1",
+                        "gas": "0x1000",
+                        "gasUsed": "0x100",
+                        "input": "0x"
+                    }]
+                },
+                {
+                    "type": "DELEGATECALL",
+                    "from": "0x00000000000000000000000000000000000000bb",
+                    "to": "0x00000000000000000000000000000000000000ee",
+                    "value": "0xde0b6b3a7640000",
+                    "gas": "0x1000",
+                    "gasUsed": "0x100",
+                    "input": "0x"
+                },
+                {
+                    "type": "CALL",
+                    "from": "0x00000000000000000000000000000000000000bb",
+                    "to": "0x00000000000000000000000000000000000000ff",
+                    "value": "0x5",
+                    "gas": "0x1000",
+                    "gasUsed": "0x1000",
+                    "input": "0x",
+                    "error": "execution reverted",
+                    "revertReason": "nope"
+                }
+            ]
+        }
+    }]"#;
+
+    fn parse() -> CallFrame {
+        let results: Vec<RpcTraceResult> = serde_json::from_str(ROUTER_SWAP).unwrap();
+        assert_eq!(results[0].tx_hash, Some(B256::with_last_byte(1)));
+        results
+            .into_iter()
+            .next()
+            .unwrap()
+            .result
+            .unwrap()
+            .into_frame()
+    }
+
+    #[test]
+    fn parses_call_tracer_output() {
+        let root = parse();
+        assert_eq!(root.kind, CallKind::Call);
+        assert_eq!(root.gas_used, 0x20000);
+        assert_eq!(
+            root.selector,
+            Some(Selector::from([0x7f, 0xf3, 0x6a, 0xb5]))
+        );
+        assert_eq!(root.frame_count(), 5);
+        assert_eq!(root.calls[1].kind, CallKind::DelegateCall);
+        assert_eq!(
+            root.calls[2].error.as_deref(),
+            Some("execution reverted: nope")
+        );
+    }
+
+    #[test]
+    fn internal_transfers_skip_root_delegates_and_reverts() {
+        let transfers = parse().internal_transfers();
+        let hops: Vec<_> = transfers
+            .iter()
+            .map(|t| (t.from.0[19], t.to.0[19], t.depth))
+            .collect();
+        assert_eq!(hops, vec![(0xbb, 0xcc, 1), (0xcc, 0xdd, 2)]);
+        assert_eq!(transfers[1].value, U256::from(1));
+    }
+
+    #[test]
+    fn conversion_caps_frame_count() {
+        let leaf =
+            r#"{"type":"CALL","from":"0x00000000000000000000000000000000000000aa","input":"0x"}"#;
+        let json = format!(
+            r#"{{"type":"CALL","from":"0x00000000000000000000000000000000000000aa","calls":[{}]}}"#,
+            vec![leaf; MAX_CALL_FRAMES * 2].join(",")
+        );
+        let frame: RpcCallFrame = serde_json::from_str(&json).unwrap();
+        assert_eq!(frame.into_frame().frame_count(), MAX_CALL_FRAMES);
+    }
+}
@@ -0,0 +1,165 @@
+//! Per-chain units used to label and scale envelope fields.
+
+use alloy::primitives::U256;
+use alloy_chains::Chain;
+
+use crate::data::{is_op_stack, solana::is_solana};
+
+/// How a chain's resource, prices and native currency are named and scaled.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChainUnits {
+    /// What blocks and transactions consume ("Gas", "CU").
+    pub resource: &'static str,
+    /// Unit prices are displayed in ("gwei", "µlamports").
+    pub price_unit: &'static str,
+    /// Envelope price units per displayed price unit.
+    pub price_scale: f64,
+    /// Displayed price at the hot end of the colour gradient.
+    pub price_ceiling: f64,
+    /// Native currency symbol.
+    pub native_symbol: &'static str,
+    /// Smallest fee units per whole native unit.
+    pub native_scale: f64,
+    /// Decimal places between the smallest and the whole native unit.
+    pub native_decimals: u8,
+}
+
+impl ChainUnits {
+    /// Converts an envelope price into the displayed price unit.
+    pub fn display_price(&self, price: u128) -> f64 {
+        price as f64 / self.price_scale
+    }
+
+    /// Converts a fee in the smallest native unit into whole native units.
+    pub fn display_fee(&self, fee: u128) -> f64 {
+        fee as f64 / self.native_scale
+    }
+
+    /// Approximates an exact amount in the smallest native unit as whole
+    /// native units, for scaling and colours.
+    pub fn display_value(&self, value: U256) -> f64 {
+        f64::from(value) / self.native_scale
+    }
+
+    /// `whole` native units in the smallest unit.
+    pub fn native_amount(&self, whole: u64) -> U256 {
+        U256::from(whole) * U256::from(10).pow(U256::from(self.native_decimals))
+    }
+
+    /// Exact amount with the native symbol, e.g. `1.5 ETH`.
+    pub fn format_value(&self, value: U256) -> String {
+        format!(
+            "{} {}",
+            format_units(value, self.native_decimals, 6),
+            self.native_symbol
+        )
+    }
+}
+
+/// Writes `amount` smallest units as whole units with `decimals` decimal
+/// places, keeping up to `max_fraction` of them. The digits come from exact
+/// integer arithmetic; the fraction is cut, not rounded, and trailing zeros
+/// are dropped. Amounts too small to show read `<0.000001`.
+pub fn format_units(amount: U256, decimals: u8, max_fraction: usize) -> String {
+    let (whole, fraction) = amount.div_rem(U256::from(10).pow(U256::from(decimals)));
+    let fraction = format!("{fraction:0>width$}", width = usize::from(decimals));
+    let shown = fraction[..max_fraction.min(fraction.len())].trim_end_matches('0');
+    match (
+        whole.is_zero(),
+        shown.is_empty(),
+        fraction.trim_matches('0').is_empty(),
+    ) {
+        (true, true, false) if max_fraction > 0 => {
+            format!("<0.{}1", "0".repeat(max_fraction - 1))
+        }
+        (_, true, _) => whole.to_string(),
+        _ => format!("{whole}.{shown}"),
+    }
+}
+
+/// Wei as ETH, up to 6 decimal places.
+pub fn format_ether(wei: U256) -> String {
+    format!("{} ETH", format_units(wei, 18, 6))
+}
+
+/// Wei as gwei, up to 3 decimal places.
+pub fn format_gwei(wei: U256) -> String {
+    format!("{} gwei", format_units(wei, 9, 3))
+}
+
+/// Wei in the most readable unit: ETH from 0.001 ETH, gwei from 1 gwei,
+/// plain wei below that.
+pub fn format_wei(wei: U256) -> String {
+    if wei >= U256::from(1_000_000_000_000_000u64) {
+        format_ether(wei)
+    } else if wei >= U256::from(1_000_000_000u64) {
+        format_gwei(wei)
+    } else {
+        format!("{wei} wei")
+    }
+}
+
+/// Units for a chain's lane. Everything that isn't Solana is treated as EVM.
+pub fn chain_units(chain: &Chain) -> ChainUnits {
+    if is_solana(chain) {
+        return ChainUnits {
+            resource: "CU",
+            price_unit: "µlamports",
+            price_scale: 1.0,
+            // Busy-slot priority fees sit well below a million µlamports per CU.
+            price_ceiling: 1_000_000.0,
+            native_symbol: "SOL",
+            native_scale: 1e9,
+            native_decimals: 9,
+        };
+    }
+    ChainUnits {
+        resource: "Gas",
+        price_unit: "gwei",
+        price_scale: 1e9,
+        // L1 spans 0–200 gwei; OP Stack L2s price far below a gwei, so their
+        // gradient tops out at 0.02 gwei to still tell transactions apart.
+        price_ceiling: if is_op_stack(chain) { 0.02 } else { 200.0 },
+        native_symbol: "ETH",
+        native_scale: 1e18,
+        native_decimals: 18,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::solana::SOLANA_MAINNET;
+
+    #[test]
+    fn units_follow_the_lane() {
+        let mainnet = chain_units(&Chain::mainnet());
+        assert_eq!(mainnet.display_price(30_000_000_000), 30.0);
+        assert_eq!(mainnet.native_symbol, "ETH");
+        assert!(chain_units(&Chain::base_mainnet()).price_ceiling < mainnet.price_ceiling);
+
+        let solana = chain_units(&SOLANA_MAINNET);
+        assert_eq!(solana.resource, "CU");
+        assert_eq!(solana.display_price(100_000), 100_000.0);
+        assert_eq!(solana.display_fee(5_000), 0.000005);
+        assert_eq!(solana.native_amount(2), U256::from(2_000_000_000u64));
+        assert_eq!(solana.format_value(U256::from(51_000_000)), "0.051 SOL");
+    }
+
+    #[test]
+    fn formats_exact_amounts() {
+        let eth = U256::from(10).pow(U256::from(18));
+        assert_eq!(format_ether(eth * U256::from(3) / U256::from(2)), "1.5 ETH");
+        assert_eq!(format_ether(U256::ZERO), "0 ETH");
+        assert_eq!(format_ether(U256::from(1)), "<0.000001 ETH");
+        // Beyond u128 and f64 precision.
+        assert_eq!(
+            format_ether(U256::MAX),
+            "115792089237316195423570985008687907853269984665640564039457.584007 ETH"
+        );
+        assert_eq!(format_gwei(U256::from(30_500_000_000u64)), "30.5 gwei");
+        assert_eq!(format_wei(U256::from(21_000)), "21000 wei");
+        assert_eq!(format_wei(U256::from(2_000_000_000u64)), "2 gwei");
+        assert_eq!(format_wei(eth / U256::from(100)), "0.01 ETH");
+    }
+}
@@ -13,4 +13,10 @@ pub mod prelude;
 pub mod sdk;
 
 pub use data::evm::EvmFetcher;
-pub use data::{is_op_stack, BlockPayload, ChainFetcher, FetcherConfig, OpStackFees, TxPayload};
+pub use data::solana::{SolanaFetcher, SOLANA_MAINNET};
+pub use data::{
+    chain_name, chain_units, is_op_stack, BlockIter, BlockPayload, BlockStart, BlockStream,
+    ChainFetcher, ChainUnits, ConnectionState, DecodedCall, EvmAddendum, FetcherConfig,
+    FetcherStatus, OpStackFees, ReorgNotice, SelectorDb, SolanaAddendum, TokenInfo, TokenRegistry,
+    TokenStandard, TokenTransfer, TransactionEnvelope,
+};
@@ -1,6 +1,8 @@
 //! Minimal prelude for SDK consumers.
 
 pub use crate::config::{chain_config, chain_configs};
-pub use crate::data::{BlockPayload, ChainFetcher, FetcherConfig, TxPayload};
+pub use crate::data::{
+    BlockPayload, BlockStart, BlockStream, ChainFetcher, FetcherConfig, TransactionEnvelope,
+};
 pub use crate::render::{BlockRenderer, SlabsAndCubesRenderer};
-pub use crate::sdk::BlockExplorerBuilder;
+pub use crate::sdk::{BlockExplorerBuilder, BuildError};
@@ -9,7 +9,7 @@ use crate::scene::blocks::{BlockRegistry, ExplorerState};
 
 pub use slabs_and_cubes::{
     BlobRenderSettings, ClusterLabelSettings, SlabSettings, SlabsAndCubesRenderer,
-    SlabsAndCubesSettings, TxRenderSettings,
+    SlabsAndCubesSettings, TxRenderSettings, TxShape, TxShapes,
 };
 
 pub trait BlockRenderer: Send + Sync + 'static {
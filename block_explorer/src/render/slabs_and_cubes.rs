@@ -1,11 +1,15 @@
 use std::collections::HashMap;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_8};
 
-use alloy::primitives::{address, Address};
+use alloy::primitives::{address, Address, Selector};
+use alloy_chains::Chain;
 use bevy::prelude::*;
 
-use crate::data::{BlockPayload, TxPayload};
+use crate::data::{BlockPayload, ContractRegistry, SelectorDb, TransactionEnvelope, TxType};
 use crate::render::BlockRenderer;
 use crate::scene::blocks::{BlockEntry, BlockSlab, HeatmapMaterial};
+use crate::scene::deployments::DEPLOYMENT_COLOR;
+use crate::scene::fee_share::FeeShareMaterial;
 use crate::scene::{labels, materials, BlockLabel, TxCube};
 
 #[derive(Clone, Debug)]
@@ -22,12 +26,75 @@ pub struct TxRenderSettings {
     pub cube_base: f32,
     pub min_height: f32,
     pub max_height: f32,
+    /// Colour for transactions whose receipt reports a revert.
+    pub reverted_color: Color,
+    pub shapes: TxShapes,
+}
+
+/// Mesh style for a transaction cube.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TxShape {
+    Cuboid,
+    /// Cuboid scaled to the given fraction of its height.
+    Flattened(f32),
+    /// Cuboid with its vertical edges cut off.
+    Chamfered,
+    /// Cuboid inside a translucent shell.
+    Outlined,
+}
+
+/// Shape per transaction type.
+#[derive(Clone, Debug)]
+pub struct TxShapes {
+    pub legacy: TxShape,
+    pub access_list: TxShape,
+    pub dynamic_fee: TxShape,
+    pub blob: TxShape,
+    pub set_code: TxShape,
+    pub deposit: TxShape,
+    /// Unknown types and transactions without one (Solana).
+    pub other: TxShape,
+}
+
+impl Default for TxShapes {
+    fn default() -> Self {
+        Self {
+            legacy: TxShape::Chamfered,
+            access_list: TxShape::Cuboid,
+            dynamic_fee: TxShape::Cuboid,
+            blob: TxShape::Cuboid,
+            set_code: TxShape::Outlined,
+            deposit: TxShape::Flattened(0.3),
+            other: TxShape::Cuboid,
+        }
+    }
+}
+
+impl TxShapes {
+    pub fn for_type(&self, tx_type: Option<TxType>) -> TxShape {
+        match tx_type {
+            Some(TxType::Legacy) => self.legacy,
+            Some(TxType::AccessList) => self.access_list,
+            Some(TxType::DynamicFee) => self.dynamic_fee,
+            Some(TxType::Blob) => self.blob,
+            Some(TxType::SetCode) => self.set_code,
+            Some(TxType::Deposit) => self.deposit,
+            Some(TxType::Other(_)) | None => self.other,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct ClusterLabelSettings {
     pub max_labels: usize,
     pub quad_height: f32,
+    /// Cluster by recipient and function selector, so swaps, approvals and
+    /// transfers to the same contract form separate clusters.
+    pub by_selector: bool,
+    /// Names selector clusters in their labels.
+    pub selectors: SelectorDb,
+    /// Names known contracts in the labels.
+    pub contracts: ContractRegistry,
 }
 
 #[derive(Clone, Debug)]
@@ -59,10 +126,15 @@ impl Default for SlabsAndCubesSettings {
                 cube_base: 0.2,
                 min_height: 0.1,
                 max_height: 0.6,
+                reverted_color: Color::srgba(0.5, 0.1, 0.1, 0.55),
+                shapes: TxShapes::default(),
             },
             clusters: ClusterLabelSettings {
                 max_labels: 1,
                 quad_height: 0.4,
+                by_selector: false,
+                selectors: SelectorDb::bundled(),
+                contracts: ContractRegistry::bundled(),
             },
             blobs: BlobRenderSettings {
                 sphere_radius: 0.06,
@@ -145,11 +217,15 @@ impl BlockRenderer for SlabsAndCubesRenderer {
             BlockSlab {
                 chain: payload.chain,
                 number: payload.number,
+                hash: payload.hash,
                 gas_used: payload.gas_used,
                 gas_limit: payload.gas_limit,
                 timestamp: payload.timestamp,
                 tx_count: payload.tx_count,
                 l1_origin_number: payload.l1_origin_number,
+                fee_recipient: payload.fee_recipient,
+                extra_data: payload.extra_data.clone(),
+                proposer_payment: payload.proposer_payment(),
             },
         ));
 
@@ -203,7 +279,7 @@ fn spawn_tx_cubes(
         return;
     }
 
-    let ordered_txs = cluster_transactions(&payload.transactions);
+    let ordered_txs = cluster_transactions(&payload.transactions, cluster_settings.by_selector);
     let positions = grid_positions(
         ordered_txs.len(),
         slab_width,
@@ -217,42 +293,65 @@ fn spawn_tx_cubes(
             break;
         }
         let pos = positions[i];
-        let height = tx_height(tx, settings);
+        let shape = settings.shapes.for_type(tx.tx_type());
+        let height = shaped_height(shape, tx_height(tx, settings));
         let y = slab_height / 2.0 + height / 2.0;
-        let material = materials::tx_cube_material(
-            materials_res,
-            tx,
-            payload.transactions.len(),
-            payload.chain,
-        );
+        let material = if tx.reverted() {
+            materials::reverted_tx_material(materials_res, settings.reverted_color)
+        } else {
+            materials::tx_cube_material(
+                materials_res,
+                tx,
+                payload.transactions.len(),
+                payload.chain,
+            )
+        };
 
         let world_pos = Vec3::new(x_offset + pos.0, y, z + pos.1);
+        let fee_share = tx.l1_fee_share().map(|share| FeeShareMaterial {
+            original: material.clone(),
+            fee_share: materials::fee_share_material(materials_res, share),
+        });
         let mut entity_commands = commands.spawn((
-            Mesh3d(meshes.add(Cuboid::new(settings.cube_base, height, settings.cube_base))),
+            Mesh3d(meshes.add(tx_mesh(shape, settings.cube_base, height))),
             MeshMaterial3d(material),
             Transform::from_xyz(x_offset + pos.0, y, z + pos.1),
             Visibility::Visible,
             TxCube {
                 chain: payload.chain,
-                hash: format!("{}", tx.hash),
-                tx_index: tx.tx_index,
-                gas: tx.gas,
-                gas_price: tx.gas_price,
-                value_eth: tx.value_eth,
-                from: tx.from,
-                to: tx.to,
                 block_number: payload.number,
                 world_position: world_pos,
-                blob_count: tx.blob_count,
-                max_fee_per_blob_gas: tx.max_fee_per_blob_gas,
+                tx: (*tx).clone(),
             },
         ));
+        if let Some(fee_share) = fee_share {
+            entity_commands.insert(fee_share);
+        }
+        if tx.deployed_contract().is_some() {
+            spawn_deployment_marker(
+                &mut entity_commands,
+                settings.cube_base,
+                height,
+                meshes,
+                materials_res,
+            );
+        }
+        if shape == TxShape::Outlined {
+            spawn_outline(
+                &mut entity_commands,
+                settings.cube_base,
+                height,
+                meshes,
+                materials_res,
+            );
+        }
 
-        if tx.blob_count > 0 {
+        let blob_count = tx.blob_count();
+        if blob_count > 0 {
             spawn_blob_spheres(
                 &mut entity_commands,
-                tx.blob_count,
-                tx.from,
+                blob_count,
+                tx.sender,
                 height,
                 meshes,
                 materials_res,
@@ -280,16 +379,32 @@ fn spawn_tx_cubes(
     );
 }
 
-/// Groups transactions by `to` address, sorts groups largest-first, and returns
-/// a flat list in cluster order.
-fn cluster_transactions(txs: &[TxPayload]) -> Vec<&TxPayload> {
-    let mut groups: HashMap<Option<Address>, Vec<&TxPayload>> = HashMap::new();
+/// Cluster a transaction belongs to: its recipient, plus its selector when
+/// clustering by selector.
+type ClusterKey = (Option<Address>, Option<Selector>);
+
+fn cluster_key(tx: &TransactionEnvelope, by_selector: bool) -> ClusterKey {
+    let selector = if by_selector { tx.selector() } else { None };
+    (tx.recipient, selector)
+}
+
+/// Groups transactions by [`cluster_key`], sorts groups largest-first, and
+/// returns a flat list in cluster order.
+fn cluster_transactions(
+    txs: &[TransactionEnvelope],
+    by_selector: bool,
+) -> Vec<&TransactionEnvelope> {
+    let mut groups: HashMap<ClusterKey, Vec<&TransactionEnvelope>> = HashMap::new();
     for tx in txs {
-        groups.entry(tx.to).or_default().push(tx);
+        groups
+            .entry(cluster_key(tx, by_selector))
+            .or_default()
+            .push(tx);
     }
 
-    let mut sorted_groups: Vec<(Option<Address>, Vec<&TxPayload>)> = groups.into_iter().collect();
-    sorted_groups.sort_by(|a, b| b.1.len().cmp(&a.1.len()));
+    let mut sorted_groups: Vec<(ClusterKey, Vec<&TransactionEnvelope>)> =
+        groups.into_iter().collect();
+    sorted_groups.sort_by_key(|g| std::cmp::Reverse(g.1.len()));
 
     sorted_groups.into_iter().flat_map(|(_, txs)| txs).collect()
 }
@@ -297,7 +412,7 @@ fn cluster_transactions(txs: &[TxPayload]) -> Vec<&TxPayload> {
 #[allow(clippy::too_many_arguments)]
 fn spawn_cluster_labels(
     commands: &mut Commands,
-    ordered_txs: &[&TxPayload],
+    ordered_txs: &[&TransactionEnvelope],
     positions: &[(f32, f32)],
     z: f32,
     meshes: &mut ResMut<Assets<Mesh>>,
@@ -314,26 +429,22 @@ fn spawn_cluster_labels(
 
     // Identify cluster boundaries and labels
     let mut clusters: Vec<(String, usize, usize)> = Vec::new(); // (label, start_idx, end_idx)
-    let mut current_to = ordered_txs[0].to;
     let mut start = 0;
 
-    for (i, tx) in ordered_txs.iter().enumerate() {
-        if tx.to != current_to {
-            if let Some(addr) = current_to {
-                let label = cluster_label(&addr);
-                clusters.push((label, start, i));
-            }
-            current_to = tx.to;
-            start = i;
+    for i in 1..=ordered_txs.len() {
+        let first = ordered_txs[start];
+        let key = cluster_key(first, settings.by_selector);
+        if i < ordered_txs.len() && cluster_key(ordered_txs[i], settings.by_selector) == key {
+            continue;
         }
-    }
-    if let Some(addr) = current_to {
-        let label = cluster_label(&addr);
-        clusters.push((label, start, ordered_txs.len()));
+        if let Some(label) = cluster_label(tag.chain, first, settings) {
+            clusters.push((label, start, i));
+        }
+        start = i;
     }
 
     // Sort by cluster size and take top N
-    clusters.sort_by(|a, b| (b.2 - b.1).cmp(&(a.2 - a.1)));
+    clusters.sort_by_key(|c| std::cmp::Reverse(c.2 - c.1));
     let labels_to_spawn = clusters.iter().take(settings.max_labels);
 
     for (label, start_idx, end_idx) in labels_to_spawn {
@@ -365,13 +476,32 @@ fn spawn_cluster_labels(
     }
 }
 
-fn cluster_label(addr: &Address) -> String {
-    if let Some(name) = crate::scene::contracts::known_contract_name(addr) {
-        return name.to_string();
-    }
-    // Show abbreviated address (checksummed)
-    let s = format!("{addr}");
-    format!("{}..{}", &s[..6], &s[s.len() - 4..])
+/// Known contract name or abbreviated recipient, followed by the method when
+/// clustering by selector. EVM contract creations read `Deployments`.
+fn cluster_label(
+    chain: Chain,
+    tx: &TransactionEnvelope,
+    settings: &ClusterLabelSettings,
+) -> Option<String> {
+    let Some(recipient) = tx.recipient else {
+        return tx.evm.is_some().then(|| "Deployments".to_string());
+    };
+    let contract = match settings.contracts.name(chain, &recipient) {
+        Some(name) => name.to_string(),
+        None => {
+            // Show abbreviated recipient as the chain writes it
+            let s = tx.recipient_display()?;
+            format!("{}..{}", &s[..6], &s[s.len() - 4..])
+        }
+    };
+    let Some(selector) = tx.selector().filter(|_| settings.by_selector) else {
+        return Some(contract);
+    };
+    let method = settings
+        .selectors
+        .name(&selector)
+        .map_or_else(|| selector.to_string(), str::to_string);
+    Some(format!("{contract}.{method}"))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -437,11 +567,81 @@ fn grid_positions(
     positions
 }
 
-fn tx_height(tx: &TxPayload, settings: &TxRenderSettings) -> f32 {
-    let t = (tx.gas as f32 / 500_000.0).clamp(0.0, 1.0);
+/// Cube height from resource used, or the limit when usage is unknown.
+fn tx_height(tx: &TransactionEnvelope, settings: &TxRenderSettings) -> f32 {
+    let t = (tx.resource_used_or_limit() as f32 / 500_000.0).clamp(0.0, 1.0);
     settings.min_height + (settings.max_height - settings.min_height) * t
 }
 
+/// Height of a cube after its shape is applied.
+fn shaped_height(shape: TxShape, height: f32) -> f32 {
+    match shape {
+        TxShape::Flattened(scale) => height * scale.clamp(0.05, 1.0),
+        _ => height,
+    }
+}
+
+fn tx_mesh(shape: TxShape, base: f32, height: f32) -> Mesh {
+    match shape {
+        TxShape::Chamfered => {
+            // An octagonal prism as wide as the cube across its flat faces.
+            let circumradius = base / 2.0 / FRAC_PI_8.cos();
+            Mesh::from(Extrusion::new(RegularPolygon::new(circumradius, 8), height))
+                .rotated_by(Quat::from_rotation_x(-FRAC_PI_2) * Quat::from_rotation_z(FRAC_PI_8))
+        }
+        _ => Mesh::from(Cuboid::new(base, height, base)),
+    }
+}
+
+/// Gold spire over a cube that deployed a contract.
+fn spawn_deployment_marker(
+    parent: &mut bevy::prelude::EntityCommands,
+    base: f32,
+    cube_height: f32,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials_res: &mut ResMut<Assets<StandardMaterial>>,
+) {
+    let spire_height = base * 1.2;
+    let spire = meshes.add(Cone::new(base * 0.35, spire_height));
+    let material = materials_res.add(StandardMaterial {
+        base_color: DEPLOYMENT_COLOR,
+        emissive: LinearRgba::from(DEPLOYMENT_COLOR) * 0.6,
+        ..default()
+    });
+    parent.with_children(|builder| {
+        builder.spawn((
+            Mesh3d(spire),
+            MeshMaterial3d(material),
+            Transform::from_xyz(0.0, cube_height / 2.0 + spire_height / 2.0 + 0.02, 0.0),
+        ));
+    });
+}
+
+fn spawn_outline(
+    parent: &mut bevy::prelude::EntityCommands,
+    base: f32,
+    height: f32,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials_res: &mut ResMut<Assets<StandardMaterial>>,
+) {
+    let margin = base * 0.2;
+    let shell = meshes.add(Cuboid::new(base + margin, height + margin, base + margin));
+    let material = materials_res.add(StandardMaterial {
+        base_color: Color::srgba(0.9, 0.95, 1.0, 0.18),
+        emissive: LinearRgba::rgb(0.3, 0.4, 0.6),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..default()
+    });
+    parent.with_children(|builder| {
+        builder.spawn((
+            Mesh3d(shell),
+            MeshMaterial3d(material),
+            Transform::default(),
+        ));
+    });
+}
+
 fn spawn_blob_spheres(
     parent: &mut bevy::prelude::EntityCommands,
     blob_count: usize,
@@ -489,3 +689,68 @@ fn spawn_blob_spheres(
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::data::EvmAddendum;
+
+    fn call(index: usize, selector: [u8; 4]) -> TransactionEnvelope {
+        TransactionEnvelope {
+            resource_limit: 50_000,
+            price: 1,
+            recipient: Some(Address::with_last_byte(0xaa)),
+            evm: Some(EvmAddendum {
+                selector: Some(Selector::from(selector)),
+                ..Default::default()
+            }),
+            ..TransactionEnvelope::for_test(index)
+        }
+    }
+
+    #[test]
+    fn clusters_split_by_selector_when_enabled() {
+        let transfer = [0xa9, 0x05, 0x9c, 0xbb];
+        let approve = [0x09, 0x5e, 0xa7, 0xb3];
+        let txs = vec![call(0, approve), call(1, transfer), call(2, transfer)];
+
+        let by_recipient = cluster_transactions(&txs, false);
+        let keys: HashSet<ClusterKey> = by_recipient
+            .iter()
+            .map(|tx| cluster_key(tx, false))
+            .collect();
+        assert_eq!(keys.len(), 1);
+
+        let by_selector = cluster_transactions(&txs, true);
+        let indices: Vec<usize> = by_selector.iter().map(|tx| tx.index).collect();
+        assert_eq!(indices, vec![1, 2, 0]);
+
+        let settings = SlabsAndCubesSettings {
+            clusters: ClusterLabelSettings {
+                by_selector: true,
+                ..SlabsAndCubesSettings::default().clusters
+            },
+            ..Default::default()
+        };
+        let label = cluster_label(Chain::mainnet(), &txs[1], &settings.clusters).unwrap();
+        assert!(label.ends_with(".transfer"), "{label}");
+    }
+
+    #[test]
+    fn shapes_follow_transaction_type() {
+        let shapes = TxShapes::default();
+        let mut tx = call(0, [0; 4]);
+        assert_eq!(shapes.for_type(tx.tx_type()), TxShape::Cuboid);
+
+        tx.evm.as_mut().unwrap().tx_type = Some(0x7e);
+        let deposit = shapes.for_type(tx.tx_type());
+        assert_eq!(deposit, TxShape::Flattened(0.3));
+        assert!((shaped_height(deposit, 0.5) - 0.15).abs() < f32::EPSILON);
+
+        assert_eq!(shapes.for_type(Some(TxType::Legacy)), TxShape::Chamfered);
+        assert_eq!(shapes.for_type(Some(TxType::SetCode)), TxShape::Outlined);
+        assert_eq!(shapes.for_type(Some(TxType::Other(0x64))), TxShape::Cuboid);
+    }
+}
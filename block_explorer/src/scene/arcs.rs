@@ -1,16 +1,23 @@
 //! Value-flow arcs: bezier arcs between transaction endpoints using Bevy Gizmos.
+//!
+//! Native transfers arc blue-to-gold by value, and so do the internal ETH
+//! transfers found by call tracing, on lower arcs. Token `Transfer` events
+//! decoded from receipts arc in a colour derived from the token's address, so
+//! each token's flow through a block reads as one hue.
 
 use std::collections::HashMap;
 
-use alloy::primitives::Address;
+use alloy::primitives::{Address, U256};
 use bevy::prelude::*;
 
+use crate::data::{chain_units, ChainUnits, InternalTransfer, TokenRegistry};
 use crate::scene::BlockSlab;
 use crate::scene::TxCube;
 use crate::ui::inspector::SelectedEntity;
 
 const MAX_ARCS: usize = 200;
-const MIN_VALUE_ETH: f64 = 0.01;
+/// Smallest value worth an arc, as a fraction of the chain's native unit.
+const MIN_VALUE_DIVISOR: u64 = 100;
 
 /// Controls arc visibility. Toggled with `V`.
 #[derive(Resource)]
@@ -26,6 +33,7 @@ impl Default for ArcSettings {
 
 pub fn arc_plugin(app: &mut App) {
     app.init_resource::<ArcSettings>()
+        .init_resource::<TokenRegistry>()
         .add_systems(Update, (toggle_arcs_system, draw_arcs_system));
 }
 
@@ -41,6 +49,7 @@ fn draw_arcs_system(
     selected: Res<SelectedEntity>,
     slabs: Query<&BlockSlab>,
     tx_cubes: Query<&TxCube>,
+    tokens: Res<TokenRegistry>,
 ) {
     if !settings.enabled {
         return;
@@ -54,7 +63,7 @@ fn draw_arcs_system(
 
     // Build address → centroid position map for the selected block
     let mut address_positions: HashMap<Address, (Vec3, u32)> = HashMap::new();
-    let mut arcs_data: Vec<(&TxCube,)> = Vec::new();
+    let mut arcs_data: Vec<(&TxCube, Vec<InternalTransfer>)> = Vec::new();
 
     for tx_cube in tx_cubes.iter() {
         // Filter to selected block if one is selected
@@ -69,18 +78,42 @@ fn draw_arcs_system(
 
         // Accumulate positions for address centroid calculation
         let from_entry = address_positions
-            .entry(tx_cube.from)
+            .entry(tx_cube.tx.sender)
             .or_insert((Vec3::ZERO, 0));
         from_entry.0 += tx_cube.world_position;
         from_entry.1 += 1;
 
-        if let Some(addr) = tx_cube.to {
+        if let Some(addr) = tx_cube.tx.recipient {
             let entry = address_positions.entry(addr).or_insert((Vec3::ZERO, 0));
             entry.0 += tx_cube.world_position;
             entry.1 += 1;
         }
 
-        arcs_data.push((tx_cube,));
+        // Token holders and pools sit at the cubes of the transactions that
+        // moved their tokens.
+        for transfer in tx_cube.tx.token_transfers() {
+            for addr in [transfer.from, transfer.to] {
+                let entry = address_positions.entry(addr).or_insert((Vec3::ZERO, 0));
+                entry.0 += tx_cube.world_position;
+                entry.1 += 1;
+            }
+        }
+
+        // Likewise for contracts that forwarded ETH in internal calls.
+        let internal = tx_cube
+            .tx
+            .trace()
+            .map(|trace| trace.internal_transfers())
+            .unwrap_or_default();
+        for transfer in &internal {
+            for addr in [transfer.from, transfer.to] {
+                let entry = address_positions.entry(addr).or_insert((Vec3::ZERO, 0));
+                entry.0 += tx_cube.world_position;
+                entry.1 += 1;
+            }
+        }
+
+        arcs_data.push((tx_cube, internal));
     }
 
     // Compute centroids
@@ -91,20 +124,21 @@ fn draw_arcs_system(
 
     // Draw arcs
     let mut arc_count = 0;
-    for (tx_cube,) in &arcs_data {
+    for (tx_cube, _) in &arcs_data {
         if arc_count >= MAX_ARCS {
             break;
         }
-        if tx_cube.value_eth < MIN_VALUE_ETH {
+        let units = chain_units(&tx_cube.chain);
+        if tx_cube.tx.value < min_value(&units) {
             continue;
         }
 
-        let Some(to_addr) = tx_cube.to else {
+        let Some(to_addr) = tx_cube.tx.recipient else {
             continue;
         };
 
         let (Some(&from_pos), Some(&to_pos)) =
-            (centroids.get(&tx_cube.from), centroids.get(&to_addr))
+            (centroids.get(&tx_cube.tx.sender), centroids.get(&to_addr))
         else {
             continue;
         };
@@ -114,35 +148,117 @@ fn draw_arcs_system(
         }
 
         // Arc height based on value
-        let arc_height = 1.0 + (tx_cube.value_eth as f32).log10().max(0.0) * 0.5;
-
-        // Color: blue-to-gold by value magnitude
-        let value_t = ((tx_cube.value_eth as f32).log10().clamp(-2.0, 2.0) + 2.0) / 4.0;
-        let color = Color::srgb(
-            0.2 + 0.8 * value_t,
-            0.4 + 0.5 * value_t,
-            1.0 - 0.8 * value_t,
-        );
-
-        // Draw bezier arc
-        let mid = (from_pos + to_pos) / 2.0 + Vec3::Y * arc_height;
-        let control1 = from_pos.lerp(mid, 0.5) + Vec3::Y * arc_height * 0.5;
-        let control2 = mid.lerp(to_pos, 0.5) + Vec3::Y * arc_height * 0.5;
-
-        let segments = 16;
-        let mut prev = from_pos;
-        for s in 1..=segments {
-            let t = s as f32 / segments as f32;
-            let point = cubic_bezier(from_pos, control1, control2, to_pos, t);
-            gizmos.line(prev, point, color);
-            prev = point;
-        }
+        let value = units.display_value(tx_cube.tx.value);
+        let arc_height = 1.0 + (value as f32).log10().max(0.0) * 0.5;
 
+        let color = value_color(value);
+        draw_arc(&mut gizmos, from_pos, to_pos, arc_height, color);
         arc_count += 1;
     }
+
+    // Internal ETH transfers, kept below the top-level arcs.
+    for (tx_cube, internal) in &arcs_data {
+        let units = chain_units(&tx_cube.chain);
+        for transfer in internal {
+            if arc_count >= MAX_ARCS {
+                return;
+            }
+            if transfer.value < min_value(&units) {
+                continue;
+            }
+            let (Some(&from_pos), Some(&to_pos)) =
+                (centroids.get(&transfer.from), centroids.get(&transfer.to))
+            else {
+                continue;
+            };
+            if from_pos.distance(to_pos) < 0.01 {
+                continue;
+            }
+            let value = units.display_value(transfer.value);
+            let arc_height = 0.5 + (value as f32).log10().max(0.0) * 0.25;
+            draw_arc(
+                &mut gizmos,
+                from_pos,
+                to_pos,
+                arc_height,
+                value_color(value),
+            );
+            arc_count += 1;
+        }
+    }
+
+    // Token arcs
+    for (tx_cube, _) in &arcs_data {
+        for transfer in tx_cube.tx.token_transfers() {
+            if arc_count >= MAX_ARCS {
+                return;
+            }
+            let (Some(&from_pos), Some(&to_pos)) =
+                (centroids.get(&transfer.from), centroids.get(&transfer.to))
+            else {
+                continue;
+            };
+            if from_pos.distance(to_pos) < 0.01 {
+                continue;
+            }
+
+            // Height by whole-token amount when the registry knows the decimals.
+            let arc_height = tokens
+                .get(tx_cube.chain, &transfer.token)
+                .map_or(0.8, |info| {
+                    let amount = info.whole_amount(transfer.amount) as f32;
+                    0.8 + amount.log10().clamp(0.0, 6.0) * 0.2
+                });
+            draw_arc(
+                &mut gizmos,
+                from_pos,
+                to_pos,
+                arc_height,
+                token_color(&transfer.token),
+            );
+            arc_count += 1;
+        }
+    }
+}
+
+fn draw_arc(gizmos: &mut Gizmos, from_pos: Vec3, to_pos: Vec3, arc_height: f32, color: Color) {
+    let mid = (from_pos + to_pos) / 2.0 + Vec3::Y * arc_height;
+    let control1 = from_pos.lerp(mid, 0.5) + Vec3::Y * arc_height * 0.5;
+    let control2 = mid.lerp(to_pos, 0.5) + Vec3::Y * arc_height * 0.5;
+
+    let segments = 16;
+    let mut prev = from_pos;
+    for s in 1..=segments {
+        let t = s as f32 / segments as f32;
+        let point = cubic_bezier(from_pos, control1, control2, to_pos, t);
+        gizmos.line(prev, point, color);
+        prev = point;
+    }
+}
+
+fn min_value(units: &ChainUnits) -> U256 {
+    units.native_amount(1) / U256::from(MIN_VALUE_DIVISOR)
+}
+
+/// Blue-to-gold by value magnitude, in the chain's native unit.
+fn value_color(value: f64) -> Color {
+    let value_t = ((value as f32).log10().clamp(-2.0, 2.0) + 2.0) / 4.0;
+    Color::srgb(
+        0.2 + 0.8 * value_t,
+        0.4 + 0.5 * value_t,
+        1.0 - 0.8 * value_t,
+    )
+}
+
+/// Stable per-token hue taken from the contract address.
+fn token_color(token: &Address) -> Color {
+    let hue = u16::from_be_bytes([token[18], token[19]]) as f32 / u16::MAX as f32 * 360.0;
+    Color::hsl(hue, 0.8, 0.6)
 }
 
-fn cubic_bezier(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, t: f32) -> Vec3 {
+/// Point at `t` along the cubic Bézier curve from `p0` to `p3` with control
+/// points `p1` and `p2`.
+pub(crate) fn cubic_bezier(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, t: f32) -> Vec3 {
     let u = 1.0 - t;
     let tt = t * t;
     let uu = u * u;
@@ -10,6 +10,7 @@ use std::collections::HashMap;
 use alloy_chains::Chain;
 use bevy::prelude::*;
 
+use crate::scene::arcs::cubic_bezier;
 use crate::scene::BlockSlab;
 
 /// A link between an L2 block and the L1 block it was derived from.
@@ -159,12 +160,3 @@ fn chain_arc_color(chain: Chain) -> Color {
         _ => Color::srgb(0.5, 0.5, 0.8),
     }
 }
-
-fn cubic_bezier(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, t: f32) -> Vec3 {
-    let u = 1.0 - t;
-    let tt = t * t;
-    let uu = u * u;
-    let uuu = uu * u;
-    let ttt = tt * t;
-    uuu * p0 + 3.0 * uu * t * p1 + 3.0 * u * tt * p2 + ttt * p3
-}
@@ -2,13 +2,20 @@
 
 use std::collections::{HashMap, HashSet};
 
+use alloy::primitives::{Address, Bytes, B256};
 use alloy_chains::Chain;
 
-use crate::data::{BlockChannel, RecordBuffer};
+#[cfg(feature = "store")]
+use crate::data::StoreWriter;
+use crate::data::{BlockChannel, BlockPayload, ProposerPayment, Recorder};
 use crate::render::RendererResource;
 use crate::scene::blob_links::BlobLinkRegistry;
-use crate::scene::BlockLabel;
+use crate::scene::builders::{BuilderColorState, BuilderMaterial};
+use crate::scene::fee_share::FeeShareMaterial;
+use crate::scene::gaps::{spawn_ghost_slab, GapTracker};
+use crate::scene::{BlockLabel, TxCube};
 use crate::ui::HudState;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 
 const DEFAULT_LANE_SPACING: f32 = 15.0;
@@ -18,6 +25,10 @@ const DEFAULT_LANE_SPACING: f32 = 15.0;
 const Z_PER_SECOND: f32 = 2.0;
 /// Rolling time window in seconds. Blocks older than this are despawned.
 const WINDOW_SECONDS: u64 = 120;
+/// How long orphaned blocks stay visible after a reorg before despawning.
+const ORPHAN_FADE_SECONDS: f32 = 1.5;
+/// Upward drift of orphaned entities, in units per second.
+const ORPHAN_RISE_SPEED: f32 = 2.0;
 
 /// Per-chain lane positioning state.
 pub struct LaneState {
@@ -67,6 +78,11 @@ impl ExplorerState {
         let reference = *self.reference_timestamp.get_or_insert(timestamp);
         -((timestamp as f64 - reference as f64) * Z_PER_SECOND as f64) as f32
     }
+
+    /// Forgets the reference timestamp so the next block starts at Z = 0.
+    pub fn reset_timeline(&mut self) {
+        self.reference_timestamp = None;
+    }
 }
 
 /// Marker + data for slab entities.
@@ -74,11 +90,27 @@ impl ExplorerState {
 pub struct BlockSlab {
     pub chain: Chain,
     pub number: u64,
+    pub hash: B256,
     pub gas_used: u64,
     pub gas_limit: u64,
     pub timestamp: u64,
     pub tx_count: u32,
     pub l1_origin_number: Option<u64>,
+    pub fee_recipient: Option<Address>,
+    pub extra_data: Bytes,
+    pub proposer_payment: Option<ProposerPayment>,
+}
+
+/// Clears every lane, e.g. before switching to another block source. The
+/// blocks fade out like reorged ones and the next block starts a new timeline.
+#[derive(Event)]
+pub struct ClearScene;
+
+/// Marks entities of a block orphaned by a reorg. They drift upward in a
+/// warning colour and are despawned when the timer finishes.
+#[derive(Component)]
+pub struct Orphaned {
+    timer: Timer,
 }
 
 /// Entry in the block registry for timeline navigation.
@@ -123,8 +155,9 @@ pub fn heatmap_plugin(app: &mut App) {
 fn heatmap_toggle_system(
     keys: Res<ButtonInput<KeyCode>>,
     mut state: ResMut<HeatmapState>,
+    builder: Option<Res<BuilderColorState>>,
     mut commands: Commands,
-    slabs: Query<(Entity, &HeatmapMaterial)>,
+    slabs: Query<(Entity, &HeatmapMaterial, Option<&BuilderMaterial>)>,
     tx_cubes: Query<Entity, With<crate::scene::TxCube>>,
 ) {
     if !keys.just_pressed(KeyCode::KeyH) {
@@ -132,8 +165,14 @@ fn heatmap_toggle_system(
     }
 
     state.enabled = !state.enabled;
+    let builder_on = builder.is_some_and(|builder| builder.enabled);
 
-    for (entity, heatmap_mat) in &slabs {
+    for (entity, heatmap_mat, builder_mat) in &slabs {
+        // Builder colours win while that mode is on; its toggle restores
+        // the heatmap when it's switched off.
+        if builder_on && builder_mat.is_some() {
+            continue;
+        }
         let mat = if state.enabled {
             heatmap_mat.heatmap.clone()
         } else {
@@ -157,6 +196,7 @@ const MAX_BLOCKS_PER_FRAME: usize = 5;
 pub fn setup_scene(mut commands: Commands) {
     commands.insert_resource(ExplorerState::default());
     commands.insert_resource(BlockRegistry::default());
+    commands.insert_resource(GapTracker::default());
     let mid_x = DEFAULT_LANE_SPACING / 2.0;
     commands.spawn((
         Camera3d::default(),
@@ -172,6 +212,14 @@ pub fn setup_scene(mut commands: Commands) {
     });
 }
 
+/// Live (non-orphaned) block entities, used to roll back the scene on reorg.
+#[derive(SystemParam)]
+pub struct SceneBlocks<'w, 's> {
+    slabs: Query<'w, 's, (Entity, &'static BlockSlab), Without<Orphaned>>,
+    cubes: Query<'w, 's, (Entity, &'static TxCube), Without<Orphaned>>,
+    labels: Query<'w, 's, (Entity, &'static BlockLabel), Without<Orphaned>>,
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn ingest_blocks(
     mut commands: Commands,
@@ -183,16 +231,49 @@ pub fn ingest_blocks(
     mut hud_state: ResMut<HudState>,
     mut images: ResMut<Assets<Image>>,
     mut registry: ResMut<BlockRegistry>,
+    mut gaps: ResMut<GapTracker>,
     blob_links: Option<ResMut<BlobLinkRegistry>>,
-    mut record_buffer: Option<ResMut<RecordBuffer>>,
+    recorder: Option<Res<Recorder>>,
+    #[cfg(feature = "store")] store_writer: Option<Res<StoreWriter>>,
+    scene_blocks: SceneBlocks,
+    mut deferred: Local<Option<BlockPayload>>,
 ) {
     let mut received = 0usize;
     let mut blob_links = blob_links;
     while received < MAX_BLOCKS_PER_FRAME {
-        match channel.0.try_recv() {
+        let next = match deferred.take() {
+            Some(payload) => Ok(payload),
+            None => channel.0.try_recv(),
+        };
+        match next {
             Ok(payload) => {
-                if let Some(ref mut buf) = record_buffer {
-                    buf.payloads.push(payload.clone());
+                if let Some(fork) = payload.reorg_fork() {
+                    // Blocks spawned earlier this frame aren't visible to the
+                    // scene queries yet, so roll back at the start of next frame.
+                    if received > 0 {
+                        *deferred = Some(payload);
+                        break;
+                    }
+                    orphan_blocks_from(
+                        &mut commands,
+                        &scene_blocks,
+                        &mut materials_res,
+                        &mut registry,
+                        blob_links.as_deref_mut(),
+                        payload.chain,
+                        fork,
+                    );
+                    for ghost in gaps.rewind(payload.chain, fork) {
+                        commands.entity(ghost).despawn();
+                    }
+                }
+
+                if let Some(ref recorder) = recorder {
+                    recorder.send(payload.clone());
+                }
+                #[cfg(feature = "store")]
+                if let Some(ref writer) = store_writer {
+                    writer.send(payload.clone());
                 }
 
                 hud_state.update_from_payload(&payload);
@@ -203,6 +284,24 @@ pub fn ingest_blocks(
                     links.register(l1_origin, payload.chain, payload.number);
                 }
 
+                if let Some(ghost) = gaps.take_ghost(payload.chain, payload.number) {
+                    commands.entity(ghost).despawn();
+                }
+                for (number, timestamp) in
+                    gaps.observe(payload.chain, payload.number, payload.timestamp)
+                {
+                    let ghost = spawn_ghost_slab(
+                        &mut commands,
+                        &mut meshes,
+                        &mut materials_res,
+                        &mut state,
+                        payload.chain,
+                        number,
+                        timestamp,
+                    );
+                    gaps.insert_ghost(payload.chain, number, timestamp, ghost);
+                }
+
                 let x_offset = state.lane_for(payload.chain).x_offset;
                 renderer.0.spawn_block(
                     &mut commands,
@@ -221,14 +320,109 @@ pub fn ingest_blocks(
     }
 }
 
-/// Flushes the record buffer to disk when the app exits.
-pub fn flush_record_buffer(
-    mut exit_events: EventReader<AppExit>,
-    buffer: Option<Res<RecordBuffer>>,
+/// Marks every live block of `chain` numbered `fork` or above as orphaned and
+/// drops it from the registries so the replacement branch can take its place.
+fn orphan_blocks_from(
+    commands: &mut Commands,
+    scene_blocks: &SceneBlocks,
+    materials: &mut Assets<StandardMaterial>,
+    registry: &mut BlockRegistry,
+    blob_links: Option<&mut BlobLinkRegistry>,
+    chain: Chain,
+    fork: u64,
+) {
+    let orphaned = |c: Chain, n: u64| c == chain && n >= fork;
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgba(0.9, 0.2, 0.15, 0.45),
+        emissive: LinearRgba::rgb(0.6, 0.05, 0.02),
+        alpha_mode: AlphaMode::Blend,
+        ..default()
+    });
+    let marker = || Orphaned {
+        timer: Timer::from_seconds(ORPHAN_FADE_SECONDS, TimerMode::Once),
+    };
+
+    let mut removed: HashSet<(Chain, u64)> = HashSet::new();
+    for (entity, slab) in &scene_blocks.slabs {
+        if orphaned(slab.chain, slab.number) {
+            commands
+                .entity(entity)
+                .remove::<HeatmapMaterial>()
+                .insert((marker(), MeshMaterial3d(material.clone())));
+            removed.insert((slab.chain, slab.number));
+        }
+    }
+    for (entity, cube) in &scene_blocks.cubes {
+        if orphaned(cube.chain, cube.block_number) {
+            commands
+                .entity(entity)
+                .remove::<FeeShareMaterial>()
+                .insert((marker(), MeshMaterial3d(material.clone())));
+        }
+    }
+    for (entity, label) in &scene_blocks.labels {
+        if orphaned(label.chain, label.block_number) {
+            commands.entity(entity).insert(marker());
+        }
+    }
+
+    registry.entries.retain(|e| !orphaned(e.chain, e.number));
+    if let Some(links) = blob_links {
+        links.remove_blocks(&removed);
+    }
+}
+
+/// Handles [`ClearScene`]: orphans every lane and resets the timeline.
+#[allow(clippy::too_many_arguments)]
+pub fn clear_scene(
+    mut events: EventReader<ClearScene>,
+    mut commands: Commands,
+    mut state: ResMut<ExplorerState>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut registry: ResMut<BlockRegistry>,
+    mut gaps: ResMut<GapTracker>,
+    mut blob_links: Option<ResMut<BlobLinkRegistry>>,
+    scene_blocks: SceneBlocks,
+) {
+    if events.read().count() == 0 {
+        return;
+    }
+    for chain in state.lanes.keys().copied().collect::<Vec<_>>() {
+        orphan_blocks_from(
+            &mut commands,
+            &scene_blocks,
+            &mut materials,
+            &mut registry,
+            blob_links.as_deref_mut(),
+            chain,
+            0,
+        );
+        for ghost in gaps.rewind(chain, 0) {
+            commands.entity(ghost).despawn();
+        }
+    }
+    state.reset_timeline();
+}
+
+/// Lifts orphaned entities out of the lane and despawns them once faded.
+pub fn fade_orphaned_blocks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut orphans: Query<(Entity, &mut Orphaned, &mut Transform)>,
 ) {
+    for (entity, mut orphaned, mut transform) in &mut orphans {
+        transform.translation.y += ORPHAN_RISE_SPEED * time.delta_secs();
+        if orphaned.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Writes out the rest of the recording when the app exits.
+pub fn finish_recording(mut exit_events: EventReader<AppExit>, recorder: Option<ResMut<Recorder>>) {
     if exit_events.read().next().is_some() {
-        if let Some(buf) = buffer {
-            buf.flush();
+        if let Some(mut recorder) = recorder {
+            recorder.finish();
         }
     }
 }
@@ -237,10 +431,11 @@ pub fn flush_record_buffer(
 /// All chains share the same temporal window so lanes stay aligned.
 pub fn cleanup_old_blocks(
     mut commands: Commands,
-    slabs: Query<(Entity, &BlockSlab)>,
-    cubes: Query<(Entity, &crate::scene::TxCube)>,
-    labels: Query<(Entity, &BlockLabel)>,
+    slabs: Query<(Entity, &BlockSlab), Without<Orphaned>>,
+    cubes: Query<(Entity, &TxCube), Without<Orphaned>>,
+    labels: Query<(Entity, &BlockLabel), Without<Orphaned>>,
     mut registry: ResMut<BlockRegistry>,
+    mut gaps: ResMut<GapTracker>,
     blob_link_registry: Option<ResMut<BlobLinkRegistry>>,
 ) {
     // Find the latest timestamp across all chains
@@ -250,6 +445,10 @@ pub fn cleanup_old_blocks(
     }
     let cutoff = latest_ts.saturating_sub(WINDOW_SECONDS);
 
+    for ghost in gaps.expire(cutoff) {
+        commands.entity(ghost).despawn();
+    }
+
     let mut removed: HashSet<(Chain, u64)> = HashSet::new();
 
     for (entity, slab) in &slabs {
@@ -287,6 +486,17 @@ pub fn cleanup_old_blocks(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::data::ReorgNotice;
+    use crate::render::SlabsAndCubesRenderer;
+    use crate::scene::gaps::GhostSlab;
+
+    fn payload(number: u64, reorg: Option<ReorgNotice>) -> BlockPayload {
+        BlockPayload {
+            gas_used: 10_000_000,
+            reorg,
+            ..BlockPayload::for_test(Chain::mainnet(), number)
+        }
+    }
 
     #[test]
     fn setup_scene_inserts_resources_and_entities() {
@@ -305,4 +515,114 @@ mod tests {
         assert!(camera_count >= 1);
         assert!(light_count >= 1);
     }
+
+    #[test]
+    fn reorg_payload_orphans_blocks_from_fork() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut app = App::new();
+        app.init_resource::<Assets<Mesh>>()
+            .init_resource::<Assets<StandardMaterial>>()
+            .init_resource::<Assets<Image>>()
+            .init_resource::<HudState>()
+            .insert_resource(BlockChannel(rx))
+            .insert_resource(RendererResource::new(SlabsAndCubesRenderer::default()))
+            .add_systems(Startup, setup_scene)
+            .add_systems(Update, ingest_blocks);
+
+        for n in 10..=12 {
+            tx.send(payload(n, None)).unwrap();
+        }
+        app.update();
+        tx.send(payload(
+            11,
+            Some(ReorgNotice {
+                depth: 2,
+                fork: None,
+            }),
+        ))
+        .unwrap();
+        app.update();
+
+        let world = app.world_mut();
+        let mut orphaned: Vec<u64> = world
+            .query_filtered::<&BlockSlab, With<Orphaned>>()
+            .iter(world)
+            .map(|slab| slab.number)
+            .collect();
+        orphaned.sort_unstable();
+        let live = world
+            .query_filtered::<&BlockSlab, Without<Orphaned>>()
+            .iter(world)
+            .count();
+
+        assert_eq!(orphaned, vec![11, 12]);
+        assert_eq!(live, 2);
+
+        let registry = world.resource::<BlockRegistry>();
+        let numbers: Vec<u64> = registry.entries.iter().map(|e| e.number).collect();
+        assert_eq!(numbers, vec![10, 11]);
+        assert_eq!(world.resource::<HudState>().last_reorg.unwrap().depth, 2);
+    }
+
+    #[test]
+    fn skipped_block_gets_ghost_until_it_arrives() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut app = App::new();
+        app.init_resource::<Assets<Mesh>>()
+            .init_resource::<Assets<StandardMaterial>>()
+            .init_resource::<Assets<Image>>()
+            .init_resource::<HudState>()
+            .insert_resource(BlockChannel(rx))
+            .insert_resource(RendererResource::new(SlabsAndCubesRenderer::default()))
+            .add_systems(Startup, setup_scene)
+            .add_systems(Update, ingest_blocks);
+
+        let ghosts = |app: &mut App| {
+            let world = app.world_mut();
+            world
+                .query::<&GhostSlab>()
+                .iter(world)
+                .map(|ghost| ghost.number)
+                .collect::<Vec<_>>()
+        };
+
+        tx.send(payload(10, None)).unwrap();
+        tx.send(payload(12, None)).unwrap();
+        app.update();
+        assert_eq!(ghosts(&mut app), vec![11]);
+
+        tx.send(payload(11, None)).unwrap();
+        app.update();
+        assert!(ghosts(&mut app).is_empty());
+    }
+
+    #[test]
+    fn clear_scene_orphans_every_lane() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut app = App::new();
+        app.init_resource::<Assets<Mesh>>()
+            .init_resource::<Assets<StandardMaterial>>()
+            .init_resource::<Assets<Image>>()
+            .init_resource::<HudState>()
+            .insert_resource(BlockChannel(rx))
+            .insert_resource(RendererResource::new(SlabsAndCubesRenderer::default()))
+            .add_event::<ClearScene>()
+            .add_systems(Startup, setup_scene)
+            .add_systems(Update, (clear_scene.before(ingest_blocks), ingest_blocks));
+
+        for n in 10..=12 {
+            tx.send(payload(n, None)).unwrap();
+        }
+        app.update();
+        app.world_mut().send_event(ClearScene);
+        app.update();
+
+        let world = app.world_mut();
+        let live = world
+            .query_filtered::<&BlockSlab, Without<Orphaned>>()
+            .iter(world)
+            .count();
+        assert_eq!(live, 0);
+        assert!(world.resource::<BlockRegistry>().entries.is_empty());
+    }
 }
@@ -0,0 +1,258 @@
+//! Builder colour mode and rolling builder market share.
+//!
+//! Each slab's builder is named through the [`BuilderRegistry`]. With the
+//! mode on (toggled with `K`), slabs take a colour per builder instead of
+//! their fullness shade, and keep it over the heatmap while both modes are
+//! on. [`BuilderShare`] counts blocks per builder over the slabs still in the
+//! scene, for the HUD.
+
+use std::collections::HashMap;
+
+use alloy_chains::Chain;
+use bevy::prelude::*;
+
+use crate::data::BuilderRegistry;
+use crate::scene::blocks::{HeatmapMaterial, HeatmapState, Orphaned};
+use crate::scene::BlockSlab;
+
+/// Name used for blocks the registry can't attribute.
+pub const UNKNOWN_BUILDER: &str = "Unknown";
+
+/// Original and builder-colour materials for a slab with a fee recipient.
+#[derive(Component)]
+pub struct BuilderMaterial {
+    pub original: Handle<StandardMaterial>,
+    pub builder: Handle<StandardMaterial>,
+}
+
+/// Global toggle for the builder colour mode.
+#[derive(Resource, Default)]
+pub struct BuilderColorState {
+    pub enabled: bool,
+}
+
+/// Blocks per builder among each chain's live slabs, most blocks first.
+/// Chains where a single party builds every block (L2 sequencers) are left
+/// out.
+#[derive(Resource, Default)]
+pub struct BuilderShare {
+    pub chains: Vec<(Chain, Vec<(String, usize)>)>,
+}
+
+pub fn builder_plugin(app: &mut App) {
+    app.init_resource::<BuilderRegistry>()
+        .init_resource::<BuilderColorState>()
+        .init_resource::<BuilderShare>()
+        .add_systems(
+            Update,
+            (
+                builder_toggle_system,
+                assign_builder_materials,
+                update_builder_share,
+            ),
+        );
+}
+
+/// Names a slab's builder; `None` where the chain has no fee recipient.
+pub(crate) fn slab_builder(registry: &BuilderRegistry, slab: &BlockSlab) -> Option<String> {
+    slab.fee_recipient?;
+    Some(
+        registry
+            .identify(slab.fee_recipient, &slab.extra_data)
+            .unwrap_or_else(|| UNKNOWN_BUILDER.to_string()),
+    )
+}
+
+fn builder_toggle_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut state: ResMut<BuilderColorState>,
+    heatmap: Option<Res<HeatmapState>>,
+    mut commands: Commands,
+    slabs: Query<(Entity, &BuilderMaterial, Option<&HeatmapMaterial>)>,
+) {
+    if !keys.just_pressed(KeyCode::KeyK) {
+        return;
+    }
+
+    state.enabled = !state.enabled;
+    let heatmap_on = heatmap.is_some_and(|heatmap| heatmap.enabled);
+
+    for (entity, mat, heatmap_mat) in &slabs {
+        let handle = match (state.enabled, heatmap_mat) {
+            (true, _) => mat.builder.clone(),
+            (false, Some(heatmap_mat)) if heatmap_on => heatmap_mat.heatmap.clone(),
+            (false, _) => mat.original.clone(),
+        };
+        commands.entity(entity).insert(MeshMaterial3d(handle));
+    }
+}
+
+/// Gives new slabs their builder material, shared between slabs of the same
+/// builder.
+fn assign_builder_materials(
+    state: Res<BuilderColorState>,
+    registry: Res<BuilderRegistry>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut cache: Local<HashMap<String, Handle<StandardMaterial>>>,
+    mut commands: Commands,
+    slabs: Query<(Entity, &BlockSlab, &HeatmapMaterial), Added<BlockSlab>>,
+) {
+    for (entity, slab, heatmap_mat) in &slabs {
+        let Some(builder) = slab_builder(&registry, slab) else {
+            continue;
+        };
+        let handle = cache
+            .entry(builder)
+            .or_insert_with_key(|builder| {
+                materials.add(StandardMaterial {
+                    base_color: builder_color(builder),
+                    ..default()
+                })
+            })
+            .clone();
+        let mut slab_commands = commands.entity(entity);
+        if state.enabled {
+            slab_commands.insert(MeshMaterial3d(handle.clone()));
+        }
+        slab_commands.insert(BuilderMaterial {
+            original: heatmap_mat.original.clone(),
+            builder: handle,
+        });
+    }
+}
+
+/// Recounts the share whenever slabs enter or leave the scene.
+fn update_builder_share(
+    registry: Res<BuilderRegistry>,
+    mut share: ResMut<BuilderShare>,
+    added: Query<(), Added<BlockSlab>>,
+    orphaned: Query<(), Added<Orphaned>>,
+    mut removed: RemovedComponents<BlockSlab>,
+    slabs: Query<&BlockSlab, Without<Orphaned>>,
+) {
+    let removed_any = removed.read().count() > 0;
+    if added.is_empty() && orphaned.is_empty() && !removed_any {
+        return;
+    }
+    share.chains = builder_shares(
+        slabs
+            .iter()
+            .filter_map(|slab| Some((slab.chain, slab_builder(&registry, slab)?))),
+    );
+}
+
+/// Counts blocks per builder and chain, dropping single-builder chains.
+fn builder_shares(
+    blocks: impl Iterator<Item = (Chain, String)>,
+) -> Vec<(Chain, Vec<(String, usize)>)> {
+    let mut counts: HashMap<Chain, HashMap<String, usize>> = HashMap::new();
+    for (chain, builder) in blocks {
+        *counts.entry(chain).or_default().entry(builder).or_default() += 1;
+    }
+    let mut chains: Vec<(Chain, Vec<(String, usize)>)> = counts
+        .into_iter()
+        .filter(|(_, builders)| builders.len() > 1)
+        .map(|(chain, builders)| {
+            let mut builders: Vec<(String, usize)> = builders.into_iter().collect();
+            builders.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            (chain, builders)
+        })
+        .collect();
+    chains.sort_by_key(|(chain, _)| chain.id());
+    chains
+}
+
+/// Stable per-builder hue; unattributed blocks stay grey.
+pub(crate) fn builder_color(builder: &str) -> Color {
+    if builder == UNKNOWN_BUILDER {
+        return Color::srgb(0.35, 0.35, 0.38);
+    }
+    // FNV-1a, so the hue doesn't change between runs.
+    let hash = builder.bytes().fold(0x811c_9dc5u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    });
+    Color::hsl((hash % 360) as f32, 0.65, 0.5)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shares_count_per_chain_and_skip_single_builder_chains() {
+        let blocks = [
+            (Chain::mainnet(), "Titan"),
+            (Chain::mainnet(), "beaverbuild"),
+            (Chain::mainnet(), "Titan"),
+            (Chain::base_mainnet(), "Sequencer"),
+            (Chain::base_mainnet(), "Sequencer"),
+        ]
+        .map(|(chain, builder)| (chain, builder.to_string()));
+
+        let shares = builder_shares(blocks.into_iter());
+        assert_eq!(
+            shares,
+            vec![(
+                Chain::mainnet(),
+                vec![("Titan".to_string(), 2), ("beaverbuild".to_string(), 1)]
+            )]
+        );
+    }
+
+    #[test]
+    fn builder_colors_are_stable_and_distinct() {
+        assert_eq!(builder_color("Titan"), builder_color("Titan"));
+        assert_ne!(builder_color("Titan"), builder_color("beaverbuild"));
+        assert_eq!(
+            builder_color(UNKNOWN_BUILDER),
+            Color::srgb(0.35, 0.35, 0.38)
+        );
+    }
+
+    #[test]
+    fn builder_colours_stay_on_top_of_the_heatmap() {
+        let mut app = App::new();
+        app.init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<Assets<StandardMaterial>>();
+        crate::scene::blocks::heatmap_plugin(&mut app);
+        builder_plugin(&mut app);
+
+        let mut materials = app.world_mut().resource_mut::<Assets<StandardMaterial>>();
+        let original = materials.add(StandardMaterial::default());
+        let heatmap = materials.add(StandardMaterial::default());
+        let builder = materials.add(StandardMaterial::default());
+        let slab = app
+            .world_mut()
+            .spawn((
+                MeshMaterial3d(original.clone()),
+                HeatmapMaterial {
+                    original: original.clone(),
+                    heatmap: heatmap.clone(),
+                },
+                BuilderMaterial {
+                    original: original.clone(),
+                    builder: builder.clone(),
+                },
+            ))
+            .id();
+
+        let mut press = |key: KeyCode| {
+            let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+            keys.reset_all();
+            keys.press(key);
+            app.update();
+            app.world()
+                .get::<MeshMaterial3d<StandardMaterial>>(slab)
+                .unwrap()
+                .0
+                .clone()
+        };
+
+        assert_eq!(press(KeyCode::KeyK), builder);
+        assert_eq!(press(KeyCode::KeyH), builder);
+        assert_eq!(press(KeyCode::KeyK), heatmap);
+        assert_eq!(press(KeyCode::KeyK), builder);
+        assert_eq!(press(KeyCode::KeyH), builder);
+        assert_eq!(press(KeyCode::KeyK), original);
+    }
+}
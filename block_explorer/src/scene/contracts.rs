@@ -1,46 +1,205 @@
-//! Well-known Ethereum contract registry.
-
-use alloy::primitives::{address, Address};
-
-/// Well-known contract addresses and their human-readable names.
-const KNOWN_CONTRACTS: &[(Address, &str)] = &[
-    (address!("dac17f958d2ee523a2206206994597c13d831ec7"), "USDT"),
-    (address!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"), "USDC"),
-    (address!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"), "WETH"),
-    (
-        address!("7a250d5630b4cf539739df2c5dacb4c659f2488d"),
-        "UniV2Router",
-    ),
-    (
-        address!("e592427a0aece92de3edee1f18e0157c05861564"),
-        "UniV3Router",
-    ),
-    (
-        address!("68b3465833fb72a70ecdf485e0e4c7bd8665fc45"),
-        "UniRouter2",
-    ),
-    (
-        address!("3fc91a3afd70395cd496c647d5a6cc9d4b2b7fad"),
-        "UniRouter",
-    ),
-    (
-        address!("1111111254eeb25477b68fb85ed929f73a960582"),
-        "1inch",
-    ),
-    (
-        address!("881d40237659c251811cec9c364ef91dc08d300c"),
-        "Metamask",
-    ),
-    (
-        address!("7d1afa7b718fb893db30a3abc0cfc608aacfebb0"),
-        "MATIC",
-    ),
-];
-
-/// Returns a human-readable name for well-known contracts, or `None`.
-pub fn known_contract_name(addr: &Address) -> Option<&'static str> {
-    KNOWN_CONTRACTS
-        .iter()
-        .find(|(a, _)| a == addr)
-        .map(|(_, name)| *name)
+//! Contract category colour mode for transaction cubes.
+//!
+//! Cubes whose recipient is in the [`ContractRegistry`] can be recoloured by
+//! the contract's category (DEX, bridge, stablecoin, ...). With the mode on
+//! (toggled with `C`), labelled cubes take their category colour and the
+//! rest are dimmed to grey. Category colours win over the L1 fee share mode
+//! while both are on.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::data::{ContractCategory, ContractRegistry};
+use crate::scene::fee_share::{FeeShareMaterial, FeeShareState};
+use crate::scene::TxCube;
+
+/// Original and category materials for a transaction cube.
+#[derive(Component)]
+pub struct CategoryMaterial {
+    pub original: Handle<StandardMaterial>,
+    pub category: Handle<StandardMaterial>,
+}
+
+/// Global toggle for the category colour mode.
+#[derive(Resource, Default)]
+pub struct CategoryColorState {
+    pub enabled: bool,
+}
+
+pub fn category_plugin(app: &mut App) {
+    app.init_resource::<ContractRegistry>()
+        .init_resource::<CategoryColorState>()
+        .add_systems(Update, (category_toggle_system, assign_category_materials));
+}
+
+fn category_toggle_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut state: ResMut<CategoryColorState>,
+    fee_share: Option<Res<FeeShareState>>,
+    mut commands: Commands,
+    cubes: Query<(Entity, &CategoryMaterial, Option<&FeeShareMaterial>)>,
+) {
+    if !keys.just_pressed(KeyCode::KeyC) {
+        return;
+    }
+
+    state.enabled = !state.enabled;
+    let fee_share_on = fee_share.is_some_and(|fee_share| fee_share.enabled);
+
+    for (entity, mat, fee_share_mat) in &cubes {
+        let handle = match (state.enabled, fee_share_mat) {
+            (true, _) => mat.category.clone(),
+            (false, Some(fee_share_mat)) if fee_share_on => fee_share_mat.fee_share.clone(),
+            (false, _) => mat.original.clone(),
+        };
+        commands.entity(entity).insert(MeshMaterial3d(handle));
+    }
+}
+
+/// A freshly spawned cube, with its fee-share materials on OP Stack lanes.
+type NewCube<'a> = (
+    Entity,
+    &'a TxCube,
+    &'a MeshMaterial3d<StandardMaterial>,
+    Option<&'a FeeShareMaterial>,
+);
+
+/// Gives new cubes their category material, shared between cubes of the
+/// same category. Cubes calling unlabelled contracts share a grey one.
+fn assign_category_materials(
+    state: Res<CategoryColorState>,
+    registry: Res<ContractRegistry>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut cache: Local<HashMap<Option<ContractCategory>, Handle<StandardMaterial>>>,
+    mut commands: Commands,
+    cubes: Query<NewCube, Added<TxCube>>,
+) {
+    for (entity, cube, material, fee_share_mat) in &cubes {
+        let category = cube
+            .tx
+            .recipient
+            .and_then(|to| registry.category(cube.chain, &to));
+        let handle = cache
+            .entry(category)
+            .or_insert_with(|| {
+                materials.add(StandardMaterial {
+                    base_color: category.map_or(UNLABELLED_COLOR, category_color),
+                    ..default()
+                })
+            })
+            .clone();
+        let mut cube_commands = commands.entity(entity);
+        if state.enabled {
+            cube_commands.insert(MeshMaterial3d(handle.clone()));
+        }
+        cube_commands.insert(CategoryMaterial {
+            original: fee_share_mat.map_or_else(|| material.0.clone(), |mat| mat.original.clone()),
+            category: handle,
+        });
+    }
+}
+
+/// Colour of cubes whose recipient has no label.
+const UNLABELLED_COLOR: Color = Color::srgba(0.3, 0.3, 0.33, 0.6);
+
+pub(crate) fn category_color(category: ContractCategory) -> Color {
+    match category {
+        ContractCategory::Dex => Color::srgb(0.95, 0.3, 0.6),
+        ContractCategory::Aggregator => Color::srgb(0.7, 0.35, 0.9),
+        ContractCategory::Bridge => Color::srgb(0.2, 0.55, 1.0),
+        ContractCategory::Stablecoin => Color::srgb(0.2, 0.8, 0.45),
+        ContractCategory::Token => Color::srgb(0.95, 0.8, 0.25),
+        ContractCategory::Lending => Color::srgb(0.2, 0.8, 0.85),
+        ContractCategory::Wallet => Color::srgb(0.95, 0.55, 0.2),
+        ContractCategory::Batcher => Color::srgb(0.45, 0.4, 1.0),
+        ContractCategory::System => Color::srgb(0.75, 0.75, 0.8),
+        ContractCategory::Other => Color::srgb(0.55, 0.5, 0.45),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn categories_have_distinct_colors() {
+        let colors: HashSet<[u8; 4]> = ContractCategory::ALL
+            .iter()
+            .map(|category| category_color(*category).to_srgba().to_u8_array())
+            .collect();
+        assert_eq!(colors.len(), ContractCategory::ALL.len());
+    }
+
+    #[test]
+    fn category_colours_stay_on_top_of_fee_share() {
+        let mut app = App::new();
+        app.init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<Assets<StandardMaterial>>();
+        crate::scene::fee_share::fee_share_plugin(&mut app);
+        category_plugin(&mut app);
+
+        let mut materials = app.world_mut().resource_mut::<Assets<StandardMaterial>>();
+        let original = materials.add(StandardMaterial::default());
+        let fee_share = materials.add(StandardMaterial::default());
+        let category = materials.add(StandardMaterial::default());
+        let materials = (
+            MeshMaterial3d(original.clone()),
+            FeeShareMaterial {
+                original: original.clone(),
+                fee_share: fee_share.clone(),
+            },
+            CategoryMaterial {
+                original: original.clone(),
+                category: category.clone(),
+            },
+        );
+        let cube = app.world_mut().spawn(materials).id();
+
+        let press = |app: &mut App, key: KeyCode| {
+            let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+            keys.reset_all();
+            keys.press(key);
+            app.update();
+        };
+        let material = |app: &App, entity: Entity| {
+            app.world()
+                .get::<MeshMaterial3d<StandardMaterial>>(entity)
+                .unwrap()
+                .0
+                .clone()
+        };
+
+        press(&mut app, KeyCode::KeyC);
+        assert_eq!(material(&app, cube), category);
+        press(&mut app, KeyCode::KeyL);
+        assert_eq!(material(&app, cube), category);
+
+        // A cube spawned with both modes on keeps its category colour.
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .reset_all();
+        let new_cube = app
+            .world_mut()
+            .spawn((
+                MeshMaterial3d(category.clone()),
+                FeeShareMaterial {
+                    original: original.clone(),
+                    fee_share: fee_share.clone(),
+                },
+            ))
+            .id();
+        app.update();
+        assert_eq!(material(&app, new_cube), category);
+
+        press(&mut app, KeyCode::KeyC);
+        assert_eq!(material(&app, cube), fee_share);
+        press(&mut app, KeyCode::KeyC);
+        press(&mut app, KeyCode::KeyL);
+        assert_eq!(material(&app, cube), category);
+        press(&mut app, KeyCode::KeyC);
+        assert_eq!(material(&app, cube), original);
+    }
 }
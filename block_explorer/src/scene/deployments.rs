@@ -0,0 +1,174 @@
+//! Contract deployments in the visible window.
+//!
+//! Creation cubes carry a marker (spawned by the renderer). [`Deployments`]
+//! lists the contracts deployed by cubes still in the scene, newest first,
+//! for the HUD feed. Later transactions that call one of those contracts are
+//! linked back to the deployment cube with an arc.
+
+use std::collections::HashMap;
+
+use alloy::primitives::Address;
+use alloy_chains::Chain;
+use bevy::prelude::*;
+
+use crate::scene::arcs::cubic_bezier;
+use crate::scene::blocks::Orphaned;
+use crate::scene::TxCube;
+
+/// A contract created by a transaction in the scene.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Deployment {
+    pub chain: Chain,
+    pub block_number: u64,
+    pub tx_index: usize,
+    pub address: Address,
+    pub deployer: Address,
+    pub position: Vec3,
+}
+
+/// Deployments among the live cubes, newest first, and the calls made to
+/// them afterwards within the window.
+#[derive(Resource, Default)]
+pub struct Deployments {
+    pub entries: Vec<Deployment>,
+    /// Deployment cube and calling cube positions.
+    links: Vec<(Vec3, Vec3)>,
+}
+
+impl Deployments {
+    /// The deployment of `address` on `chain`, if it is in the window.
+    pub fn find(&self, chain: Chain, address: Address) -> Option<&Deployment> {
+        self.entries
+            .iter()
+            .find(|deployment| deployment.chain == chain && deployment.address == address)
+    }
+}
+
+/// Colour of the deployment markers and their call links.
+pub(crate) const DEPLOYMENT_COLOR: Color = Color::srgb(0.95, 0.75, 0.25);
+
+pub fn deployment_plugin(app: &mut App) {
+    app.init_resource::<Deployments>().add_systems(
+        Update,
+        (update_deployments, draw_deployment_links_system).chain(),
+    );
+}
+
+/// Rebuilds the feed and links whenever cubes enter or leave the scene.
+fn update_deployments(
+    mut deployments: ResMut<Deployments>,
+    added: Query<(), Added<TxCube>>,
+    orphaned: Query<(), (With<TxCube>, Added<Orphaned>)>,
+    mut removed: RemovedComponents<TxCube>,
+    cubes: Query<&TxCube, Without<Orphaned>>,
+) {
+    let removed_any = removed.read().count() > 0;
+    if added.is_empty() && orphaned.is_empty() && !removed_any {
+        return;
+    }
+    let (entries, links) = collect_deployments(cubes.iter());
+    deployments.entries = entries;
+    deployments.links = links;
+}
+
+/// Deployments, newest first, and links from each to the cubes that call
+/// the deployed contract later on the same chain.
+fn collect_deployments<'a>(
+    cubes: impl Iterator<Item = &'a TxCube>,
+) -> (Vec<Deployment>, Vec<(Vec3, Vec3)>) {
+    let cubes: Vec<&TxCube> = cubes.collect();
+    let mut entries: Vec<Deployment> = cubes
+        .iter()
+        .filter_map(|cube| {
+            Some(Deployment {
+                chain: cube.chain,
+                block_number: cube.block_number,
+                tx_index: cube.tx.index,
+                address: cube.tx.deployed_contract()?,
+                deployer: cube.tx.sender,
+                position: cube.world_position,
+            })
+        })
+        .collect();
+    entries.sort_by_key(|d| std::cmp::Reverse((d.block_number, d.tx_index)));
+
+    let by_address: HashMap<(Chain, Address), &Deployment> = entries
+        .iter()
+        .map(|deployment| ((deployment.chain, deployment.address), deployment))
+        .collect();
+    let links = cubes
+        .iter()
+        .filter_map(|cube| {
+            let deployment = by_address.get(&(cube.chain, cube.tx.recipient?))?;
+            let after =
+                (cube.block_number, cube.tx.index) > (deployment.block_number, deployment.tx_index);
+            after.then_some((deployment.position, cube.world_position))
+        })
+        .collect();
+    (entries, links)
+}
+
+fn draw_deployment_links_system(mut gizmos: Gizmos, deployments: Res<Deployments>) {
+    let color = DEPLOYMENT_COLOR.with_alpha(0.6);
+    for &(from, to) in &deployments.links {
+        let arc_height = 0.5 + from.distance(to) * 0.15;
+        let control1 = from + Vec3::Y * arc_height;
+        let control2 = to + Vec3::Y * arc_height;
+
+        let segments = 16;
+        let mut prev = from;
+        for s in 1..=segments {
+            let t = s as f32 / segments as f32;
+            let point = cubic_bezier(from, control1, control2, to, t);
+            gizmos.line(prev, point, color);
+            prev = point;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{EvmAddendum, TransactionEnvelope};
+
+    fn cube(block_number: u64, index: usize, recipient: Option<Address>) -> TxCube {
+        TxCube {
+            chain: Chain::mainnet(),
+            block_number,
+            world_position: Vec3::new(index as f32, 0.0, block_number as f32),
+            tx: TransactionEnvelope {
+                price: 1,
+                sender: Address::with_last_byte(0xde),
+                recipient,
+                evm: Some(EvmAddendum {
+                    contract_address: recipient
+                        .is_none()
+                        .then(|| Address::with_last_byte(index as u8)),
+                    ..Default::default()
+                }),
+                ..TransactionEnvelope::for_test(index)
+            },
+        }
+    }
+
+    #[test]
+    fn lists_deployments_and_links_later_calls() {
+        let first = Address::with_last_byte(1);
+        let cubes = [
+            cube(10, 0, Some(first)), // before the deployment
+            cube(10, 1, None),
+            cube(11, 2, None),
+            cube(12, 0, Some(first)),
+            cube(12, 1, Some(Address::with_last_byte(0xff))),
+        ];
+
+        let (entries, links) = collect_deployments(cubes.iter());
+        let addresses: Vec<Address> = entries.iter().map(|d| d.address).collect();
+        assert_eq!(addresses, vec![Address::with_last_byte(2), first]);
+        assert_eq!(entries[1].deployer, Address::with_last_byte(0xde));
+        assert_eq!(
+            links,
+            vec![(cubes[1].world_position, cubes[3].world_position)]
+        );
+    }
+}
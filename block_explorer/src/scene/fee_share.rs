@@ -0,0 +1,99 @@
+//! L1 fee share colour mode for OP Stack transaction cubes.
+//!
+//! OP Stack users pay an L2 execution fee plus an L1 data fee for posting
+//! their calldata to L1. With the mode on (toggled with `L`), Base and
+//! Optimism cubes are recoloured from teal (execution-dominated) to orange
+//! (data-dominated) by the L1 share of their total fee. The contract
+//! category mode takes precedence while both are on.
+
+use bevy::prelude::*;
+
+use crate::scene::contracts::{CategoryColorState, CategoryMaterial};
+
+/// Original and fee-share materials for an OP Stack tx cube with receipt data.
+#[derive(Component)]
+pub struct FeeShareMaterial {
+    pub original: Handle<StandardMaterial>,
+    pub fee_share: Handle<StandardMaterial>,
+}
+
+/// Global toggle for the L1 fee share colour mode.
+#[derive(Resource, Default)]
+pub struct FeeShareState {
+    pub enabled: bool,
+}
+
+pub fn fee_share_plugin(app: &mut App) {
+    app.init_resource::<FeeShareState>().add_systems(
+        Update,
+        (fee_share_toggle_system, apply_fee_share_to_new_cubes),
+    );
+}
+
+fn fee_share_toggle_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut state: ResMut<FeeShareState>,
+    category: Option<Res<CategoryColorState>>,
+    mut commands: Commands,
+    cubes: Query<(Entity, &FeeShareMaterial, Option<&CategoryMaterial>)>,
+) {
+    if !keys.just_pressed(KeyCode::KeyL) {
+        return;
+    }
+
+    state.enabled = !state.enabled;
+    let category_on = category.is_some_and(|category| category.enabled);
+
+    for (entity, mat, category_mat) in &cubes {
+        // Category colours win while that mode is on; its toggle restores
+        // the fee share colour when it's switched off.
+        if category_on && category_mat.is_some() {
+            continue;
+        }
+        let handle = if state.enabled {
+            mat.fee_share.clone()
+        } else {
+            mat.original.clone()
+        };
+        commands.entity(entity).insert(MeshMaterial3d(handle));
+    }
+}
+
+/// Cubes spawned while the mode is on start out in their fee-share colour,
+/// unless the category mode is on too.
+fn apply_fee_share_to_new_cubes(
+    state: Res<FeeShareState>,
+    category: Option<Res<CategoryColorState>>,
+    mut commands: Commands,
+    cubes: Query<(Entity, &FeeShareMaterial), Added<FeeShareMaterial>>,
+) {
+    if !state.enabled || category.is_some_and(|category| category.enabled) {
+        return;
+    }
+    for (entity, mat) in &cubes {
+        commands
+            .entity(entity)
+            .insert(MeshMaterial3d(mat.fee_share.clone()));
+    }
+}
+
+/// Teal → orange gradient for an L1 fee share in `0.0..=1.0`.
+pub(crate) fn fee_share_color(share: f32) -> Color {
+    let t = share.clamp(0.0, 1.0);
+    Color::srgb(0.1 + 0.9 * t, 0.75 - 0.3 * t, 0.7 - 0.6 * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fee_share_color_spans_teal_to_orange() {
+        let l2_heavy = fee_share_color(0.0).to_srgba();
+        let l1_heavy = fee_share_color(1.0).to_srgba();
+
+        assert!(l2_heavy.blue > l2_heavy.red);
+        assert!(l1_heavy.red > l1_heavy.blue);
+        assert_eq!(fee_share_color(2.0), fee_share_color(1.0));
+    }
+}
@@ -0,0 +1,153 @@
+//! Ghost slabs: placeholders for blocks a lane skipped while the fetcher retries them.
+
+use std::collections::HashMap;
+
+use alloy_chains::Chain;
+use bevy::prelude::*;
+
+use crate::scene::blocks::ExplorerState;
+use crate::scene::materials;
+
+/// Largest jump in block numbers drawn as ghosts. Bigger jumps (e.g. a new
+/// historical range) are treated as a fresh start rather than a gap.
+const MAX_GHOSTS_PER_GAP: u64 = 32;
+const GHOST_WIDTH: f32 = 2.0;
+const GHOST_HEIGHT: f32 = 1.0;
+const GHOST_DEPTH: f32 = 2.0;
+
+/// Marker + data for a ghost slab standing in for a missing block.
+#[derive(Component)]
+pub struct GhostSlab {
+    pub chain: Chain,
+    pub number: u64,
+}
+
+/// Tracks each lane's highest delivered block and the ghost slabs spawned for
+/// the numbers it skipped.
+#[derive(Resource, Default)]
+pub struct GapTracker {
+    tips: HashMap<Chain, (u64, u64)>,
+    ghosts: HashMap<(Chain, u64), (Entity, u64)>,
+}
+
+impl GapTracker {
+    /// Records a delivered block and returns `(number, estimated timestamp)`
+    /// for every block skipped since the lane's previous tip.
+    pub fn observe(&mut self, chain: Chain, number: u64, timestamp: u64) -> Vec<(u64, u64)> {
+        let previous = self.tips.get(&chain).copied();
+        if previous.is_some_and(|(tip, _)| number <= tip) {
+            return Vec::new();
+        }
+        self.tips.insert(chain, (number, timestamp));
+
+        let Some((tip, tip_timestamp)) = previous else {
+            return Vec::new();
+        };
+        let missing = number - tip - 1;
+        if missing == 0 || missing > MAX_GHOSTS_PER_GAP {
+            return Vec::new();
+        }
+        // Spread the missing blocks evenly between their delivered neighbours.
+        let span = timestamp.saturating_sub(tip_timestamp);
+        (1..=missing)
+            .map(|i| (tip + i, tip_timestamp + span * i / (missing + 1)))
+            .collect()
+    }
+
+    pub fn insert_ghost(&mut self, chain: Chain, number: u64, timestamp: u64, entity: Entity) {
+        self.ghosts.insert((chain, number), (entity, timestamp));
+    }
+
+    /// Removes and returns the ghost for a block that has now arrived.
+    pub fn take_ghost(&mut self, chain: Chain, number: u64) -> Option<Entity> {
+        self.ghosts
+            .remove(&(chain, number))
+            .map(|(entity, _)| entity)
+    }
+
+    /// Forgets the lane tip after a reorg at `fork` and returns the ghosts at
+    /// or above it; the replacement branch re-delivers that range.
+    pub fn rewind(&mut self, chain: Chain, fork: u64) -> Vec<Entity> {
+        self.tips.remove(&chain);
+        self.drain(|c, n, _| c == chain && n >= fork)
+    }
+
+    /// Returns the ghosts older than `cutoff`, mirroring the block window.
+    pub fn expire(&mut self, cutoff: u64) -> Vec<Entity> {
+        self.drain(|_, _, timestamp| timestamp < cutoff)
+    }
+
+    fn drain(&mut self, mut remove: impl FnMut(Chain, u64, u64) -> bool) -> Vec<Entity> {
+        let mut removed = Vec::new();
+        self.ghosts.retain(|(chain, number), (entity, timestamp)| {
+            let keep = !remove(*chain, *number, *timestamp);
+            if !keep {
+                removed.push(*entity);
+            }
+            keep
+        });
+        removed
+    }
+}
+
+/// Spawns a ghost slab in the chain's lane at the estimated block time.
+pub(crate) fn spawn_ghost_slab(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    state: &mut ExplorerState,
+    chain: Chain,
+    number: u64,
+    timestamp: u64,
+) -> Entity {
+    let x_offset = state.lane_for(chain).x_offset;
+    let z = state.z_for_timestamp(timestamp);
+    commands
+        .spawn((
+            Mesh3d(meshes.add(Cuboid::new(GHOST_WIDTH, GHOST_HEIGHT, GHOST_DEPTH))),
+            MeshMaterial3d(materials::ghost_slab_material(materials)),
+            Transform::from_xyz(x_offset, 0.0, z),
+            GhostSlab { chain, number },
+        ))
+        .id()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn observe_reports_skipped_numbers_with_interpolated_times() {
+        let chain = Chain::mainnet();
+        let mut gaps = GapTracker::default();
+
+        assert!(gaps.observe(chain, 100, 1_200).is_empty());
+        assert!(gaps.observe(chain, 101, 1_212).is_empty());
+        assert_eq!(
+            gaps.observe(chain, 104, 1_248),
+            vec![(102, 1_224), (103, 1_236)]
+        );
+        // A late fill doesn't move the tip back.
+        assert!(gaps.observe(chain, 102, 1_224).is_empty());
+        assert!(gaps.observe(chain, 200, 2_400).is_empty());
+    }
+
+    #[test]
+    fn rewind_and_expire_return_matching_ghosts() {
+        let chain = Chain::mainnet();
+        let mut gaps = GapTracker::default();
+        let (a, b, c) = (
+            Entity::from_raw(1),
+            Entity::from_raw(2),
+            Entity::from_raw(3),
+        );
+        gaps.insert_ghost(chain, 10, 100, a);
+        gaps.insert_ghost(chain, 12, 124, b);
+        gaps.insert_ghost(chain, 14, 148, c);
+
+        assert_eq!(gaps.take_ghost(chain, 12), Some(b));
+        assert_eq!(gaps.rewind(chain, 13), vec![c]);
+        assert_eq!(gaps.expire(101), vec![a]);
+        assert_eq!(gaps.take_ghost(chain, 10), None);
+    }
+}
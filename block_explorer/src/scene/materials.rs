@@ -3,7 +3,7 @@
 use alloy_chains::Chain;
 use bevy::prelude::*;
 
-use crate::data::TxPayload;
+use crate::data::{chain_units, TransactionEnvelope};
 
 pub fn block_slab_material_with_fullness(
     materials: &mut ResMut<Assets<StandardMaterial>>,
@@ -18,16 +18,16 @@ pub fn block_slab_material_with_fullness(
 
 pub fn tx_cube_material(
     materials: &mut ResMut<Assets<StandardMaterial>>,
-    tx: &TxPayload,
+    tx: &TransactionEnvelope,
     tx_count: usize,
     chain: Chain,
 ) -> Handle<StandardMaterial> {
-    let gwei = tx.gas_price as f64 / 1e9;
-    let color = gas_price_color(gwei, gas_price_range(chain));
+    let units = chain_units(&chain);
+    let color = price_color(units.display_price(tx.price), units.price_ceiling);
 
     // Position-based brightness: first tx = full, last tx = 40%
     let brightness = if tx_count > 1 {
-        1.0 - 0.6 * (tx.tx_index as f32 / (tx_count - 1) as f32)
+        1.0 - 0.6 * (tx.index as f32 / (tx_count - 1) as f32)
     } else {
         1.0
     };
@@ -38,7 +38,8 @@ pub fn tx_cube_material(
         lin.blue * brightness,
     );
 
-    let emissive = if tx.value_eth > 1.0 {
+    // Transfers of more than one whole native unit glow.
+    let emissive = if tx.value > units.native_amount(1) {
         let m = modulated.to_linear();
         Color::linear_rgb(m.red * 5.0, m.green * 5.0, m.blue * 5.0)
     } else {
@@ -52,21 +53,75 @@ pub fn tx_cube_material(
     })
 }
 
-/// Generates a heatmap image from transaction gas prices.
-/// Each pixel column represents one transaction, colored by gas price.
-pub(crate) fn generate_heatmap_image(txs: &[TxPayload], chain: Chain) -> Image {
+/// Translucent, unlit material for a pending transaction, coloured by its bid
+/// like a mined cube. Each cube gets its own handle so it can fade out.
+pub fn pending_tx_material(
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    tx: &TransactionEnvelope,
+    chain: Chain,
+    alpha: f32,
+) -> Handle<StandardMaterial> {
+    let units = chain_units(&chain);
+    let color = price_color(units.display_price(tx.price), units.price_ceiling);
+    materials.add(StandardMaterial {
+        base_color: color.with_alpha(alpha),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..default()
+    })
+}
+
+/// Faint translucent material for ghost slabs standing in for missing blocks.
+pub fn ghost_slab_material(
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+) -> Handle<StandardMaterial> {
+    materials.add(StandardMaterial {
+        base_color: Color::srgba(0.6, 0.65, 0.75, 0.15),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..default()
+    })
+}
+
+/// Translucent material with a dim red glow for reverted transactions.
+pub fn reverted_tx_material(
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    color: Color,
+) -> Handle<StandardMaterial> {
+    let lin = color.to_linear();
+    materials.add(StandardMaterial {
+        base_color: color,
+        emissive: LinearRgba::rgb(lin.red * 0.8, lin.green * 0.3, lin.blue * 0.3),
+        alpha_mode: AlphaMode::Blend,
+        ..default()
+    })
+}
+
+/// Material coloured by the L1 data fee share of an OP Stack tx.
+pub fn fee_share_material(
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    share: f32,
+) -> Handle<StandardMaterial> {
+    materials.add(StandardMaterial {
+        base_color: crate::scene::fee_share::fee_share_color(share),
+        ..default()
+    })
+}
+
+/// Generates a heatmap image from transaction prices.
+/// Each pixel column represents one transaction, colored by its price.
+pub(crate) fn generate_heatmap_image(txs: &[TransactionEnvelope], chain: Chain) -> Image {
     use bevy::image::ImageSampler;
     use bevy::render::render_asset::RenderAssetUsages;
     use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
 
-    let max_gwei = gas_price_range(chain);
+    let units = chain_units(&chain);
     let width = txs.len().max(1) as u32;
     let height: u32 = 16;
     let mut data = vec![0u8; (width * height * 4) as usize];
 
     for (i, tx) in txs.iter().enumerate() {
-        let gwei = tx.gas_price as f64 / 1e9;
-        let color = gas_price_color(gwei, max_gwei);
+        let color = price_color(units.display_price(tx.price), units.price_ceiling);
         let lin = color.to_linear();
         let r = (lin.red * 255.0) as u8;
         let g = (lin.green * 255.0) as u8;
@@ -96,20 +151,10 @@ pub(crate) fn generate_heatmap_image(txs: &[TxPayload], chain: Chain) -> Image {
     image
 }
 
-/// Gas price range (in gwei) for the color gradient.
-/// L1 uses 0–200 gwei; OP Stack L2s use 0–0.1 gwei so the gradient
-/// actually differentiates transactions at sub-gwei prices.
-fn gas_price_range(chain: Chain) -> f64 {
-    if crate::data::is_op_stack(&chain) {
-        0.02
-    } else {
-        200.0
-    }
-}
-
-/// Blue → Cyan → Yellow → Red gradient mapped to 0–`max_gwei`.
-fn gas_price_color(gwei: f64, max_gwei: f64) -> Color {
-    let t = (gwei / max_gwei).clamp(0.0, 1.0) as f32;
+/// Blue → Cyan → Yellow → Red gradient mapped to 0–`ceiling`, both in the
+/// chain's displayed price unit.
+fn price_color(price: f64, ceiling: f64) -> Color {
+    let t = (price / ceiling).clamp(0.0, 1.0) as f32;
 
     if t < 0.33 {
         let s = t / 0.33;
@@ -126,20 +171,11 @@ fn gas_price_color(gwei: f64, max_gwei: f64) -> Color {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use alloy::primitives::{Address, B256};
-
-    fn tx_with_gas(gwei: u64, tx_index: usize) -> TxPayload {
-        TxPayload {
-            hash: B256::ZERO,
-            tx_index,
-            gas: 21_000,
-            gas_price: (gwei as u128) * 1_000_000_000u128,
-            value_eth: 0.0,
-            from: Address::ZERO,
-            to: None,
-            blob_count: 0,
-            max_fee_per_blob_gas: None,
-            op_stack_fees: None,
+
+    fn tx_with_gas(gwei: u64, index: usize) -> TransactionEnvelope {
+        TransactionEnvelope {
+            price: (gwei as u128) * 1_000_000_000u128,
+            ..TransactionEnvelope::for_test(index)
         }
     }
 
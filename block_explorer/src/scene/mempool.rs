@@ -0,0 +1,473 @@
+//! Pending transactions hovering ahead of each lane's tip.
+//!
+//! Mempool transactions appear as translucent cubes in a grid just ahead of
+//! the newest block in their lane, highest bidders nearest the tip. When a
+//! block includes one, its cube flies into the transaction's slot and hands
+//! over to the mined cube. Replaced transactions (same sender and nonce) and
+//! ones that leave the pool without being mined fade out.
+
+use std::collections::{HashMap, VecDeque};
+
+use alloy::primitives::{Address, B256};
+use alloy_chains::Chain;
+use bevy::prelude::*;
+
+use crate::data::mempool::{MempoolEvent, PendingTx};
+use crate::data::{MempoolChannel, TransactionEnvelope};
+use crate::scene::blocks::{BlockRegistry, ExplorerState};
+use crate::scene::{materials, TxCube};
+
+/// Mempool events handled per frame; the rest wait for the next one.
+const MAX_EVENTS_PER_FRAME: usize = 256;
+/// Waiting cubes shown per lane. Past this, the lowest bidders fade out.
+const MAX_PENDING_PER_LANE: usize = 96;
+const PENDING_COLUMNS: usize = 8;
+const PENDING_SPACING: f32 = 0.8;
+const PENDING_SIZE: f32 = 0.4;
+/// Height the pending grid hovers at.
+const HOVER_HEIGHT: f32 = 3.0;
+/// Distance ahead of the lane's newest block to the first pending row.
+const TIP_GAP: f32 = 6.0;
+/// How quickly waiting cubes ease towards their grid slot, per second.
+const FOLLOW_RATE: f32 = 6.0;
+/// How long a transaction reported as gone from the pool may still turn up in
+/// a block before it is treated as dropped.
+const LEFT_GRACE_SECONDS: f32 = 15.0;
+/// Transactions pending longer than this fade out (the subscription never
+/// reports drops).
+const PENDING_TTL_SECONDS: f32 = 300.0;
+const INCLUDE_SECONDS: f32 = 0.6;
+const FADE_SECONDS: f32 = 1.0;
+const PENDING_ALPHA: f32 = 0.35;
+/// Inclusion waits kept for the HUD average.
+const WAIT_WINDOW: usize = 100;
+
+/// A transaction waiting in the mempool.
+#[derive(Component)]
+pub struct PendingCube {
+    pub chain: Chain,
+    pub tx: TransactionEnvelope,
+    /// Elapsed app time when the transaction was first seen.
+    pub seen_at: f32,
+    state: PendingState,
+}
+
+enum PendingState {
+    /// Hovering ahead of the tip. `left_at` is set once the pool reports the
+    /// transaction gone.
+    Waiting { left_at: Option<f32> },
+    /// Flying into its slot in the block that included it.
+    Including { from: Vec3, to: Vec3, elapsed: f32 },
+    /// Replaced or dropped.
+    Fading { elapsed: f32 },
+}
+
+impl PendingCube {
+    fn key(&self) -> (Chain, B256) {
+        (self.chain, self.tx.id)
+    }
+
+    fn nonce_key(&self) -> Option<(Chain, Address, u64)> {
+        nonce_key(self.chain, &self.tx)
+    }
+}
+
+fn nonce_key(chain: Chain, tx: &TransactionEnvelope) -> Option<(Chain, Address, u64)> {
+    Some((chain, tx.sender, tx.evm.as_ref()?.nonce?))
+}
+
+/// Waiting cubes by transaction id and by sender nonce.
+#[derive(Resource, Default)]
+struct PendingIndex {
+    by_id: HashMap<(Chain, B256), Entity>,
+    by_nonce: HashMap<(Chain, Address, u64), Entity>,
+}
+
+impl PendingIndex {
+    fn insert(&mut self, cube: &PendingCube, entity: Entity) {
+        self.by_id.insert(cube.key(), entity);
+        if let Some(key) = cube.nonce_key() {
+            self.by_nonce.insert(key, entity);
+        }
+    }
+
+    /// Forgets a cube that stopped waiting.
+    fn remove(&mut self, cube: &PendingCube) {
+        let entity = self.by_id.remove(&cube.key());
+        if let Some(key) = cube.nonce_key() {
+            if entity.is_some() && self.by_nonce.get(&key).copied() == entity {
+                self.by_nonce.remove(&key);
+            }
+        }
+    }
+}
+
+/// Pending counts and inclusion waits for the HUD.
+#[derive(Resource, Default)]
+pub struct MempoolStats {
+    /// Waiting transactions per chain.
+    pub pending: HashMap<Chain, usize>,
+    waits: VecDeque<f32>,
+}
+
+impl MempoolStats {
+    fn record_wait(&mut self, seconds: f32) {
+        if self.waits.len() == WAIT_WINDOW {
+            self.waits.pop_front();
+        }
+        self.waits.push_back(seconds);
+    }
+
+    /// Average seconds from first sighting to inclusion over recent
+    /// transactions.
+    pub fn avg_wait(&self) -> Option<f32> {
+        if self.waits.is_empty() {
+            return None;
+        }
+        Some(self.waits.iter().sum::<f32>() / self.waits.len() as f32)
+    }
+}
+
+pub fn mempool_plugin(app: &mut App) {
+    app.init_resource::<PendingIndex>()
+        .init_resource::<MempoolStats>()
+        .add_systems(
+            Update,
+            (
+                receive_mempool_events,
+                include_mined_transactions,
+                layout_pending_cubes,
+                animate_leaving_cubes,
+            )
+                .chain(),
+        );
+}
+
+#[allow(clippy::too_many_arguments)]
+fn receive_mempool_events(
+    mut commands: Commands,
+    channel: Res<MempoolChannel>,
+    time: Res<Time>,
+    state: Option<Res<ExplorerState>>,
+    registry: Option<Res<BlockRegistry>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials_res: ResMut<Assets<StandardMaterial>>,
+    mut index: ResMut<PendingIndex>,
+    mut cubes: Query<&mut PendingCube>,
+    mut mesh: Local<Option<Handle<Mesh>>>,
+) {
+    let now = time.elapsed_secs();
+    let mesh = mesh
+        .get_or_insert_with(|| meshes.add(Cuboid::from_length(PENDING_SIZE)))
+        .clone();
+
+    for event in channel.0.try_iter().take(MAX_EVENTS_PER_FRAME) {
+        match event {
+            MempoolEvent::Pending(pending) => {
+                if index.by_id.contains_key(&(pending.chain, pending.tx.id)) {
+                    continue;
+                }
+                if let Some(replaced) = nonce_key(pending.chain, &pending.tx)
+                    .and_then(|key| index.by_nonce.get(&key).copied())
+                {
+                    match cubes.get_mut(replaced) {
+                        Ok(mut old) => {
+                            index.remove(&old);
+                            old.state = PendingState::Fading { elapsed: 0.0 };
+                        }
+                        // Replaced within the frame it was spawned in.
+                        Err(_) => {
+                            index.by_id.retain(|_, entity| *entity != replaced);
+                            commands.entity(replaced).despawn();
+                        }
+                    }
+                }
+
+                let anchor = match (&state, &registry) {
+                    (Some(state), Some(registry)) => lane_anchor(state, registry, pending.chain),
+                    _ => None,
+                };
+                let PendingTx { chain, tx } = *pending;
+                let cube = PendingCube {
+                    chain,
+                    tx,
+                    seen_at: now,
+                    state: PendingState::Waiting { left_at: None },
+                };
+                let material = materials::pending_tx_material(
+                    &mut materials_res,
+                    &cube.tx,
+                    cube.chain,
+                    PENDING_ALPHA,
+                );
+                let entity = commands
+                    .spawn((
+                        Mesh3d(mesh.clone()),
+                        MeshMaterial3d(material),
+                        Transform::from_translation(anchor.unwrap_or(Vec3::Y * HOVER_HEIGHT)),
+                        Visibility::Visible,
+                    ))
+                    .id();
+                index.insert(&cube, entity);
+                commands.entity(entity).insert(cube);
+            }
+            MempoolEvent::Left { chain, id } => {
+                let Some(&entity) = index.by_id.get(&(chain, id)) else {
+                    continue;
+                };
+                if let Ok(mut cube) = cubes.get_mut(entity) {
+                    if let PendingState::Waiting { left_at } = &mut cube.state {
+                        left_at.get_or_insert(now);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Sends pending cubes into the blocks that mined them and fades out the
+/// ones those blocks replaced.
+fn include_mined_transactions(
+    time: Res<Time>,
+    mut index: ResMut<PendingIndex>,
+    mut stats: ResMut<MempoolStats>,
+    mined: Query<&TxCube, Added<TxCube>>,
+    mut cubes: Query<(&mut PendingCube, &Transform)>,
+) {
+    let now = time.elapsed_secs();
+    for tx_cube in &mined {
+        if let Some(entity) = index.by_id.get(&(tx_cube.chain, tx_cube.tx.id)).copied() {
+            if let Ok((mut cube, transform)) = cubes.get_mut(entity) {
+                index.remove(&cube);
+                stats.record_wait(now - cube.seen_at);
+                cube.state = PendingState::Including {
+                    from: transform.translation,
+                    to: tx_cube.world_position,
+                    elapsed: 0.0,
+                };
+            }
+        }
+
+        // Another transaction with the mined one's sender and nonce lost.
+        let Some(entity) =
+            nonce_key(tx_cube.chain, &tx_cube.tx).and_then(|key| index.by_nonce.get(&key).copied())
+        else {
+            continue;
+        };
+        if let Ok((mut cube, _)) = cubes.get_mut(entity) {
+            index.remove(&cube);
+            cube.state = PendingState::Fading { elapsed: 0.0 };
+        }
+    }
+}
+
+/// Eases waiting cubes into each lane's pending grid and expires the ones
+/// that left the pool or waited too long.
+fn layout_pending_cubes(
+    time: Res<Time>,
+    state: Option<Res<ExplorerState>>,
+    registry: Option<Res<BlockRegistry>>,
+    mut index: ResMut<PendingIndex>,
+    mut stats: ResMut<MempoolStats>,
+    mut cubes: Query<(Entity, &mut PendingCube, &mut Transform)>,
+) {
+    let now = time.elapsed_secs();
+
+    let mut lanes: HashMap<Chain, Vec<(Entity, u128, f32)>> = HashMap::new();
+    for (entity, mut cube, _) in &mut cubes {
+        let PendingState::Waiting { left_at } = cube.state else {
+            continue;
+        };
+        let dropped = left_at.is_some_and(|at| now - at > LEFT_GRACE_SECONDS);
+        if dropped || now - cube.seen_at > PENDING_TTL_SECONDS {
+            index.remove(&cube);
+            cube.state = PendingState::Fading { elapsed: 0.0 };
+            continue;
+        }
+        lanes
+            .entry(cube.chain)
+            .or_default()
+            .push((entity, cube.tx.price, cube.seen_at));
+    }
+
+    stats.pending.clear();
+    let step = (FOLLOW_RATE * time.delta_secs()).min(1.0);
+    for (chain, mut waiting) in lanes {
+        waiting.sort_by(|a, b| b.1.cmp(&a.1).then(a.2.total_cmp(&b.2)));
+        stats.pending.insert(chain, waiting.len());
+
+        let anchor = match (&state, &registry) {
+            (Some(state), Some(registry)) => lane_anchor(state, registry, chain),
+            _ => None,
+        };
+        for (slot, (entity, _, _)) in waiting.into_iter().enumerate() {
+            let Ok((_, mut cube, mut transform)) = cubes.get_mut(entity) else {
+                continue;
+            };
+            if slot >= MAX_PENDING_PER_LANE {
+                index.remove(&cube);
+                cube.state = PendingState::Fading { elapsed: 0.0 };
+                continue;
+            }
+            if let Some(anchor) = anchor {
+                let target = anchor + slot_offset(slot);
+                transform.translation = transform.translation.lerp(target, step);
+            }
+        }
+    }
+}
+
+/// Animates cubes flying into their block or fading out, then despawns them.
+fn animate_leaving_cubes(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut materials_res: ResMut<Assets<StandardMaterial>>,
+    mut cubes: Query<(
+        Entity,
+        &mut PendingCube,
+        &mut Transform,
+        &MeshMaterial3d<StandardMaterial>,
+    )>,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut cube, mut transform, material) in &mut cubes {
+        match &mut cube.state {
+            PendingState::Waiting { .. } => {}
+            PendingState::Including { from, to, elapsed } => {
+                *elapsed += dt;
+                let t = (*elapsed / INCLUDE_SECONDS).min(1.0);
+                let eased = t * t * (3.0 - 2.0 * t);
+                transform.translation = from.lerp(*to, eased);
+                if t >= 1.0 {
+                    commands.entity(entity).despawn();
+                }
+            }
+            PendingState::Fading { elapsed } => {
+                *elapsed += dt;
+                let t = (*elapsed / FADE_SECONDS).min(1.0);
+                if let Some(material) = materials_res.get_mut(&material.0) {
+                    material.base_color.set_alpha(PENDING_ALPHA * (1.0 - t));
+                }
+                if t >= 1.0 {
+                    commands.entity(entity).despawn();
+                }
+            }
+        }
+    }
+}
+
+/// Centre of the first pending row: above the lane, just ahead of its newest
+/// block. `None` until the lane has a block.
+fn lane_anchor(state: &ExplorerState, registry: &BlockRegistry, chain: Chain) -> Option<Vec3> {
+    let x = state.lanes.get(&chain)?.x_offset;
+    let tip_z = registry
+        .entries
+        .iter()
+        .filter(|entry| entry.chain == chain)
+        .map(|entry| entry.z_position)
+        .min_by(f32::total_cmp)?;
+    Some(Vec3::new(x, HOVER_HEIGHT, tip_z - TIP_GAP))
+}
+
+/// Offset of a grid slot from the lane anchor; rows extend away from the tip.
+fn slot_offset(slot: usize) -> Vec3 {
+    let column = (slot % PENDING_COLUMNS) as f32 - (PENDING_COLUMNS - 1) as f32 / 2.0;
+    let row = (slot / PENDING_COLUMNS) as f32;
+    Vec3::new(column * PENDING_SPACING, 0.0, -row * PENDING_SPACING)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::EvmAddendum;
+
+    fn envelope(id: u8, nonce: u64) -> TransactionEnvelope {
+        TransactionEnvelope {
+            id: B256::with_last_byte(id),
+            price: 2_000_000_000,
+            sender: Address::with_last_byte(0xaa),
+            evm: Some(EvmAddendum {
+                nonce: Some(nonce),
+                ..Default::default()
+            }),
+            ..TransactionEnvelope::for_test(0)
+        }
+    }
+
+    fn pending(id: u8, nonce: u64) -> MempoolEvent {
+        MempoolEvent::Pending(Box::new(PendingTx {
+            chain: Chain::mainnet(),
+            tx: envelope(id, nonce),
+        }))
+    }
+
+    fn app() -> (App, crossbeam_channel::Sender<MempoolEvent>) {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut app = App::new();
+        app.init_resource::<Assets<Mesh>>()
+            .init_resource::<Assets<StandardMaterial>>()
+            .init_resource::<Time>()
+            .insert_resource(MempoolChannel(rx))
+            .add_plugins(mempool_plugin);
+        (app, tx)
+    }
+
+    fn states(app: &mut App) -> Vec<(u8, &'static str)> {
+        let world = app.world_mut();
+        let mut states: Vec<(u8, &'static str)> = world
+            .query::<&PendingCube>()
+            .iter(world)
+            .map(|cube| {
+                let state = match cube.state {
+                    PendingState::Waiting { .. } => "waiting",
+                    PendingState::Including { .. } => "including",
+                    PendingState::Fading { .. } => "fading",
+                };
+                (cube.tx.id[31], state)
+            })
+            .collect();
+        states.sort();
+        states
+    }
+
+    #[test]
+    fn replacement_fades_the_earlier_transaction() {
+        let (mut app, tx) = app();
+        tx.send(pending(1, 7)).unwrap();
+        app.update();
+        tx.send(pending(2, 7)).unwrap();
+        app.update();
+
+        assert_eq!(states(&mut app), vec![(1, "fading"), (2, "waiting")]);
+        assert_eq!(
+            app.world().resource::<MempoolStats>().pending[&Chain::mainnet()],
+            1
+        );
+    }
+
+    #[test]
+    fn mined_transaction_flies_into_its_block() {
+        let (mut app, tx) = app();
+        tx.send(pending(1, 7)).unwrap();
+        tx.send(pending(3, 8)).unwrap();
+        app.update();
+
+        app.world_mut().spawn(TxCube {
+            chain: Chain::mainnet(),
+            block_number: 10,
+            world_position: Vec3::new(1.0, 0.5, -4.0),
+            tx: envelope(1, 7),
+        });
+        app.update();
+
+        assert_eq!(states(&mut app), vec![(1, "including"), (3, "waiting")]);
+        assert!(app.world().resource::<MempoolStats>().avg_wait().is_some());
+    }
+
+    #[test]
+    fn slots_fill_rows_away_from_the_tip() {
+        assert_eq!(slot_offset(0).z, 0.0);
+        assert_eq!(slot_offset(PENDING_COLUMNS).z, -PENDING_SPACING);
+        assert_eq!(slot_offset(0).x, -slot_offset(PENDING_COLUMNS - 1).x);
+    }
+}
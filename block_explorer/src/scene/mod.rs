@@ -1,17 +1,27 @@
 pub(crate) mod arcs;
 pub(crate) mod blob_links;
 pub(crate) mod blocks;
+pub(crate) mod builders;
 pub(crate) mod contracts;
+pub(crate) mod deployments;
+pub(crate) mod fee_share;
+pub(crate) mod gaps;
 pub(crate) mod labels;
 pub(crate) mod materials;
+pub(crate) mod mempool;
 pub(crate) mod screenshot;
 mod transactions;
 
 pub use arcs::arc_plugin;
 pub use blob_links::blob_link_plugin;
 pub use blocks::{
-    cleanup_old_blocks, flush_record_buffer, heatmap_plugin, ingest_blocks, setup_scene,
-    BlockEntry, BlockRegistry, BlockSlab, HeatmapState,
+    cleanup_old_blocks, clear_scene, fade_orphaned_blocks, finish_recording, heatmap_plugin,
+    ingest_blocks, setup_scene, BlockEntry, BlockRegistry, BlockSlab, ClearScene, HeatmapState,
 };
+pub use builders::{builder_plugin, BuilderShare};
+pub use contracts::category_plugin;
+pub use deployments::{deployment_plugin, Deployment, Deployments};
+pub use fee_share::fee_share_plugin;
+pub use mempool::{mempool_plugin, MempoolStats};
 pub use screenshot::{screenshot_plugin, ScreenshotMode};
 pub use transactions::{BlockLabel, TxCube};
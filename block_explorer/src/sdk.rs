@@ -1,22 +1,63 @@
 //! SDK entry points and builder for composing the block explorer app.
 
+use std::fmt;
 use std::path::PathBuf;
 
+use alloy_chains::Chain;
 use bevy::prelude::*;
 
 use crate::camera::fly_camera_plugin;
 use crate::config;
-use crate::data::{init_fixture_channel, init_multi_chain_channel, FetcherConfig, RecordBuffer};
+use crate::data::{
+    config_fetchers, init_fetcher_channels, init_fixture_channel, init_mempool_channel,
+    BlockChannel, BlockStart, BuilderRegistry, ChainFetcher, ContractRegistry, EndpointStrategy,
+    FetcherConfig, Recorder, Rotation, SelectorDb, TokenRegistry,
+};
+#[cfg(feature = "store")]
+use crate::data::{
+    replay_receiver, warm_receiver, BlockStore, Retention, StoreHistory, StoreWriter, WARM_BLOCKS,
+    WARM_MAX_AGE,
+};
 use crate::render::{BlockRenderer, RendererResource, SlabsAndCubesRenderer};
 use crate::scene::{
-    arc_plugin, blob_link_plugin, cleanup_old_blocks, flush_record_buffer, heatmap_plugin,
-    ingest_blocks, screenshot_plugin, setup_scene, ScreenshotMode,
+    arc_plugin, blob_link_plugin, builder_plugin, category_plugin, cleanup_old_blocks, clear_scene,
+    deployment_plugin, fade_orphaned_blocks, fee_share_plugin, finish_recording, heatmap_plugin,
+    ingest_blocks, mempool_plugin, screenshot_plugin, setup_scene, ClearScene, ScreenshotMode,
 };
 use crate::ui::{hud_plugin, inspector_plugin, timeline_plugin};
 
+/// Why [`BlockExplorerBuilder::build`] rejected its configuration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BuildError {
+    /// A chain's historical range ends before the block it starts at.
+    EmptyRange { chain: Chain, from: u64, to: u64 },
+    /// Custom fetchers were added alongside a fixture or store replay, which
+    /// would leave them without a channel to deliver into.
+    FetchersWithReplay,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyRange { chain, from, to } => write!(
+                f,
+                "[{chain}] range ends at block {to}, before its start block {from}"
+            ),
+            Self::FetchersWithReplay => write!(
+                f,
+                "custom fetchers can't be combined with a fixture or store replay; \
+                 remove the add_fetcher calls or the replay source"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
 /// Builder for constructing a Tessera app with customizable plugins.
 pub struct BlockExplorerBuilder {
     configs: Vec<FetcherConfig>,
+    fetchers: Vec<Box<dyn ChainFetcher>>,
     renderer: Option<Box<dyn BlockRenderer>>,
     window_title: String,
     window_resolution: (f32, f32),
@@ -28,15 +69,40 @@ pub struct BlockExplorerBuilder {
     enable_arcs: bool,
     enable_heatmap: bool,
     enable_blob_links: bool,
+    enable_fee_share: bool,
+    enable_builders: bool,
+    enable_deployments: bool,
+    enable_categories: bool,
     fixture_path: Option<PathBuf>,
     screenshot_path: Option<PathBuf>,
     record_path: Option<PathBuf>,
+    record_rotation: Rotation,
+    #[cfg(feature = "store")]
+    store_path: Option<PathBuf>,
+    #[cfg(feature = "store")]
+    store_retention: Option<Retention>,
+    #[cfg(feature = "store")]
+    replay_store: bool,
+    fetch_receipts: bool,
+    fetch_traces: bool,
+    watch_mempool: bool,
+    selectors_path: Option<PathBuf>,
+    tokens_path: Option<PathBuf>,
+    builders_path: Option<PathBuf>,
+    contracts_paths: Vec<PathBuf>,
+    cluster_by_selector: bool,
+    range_from: Option<BlockStart>,
+    range_to: Option<u64>,
+    concurrency: Option<usize>,
+    batch_size: Option<usize>,
+    endpoint_strategy: Option<EndpointStrategy>,
 }
 
 impl Default for BlockExplorerBuilder {
     fn default() -> Self {
         Self {
             configs: Vec::new(),
+            fetchers: Vec::new(),
             renderer: None,
             window_title: "Tessera".to_string(),
             window_resolution: (1280.0, 720.0),
@@ -48,9 +114,33 @@ impl Default for BlockExplorerBuilder {
             enable_arcs: true,
             enable_heatmap: true,
             enable_blob_links: true,
+            enable_fee_share: true,
+            enable_builders: true,
+            enable_deployments: true,
+            enable_categories: true,
             fixture_path: None,
             screenshot_path: None,
             record_path: None,
+            record_rotation: Rotation::default(),
+            #[cfg(feature = "store")]
+            store_path: None,
+            #[cfg(feature = "store")]
+            store_retention: None,
+            #[cfg(feature = "store")]
+            replay_store: false,
+            fetch_receipts: false,
+            fetch_traces: false,
+            watch_mempool: false,
+            selectors_path: None,
+            tokens_path: None,
+            builders_path: None,
+            contracts_paths: Vec::new(),
+            cluster_by_selector: false,
+            range_from: None,
+            range_to: None,
+            concurrency: None,
+            batch_size: None,
+            endpoint_strategy: None,
         }
     }
 }
@@ -72,6 +162,18 @@ impl BlockExplorerBuilder {
         self
     }
 
+    /// Add a custom block source (an indexer, a database, a simulator) that
+    /// feeds lanes next to the EVM chains. Range, batching and receipt
+    /// options on this builder only apply to the EVM chains.
+    ///
+    /// When only custom fetchers are added, no EVM chains are loaded from the
+    /// environment. Custom fetchers can't be combined with a fixture or store
+    /// replay; [`build`](Self::build) returns an error instead.
+    pub fn add_fetcher(mut self, fetcher: impl ChainFetcher) -> Self {
+        self.fetchers.push(Box::new(fetcher));
+        self
+    }
+
     /// Load all configured chains from environment variables.
     pub fn chain_configs(mut self) -> Self {
         self.configs = config::chain_configs();
@@ -140,7 +242,28 @@ impl BlockExplorerBuilder {
         self
     }
 
-    /// Replay pre-recorded block data from a JSON fixture file instead of live RPC.
+    pub fn disable_fee_share(mut self) -> Self {
+        self.enable_fee_share = false;
+        self
+    }
+
+    pub fn disable_builders(mut self) -> Self {
+        self.enable_builders = false;
+        self
+    }
+
+    pub fn disable_deployments(mut self) -> Self {
+        self.enable_deployments = false;
+        self
+    }
+
+    pub fn disable_categories(mut self) -> Self {
+        self.enable_categories = false;
+        self
+    }
+
+    /// Replay pre-recorded block data from a fixture file (a JSON array, JSON Lines or a
+    /// binary `.tfix` fixture) instead of live RPC.
     pub fn fixture(mut self, path: impl Into<PathBuf>) -> Self {
         self.fixture_path = Some(path.into());
         self
@@ -152,14 +275,146 @@ impl BlockExplorerBuilder {
         self
     }
 
-    /// Record ingested blocks to a JSON fixture file on exit.
+    /// Record ingested blocks to a JSON Lines fixture file as they arrive.
     pub fn record(mut self, path: impl Into<PathBuf>) -> Self {
         self.record_path = Some(path.into());
         self
     }
 
+    /// Start a new recording file once the current one is too large or too
+    /// old. The full file is renamed with a sequence number.
+    pub fn record_rotation(mut self, rotation: Rotation) -> Self {
+        self.record_rotation = rotation;
+        self
+    }
+
+    /// Keep every ingested block in a local store at `path`. On startup the
+    /// newest stored blocks warm the scene while the fetchers catch up, and
+    /// the timeline can replay the stored history.
+    #[cfg(feature = "store")]
+    pub fn store(mut self, path: impl Into<PathBuf>) -> Self {
+        self.store_path = Some(path.into());
+        self
+    }
+
+    /// Set how much history the store keeps (default: 10,000 blocks per chain).
+    #[cfg(feature = "store")]
+    pub fn store_retention(mut self, retention: Retention) -> Self {
+        self.store_retention = Some(retention);
+        self
+    }
+
+    /// Replay the store's blocks instead of fetching from RPC. Needs
+    /// [`store`](Self::store).
+    #[cfg(feature = "store")]
+    pub fn replay_store(mut self) -> Self {
+        self.replay_store = true;
+        self
+    }
+
+    /// Fetch transaction receipts for every chain, so cubes show gas used and
+    /// reverted transactions stand out. Costs one extra RPC call per block.
+    pub fn receipts(mut self) -> Self {
+        self.fetch_receipts = true;
+        self
+    }
+
+    /// Trace internal calls of every EVM transaction with
+    /// `debug_traceBlockByNumber`, so value forwarded between contracts shows
+    /// up in the inspector and as arcs. Needs a node with the `debug`
+    /// namespace, such as Anvil or an archive node.
+    pub fn traces(mut self) -> Self {
+        self.fetch_traces = true;
+        self
+    }
+
+    /// Watch every EVM chain's mempool and show pending transactions ahead of
+    /// the lane tip. Ignored when replaying a fixture.
+    pub fn mempool(mut self) -> Self {
+        self.watch_mempool = true;
+        self
+    }
+
+    /// Extend the bundled function-selector database with the signatures in a
+    /// file (one per line, e.g. `transfer(address to, uint256 amount)`).
+    pub fn selectors(mut self, path: impl Into<PathBuf>) -> Self {
+        self.selectors_path = Some(path.into());
+        self
+    }
+
+    /// Extend the bundled token registry with the tokens in a JSON file, for
+    /// symbols and decimals on token-flow arcs and in the inspector.
+    pub fn tokens(mut self, path: impl Into<PathBuf>) -> Self {
+        self.tokens_path = Some(path.into());
+        self
+    }
+
+    /// Extend the bundled block builder registry with the builders in a JSON
+    /// file, matched by fee recipient or `extra_data` tag.
+    pub fn builders(mut self, path: impl Into<PathBuf>) -> Self {
+        self.builders_path = Some(path.into());
+        self
+    }
+
+    /// Add the contract labels in a JSON or TOML file to the bundled
+    /// registry. May be called more than once; later files take precedence.
+    pub fn contracts(mut self, path: impl Into<PathBuf>) -> Self {
+        self.contracts_paths.push(path.into());
+        self
+    }
+
+    /// Cluster cubes by recipient and function selector instead of recipient
+    /// alone. Applies to the default renderer.
+    pub fn cluster_by_selector(mut self) -> Self {
+        self.cluster_by_selector = true;
+        self
+    }
+
+    /// Start every chain at an explicit block number instead of near the tip.
+    pub fn from_block(mut self, number: u64) -> Self {
+        self.range_from = Some(BlockStart::Number(number));
+        self
+    }
+
+    /// Start every chain at the first block produced at or after a Unix
+    /// timestamp. Each chain resolves the timestamp to its own block number.
+    pub fn at_timestamp(mut self, timestamp: u64) -> Self {
+        self.range_from = Some(BlockStart::Timestamp(timestamp));
+        self
+    }
+
+    /// Stop every chain after this block instead of following the tip.
+    pub fn to_block(mut self, number: u64) -> Self {
+        self.range_to = Some(number);
+        self
+    }
+
+    /// Number of backfill batches each chain keeps in flight. Blocks are still
+    /// delivered in order.
+    pub fn backfill_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = Some(concurrency);
+        self
+    }
+
+    /// Number of blocks requested per JSON-RPC batch; `1` disables batching.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = Some(batch_size);
+        self
+    }
+
+    /// How chains with several RPC endpoints spread their requests.
+    pub fn endpoint_strategy(mut self, strategy: EndpointStrategy) -> Self {
+        self.endpoint_strategy = Some(strategy);
+        self
+    }
+
     /// Build the Bevy app with the selected configuration and plugins.
-    pub fn build(mut self) -> App {
+    ///
+    /// # Errors
+    ///
+    /// If a chain's range ends before the block it starts at, or custom
+    /// fetchers were added alongside a fixture.
+    pub fn build(mut self) -> Result<App, BuildError> {
         // Check env var fallbacks for fixture, screenshot, and record paths.
         if self.fixture_path.is_none() {
             if let Ok(val) = std::env::var("TESSERA_FIXTURE") {
@@ -176,21 +431,216 @@ impl BlockExplorerBuilder {
                 self.record_path = Some(PathBuf::from(val));
             }
         }
+        #[cfg(feature = "store")]
+        {
+            if self.store_path.is_none() {
+                if let Ok(val) = std::env::var("TESSERA_STORE") {
+                    self.store_path = Some(PathBuf::from(val));
+                }
+            }
+            if self.store_retention.is_none() {
+                if let Some(keep) = std::env::var("TESSERA_STORE_KEEP")
+                    .ok()
+                    .and_then(|val| val.parse().ok())
+                {
+                    self.store_retention = Some(Retention {
+                        max_blocks: Some(keep),
+                        ..Retention::default()
+                    });
+                }
+            }
+            if config::env_flag("TESSERA_REPLAY_STORE") {
+                self.replay_store = true;
+            }
+        }
+        if config::env_flag("TESSERA_RECEIPTS") {
+            self.fetch_receipts = true;
+        }
+        if config::env_flag("TESSERA_TRACES") {
+            self.fetch_traces = true;
+        }
+        if config::env_flag("TESSERA_MEMPOOL") {
+            self.watch_mempool = true;
+        }
+        if self.selectors_path.is_none() {
+            if let Ok(val) = std::env::var("TESSERA_SELECTORS") {
+                self.selectors_path = Some(PathBuf::from(val));
+            }
+        }
+        if self.tokens_path.is_none() {
+            if let Ok(val) = std::env::var("TESSERA_TOKENS") {
+                self.tokens_path = Some(PathBuf::from(val));
+            }
+        }
+        if self.builders_path.is_none() {
+            if let Ok(val) = std::env::var("TESSERA_BUILDERS") {
+                self.builders_path = Some(PathBuf::from(val));
+            }
+        }
+        if self.contracts_paths.is_empty() {
+            if let Some(val) = std::env::var_os("TESSERA_CONTRACTS") {
+                self.contracts_paths = std::env::split_paths(&val).collect();
+            }
+        }
+        if config::env_flag("TESSERA_CLUSTER_BY_SELECTOR") {
+            self.cluster_by_selector = true;
+        }
+
+        let mut selectors = SelectorDb::bundled();
+        if let Some(ref path) = self.selectors_path {
+            match selectors.extend_from_file(path) {
+                Ok(added) => {
+                    eprintln!("tessera: loaded {added} signatures from {}", path.display())
+                }
+                Err(e) => eprintln!("tessera: failed to read selectors {}: {e}", path.display()),
+            }
+        }
+
+        let mut tokens = TokenRegistry::bundled();
+        if let Some(ref path) = self.tokens_path {
+            match tokens.extend_from_file(path) {
+                Ok(added) => eprintln!("tessera: loaded {added} tokens from {}", path.display()),
+                Err(e) => eprintln!("tessera: failed to read tokens {}: {e}", path.display()),
+            }
+        }
 
-        let channel = if let Some(ref path) = self.fixture_path {
-            init_fixture_channel(path)
+        let mut builders = BuilderRegistry::bundled();
+        if let Some(ref path) = self.builders_path {
+            match builders.extend_from_file(path) {
+                Ok(added) => eprintln!("tessera: loaded {added} builders from {}", path.display()),
+                Err(e) => eprintln!("tessera: failed to read builders {}: {e}", path.display()),
+            }
+        }
+
+        let mut contracts = ContractRegistry::bundled();
+        for path in &self.contracts_paths {
+            match contracts.extend_from_file(path) {
+                Ok(added) => {
+                    eprintln!("tessera: loaded {added} contracts from {}", path.display())
+                }
+                Err(e) => eprintln!("tessera: failed to read contracts {}: {e}", path.display()),
+            }
+        }
+
+        #[cfg(feature = "store")]
+        let store = self
+            .store_path
+            .as_ref()
+            .and_then(|path| match BlockStore::open(path) {
+                Ok(store) => Some(store),
+                Err(e) => {
+                    eprintln!(
+                        "tessera: failed to open block store {}: {e}",
+                        path.display()
+                    );
+                    None
+                }
+            });
+        #[cfg(feature = "store")]
+        let replay_channel = store
+            .clone()
+            .filter(|_| self.replay_store)
+            .map(|store| BlockChannel(replay_receiver(store)));
+        #[cfg(not(feature = "store"))]
+        let replay_channel: Option<BlockChannel> = None;
+
+        let replaying = self.fixture_path.is_some() || replay_channel.is_some();
+        if replaying && !self.fetchers.is_empty() {
+            return Err(BuildError::FetchersWithReplay);
+        }
+
+        let (channel, status_channel, mempool_channel) = if let Some(ref path) = self.fixture_path {
+            (init_fixture_channel(path), None, None)
+        } else if let Some(channel) = replay_channel {
+            (channel, None, None)
         } else {
-            let configs = if self.configs.is_empty() {
+            let configs = if self.configs.is_empty() && self.fetchers.is_empty() {
                 config::chain_configs()
             } else {
                 self.configs
             };
-            init_multi_chain_channel(configs)
+            let configs = configs
+                .into_iter()
+                .map(|c| {
+                    let receipts = c.receipts || self.fetch_receipts;
+                    let traces = c.traces || self.fetch_traces;
+                    let mempool = c.mempool || self.watch_mempool;
+                    let mut c = c
+                        .with_receipts(receipts)
+                        .with_traces(traces)
+                        .with_mempool(mempool);
+                    c.from = self.range_from.or(c.from);
+                    c.to = self.range_to.or(c.to);
+                    if let Some(concurrency) = self.concurrency {
+                        c = c.with_concurrency(concurrency);
+                    }
+                    if let Some(batch_size) = self.batch_size {
+                        c = c.with_batch_size(batch_size);
+                    }
+                    if let Some(strategy) = self.endpoint_strategy {
+                        c = c.with_strategy(strategy);
+                    }
+                    c
+                })
+                .collect::<Vec<_>>();
+            for c in &configs {
+                if let (Some(BlockStart::Number(from)), Some(to)) = (c.from, c.to) {
+                    if from > to {
+                        return Err(BuildError::EmptyRange {
+                            chain: c.chain,
+                            from,
+                            to,
+                        });
+                    }
+                }
+            }
+            let mempool_channel = init_mempool_channel(&configs);
+            // Custom fetchers don't name their chains up front, so they warm
+            // every stored chain.
+            #[cfg(feature = "store")]
+            let warm_chains = if self.fetchers.is_empty() {
+                configs.iter().map(|c| c.chain).collect()
+            } else {
+                store
+                    .as_ref()
+                    .and_then(|store| store.chains().ok())
+                    .unwrap_or_default()
+            };
+            let mut fetchers = config_fetchers(configs);
+            fetchers.append(&mut self.fetchers);
+            let (channel, status_channel) = init_fetcher_channels(fetchers);
+            #[cfg(feature = "store")]
+            let channel = match store {
+                Some(ref store) => {
+                    let since = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map_or(0, |now| now.as_secs())
+                        .saturating_sub(WARM_MAX_AGE.as_secs());
+                    BlockChannel(warm_receiver(
+                        store,
+                        &warm_chains,
+                        WARM_BLOCKS,
+                        since,
+                        channel.0,
+                    ))
+                }
+                None => channel,
+            };
+            (channel, Some(status_channel), mempool_channel)
         };
 
-        let renderer = self
-            .renderer
-            .unwrap_or_else(|| Box::new(SlabsAndCubesRenderer::default()));
+        // Only blocks fetched from RPC go into the store; fixtures and store
+        // replays would mix other data into, or rewrite, the live history.
+        #[cfg(feature = "store")]
+        let live_source = status_channel.is_some();
+
+        let renderer = self.renderer.unwrap_or_else(|| {
+            let mut renderer = SlabsAndCubesRenderer::default();
+            renderer.settings.clusters.by_selector = self.cluster_by_selector;
+            renderer.settings.clusters.selectors = selectors.clone();
+            renderer.settings.clusters.contracts = contracts.clone();
+            Box::new(renderer)
+        });
 
         let mut app = App::new();
         app.add_plugins(DefaultPlugins.set(WindowPlugin {
@@ -203,15 +653,53 @@ impl BlockExplorerBuilder {
         }))
         .insert_resource(ClearColor(self.clear_color))
         .insert_resource(channel)
+        .insert_resource(selectors)
+        .insert_resource(tokens)
+        .insert_resource(builders)
+        .insert_resource(contracts)
         .add_systems(Startup, setup_scene)
-        .add_systems(Update, (ingest_blocks, cleanup_old_blocks));
+        .add_systems(
+            Update,
+            (
+                clear_scene.before(ingest_blocks),
+                ingest_blocks,
+                cleanup_old_blocks,
+                fade_orphaned_blocks,
+            ),
+        )
+        .add_event::<ClearScene>();
+
+        if let Some(status_channel) = status_channel {
+            app.insert_resource(status_channel);
+        }
+        if let Some(mempool_channel) = mempool_channel {
+            app.insert_resource(mempool_channel)
+                .add_plugins(mempool_plugin);
+        }
 
         renderer.setup(&mut app);
         app.insert_resource(RendererResource(renderer));
 
         if let Some(record_path) = self.record_path {
-            app.insert_resource(RecordBuffer::new(record_path))
-                .add_systems(Last, flush_record_buffer);
+            match Recorder::start(&record_path, self.record_rotation) {
+                Ok(recorder) => {
+                    app.insert_resource(recorder)
+                        .add_systems(Last, finish_recording);
+                }
+                Err(e) => eprintln!(
+                    "tessera: failed to open recording {}: {e}",
+                    record_path.display()
+                ),
+            }
+        }
+
+        #[cfg(feature = "store")]
+        if let Some(store) = store {
+            if live_source {
+                let retention = self.store_retention.unwrap_or_default();
+                app.insert_resource(StoreWriter::spawn(store.clone(), retention));
+            }
+            app.insert_resource(StoreHistory::new(store));
         }
 
         if let Some(screenshot_path) = self.screenshot_path {
@@ -240,7 +728,61 @@ impl BlockExplorerBuilder {
         if self.enable_blob_links {
             app.add_plugins(blob_link_plugin);
         }
+        if self.enable_fee_share {
+            app.add_plugins(fee_share_plugin);
+        }
+        if self.enable_builders {
+            app.add_plugins(builder_plugin);
+        }
+        if self.enable_deployments {
+            app.add_plugins(deployment_plugin);
+        }
+        if self.enable_categories {
+            app.add_plugins(category_plugin);
+        }
+
+        Ok(app)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_rejects_a_range_ending_before_its_start() {
+        let config = FetcherConfig::new(Chain::mainnet(), "http://127.0.0.1:8545".parse().unwrap());
+        let result = BlockExplorerBuilder::new()
+            .config(config)
+            .from_block(20)
+            .to_block(10)
+            .build();
+
+        assert_eq!(
+            result.err(),
+            Some(BuildError::EmptyRange {
+                chain: Chain::mainnet(),
+                from: 20,
+                to: 10
+            })
+        );
+    }
+
+    struct IdleFetcher;
+
+    impl ChainFetcher for IdleFetcher {
+        fn spawn(self: Box<Self>) -> crossbeam_channel::Receiver<crate::data::BlockPayload> {
+            crossbeam_channel::never()
+        }
+    }
+
+    #[test]
+    fn build_rejects_custom_fetchers_with_a_fixture() {
+        let result = BlockExplorerBuilder::new()
+            .add_fetcher(IdleFetcher)
+            .fixture("blocks.json")
+            .build();
 
-        app
+        assert_eq!(result.err(), Some(BuildError::FetchersWithReplay));
     }
 }
@@ -8,9 +8,11 @@ use bevy_egui::{egui, EguiContexts, EguiPlugin};
 
 use alloy_chains::Chain;
 
-use crate::data::BlockPayload;
+use crate::data::{
+    chain_name, chain_units, BlockPayload, ConnectionState, FetcherStatus, StatusChannel,
+};
 
-const GAS_PRICE_WINDOW: usize = 10;
+const PRICE_WINDOW: usize = 10;
 
 /// Live HUD state updated each time a block is ingested.
 #[derive(Resource)]
@@ -22,10 +24,22 @@ pub struct HudState {
     pub latest_tx_count: u32,
     pub latest_timestamp: u64,
     pub blocks_rendered: u64,
-    pub avg_gas_price_gwei: f64,
+    /// Rolling average transaction price, in the chain's displayed price unit.
+    pub avg_price: f64,
     pub base_fee_per_gas: Option<u64>,
     pub blob_gas_used: Option<u64>,
-    gas_price_buffer: VecDeque<f64>,
+    pub last_reorg: Option<ReorgSummary>,
+    /// Latest health snapshot from each live fetcher, in first-seen order.
+    pub fetchers: Vec<FetcherStatus>,
+    price_buffer: VecDeque<f64>,
+}
+
+/// The most recent chain reorganization seen during ingestion.
+#[derive(Clone, Copy, Debug)]
+pub struct ReorgSummary {
+    pub chain: Chain,
+    pub fork_number: u64,
+    pub depth: u64,
 }
 
 impl Default for HudState {
@@ -38,10 +52,12 @@ impl Default for HudState {
             latest_tx_count: 0,
             latest_timestamp: 0,
             blocks_rendered: 0,
-            avg_gas_price_gwei: 0.0,
+            avg_price: 0.0,
             base_fee_per_gas: None,
             blob_gas_used: None,
-            gas_price_buffer: VecDeque::new(),
+            last_reorg: None,
+            fetchers: Vec::new(),
+            price_buffer: VecDeque::new(),
         }
     }
 }
@@ -58,6 +74,14 @@ impl HudState {
         self.blob_gas_used = entry.blob_gas_used;
     }
 
+    /// Replaces the stored snapshot for the status's chain.
+    pub fn update_fetcher_status(&mut self, status: FetcherStatus) {
+        match self.fetchers.iter_mut().find(|s| s.chain == status.chain) {
+            Some(existing) => *existing = status,
+            None => self.fetchers.push(status),
+        }
+    }
+
     pub fn update_from_payload(&mut self, payload: &BlockPayload) {
         self.chain = Some(payload.chain);
         self.latest_block_number = payload.number;
@@ -69,20 +93,27 @@ impl HudState {
         self.blob_gas_used = payload.blob_gas_used;
         self.blocks_rendered += 1;
 
+        if let (Some(reorg), Some(fork_number)) = (&payload.reorg, payload.reorg_fork()) {
+            self.last_reorg = Some(ReorgSummary {
+                chain: payload.chain,
+                fork_number,
+                depth: reorg.depth,
+            });
+        }
+
         if !payload.transactions.is_empty() {
-            let avg_wei: f64 = payload
+            let units = chain_units(&payload.chain);
+            let avg = payload
                 .transactions
                 .iter()
-                .map(|tx| tx.gas_price as f64)
+                .map(|tx| units.display_price(tx.price))
                 .sum::<f64>()
                 / payload.transactions.len() as f64;
-            let avg_gwei = avg_wei / 1e9;
-            self.gas_price_buffer.push_back(avg_gwei);
-            if self.gas_price_buffer.len() > GAS_PRICE_WINDOW {
-                self.gas_price_buffer.pop_front();
+            self.price_buffer.push_back(avg);
+            if self.price_buffer.len() > PRICE_WINDOW {
+                self.price_buffer.pop_front();
             }
-            self.avg_gas_price_gwei =
-                self.gas_price_buffer.iter().sum::<f64>() / self.gas_price_buffer.len() as f64;
+            self.avg_price = self.price_buffer.iter().sum::<f64>() / self.price_buffer.len() as f64;
         }
     }
 }
@@ -91,9 +122,22 @@ pub fn hud_plugin(app: &mut App) {
     app.add_plugins(EguiPlugin)
         .add_plugins(FrameTimeDiagnosticsPlugin)
         .init_resource::<HudState>()
-        .add_systems(Update, hud_overlay_system);
+        .add_systems(Update, (drain_fetcher_status, hud_overlay_system).chain());
 }
 
+/// Folds fetcher health updates into the HUD state.
+fn drain_fetcher_status(channel: Option<Res<StatusChannel>>, mut hud: ResMut<HudState>) {
+    let Some(channel) = channel else {
+        return;
+    };
+    for rx in &channel.0 {
+        while let Ok(status) = rx.try_recv() {
+            hud.update_fetcher_status(status);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn hud_overlay_system(
     mut contexts: EguiContexts,
     hud: Res<HudState>,
@@ -101,6 +145,13 @@ fn hud_overlay_system(
     heatmap_state: Res<crate::scene::HeatmapState>,
     arc_settings: Res<crate::scene::arcs::ArcSettings>,
     blob_link_settings: Option<Res<crate::scene::blob_links::BlobLinkSettings>>,
+    fee_share_state: Option<Res<crate::scene::fee_share::FeeShareState>>,
+    mempool: Option<Res<crate::scene::MempoolStats>>,
+    builder_share: Option<Res<crate::scene::BuilderShare>>,
+    builder_colors: Option<Res<crate::scene::builders::BuilderColorState>>,
+    deployments: Option<Res<crate::scene::Deployments>>,
+    category_colors: Option<Res<crate::scene::contracts::CategoryColorState>>,
+    ghosts: Query<&crate::scene::gaps::GhostSlab>,
 ) {
     let fps = diagnostics
         .get(&FrameTimeDiagnosticsPlugin::FPS)
@@ -128,11 +179,8 @@ fn hud_overlay_system(
             ui.style_mut().override_text_style = Some(egui::TextStyle::Monospace);
             ui.visuals_mut().override_text_color = Some(egui::Color32::from_rgb(200, 220, 240));
 
-            let chain_label = hud
-                .chain
-                .and_then(|c| c.named())
-                .map(|n| n.to_string())
-                .unwrap_or_default();
+            let chain_label = hud.chain.map(|c| chain_name(&c)).unwrap_or_default();
+            let units = chain_units(&hud.chain.unwrap_or_default());
             let header = if chain_label.is_empty() {
                 format!("Block #{}", hud.latest_block_number)
             } else {
@@ -146,7 +194,8 @@ fn hud_overlay_system(
             ui.add_space(4.0);
 
             ui.label(format!(
-                "Gas  {}/{}",
+                "{}  {}/{}",
+                units.resource,
                 format_gas(hud.latest_gas_used),
                 format_gas(hud.latest_gas_limit)
             ));
@@ -158,9 +207,16 @@ fn hud_overlay_system(
             ui.add_space(4.0);
 
             ui.label(format!("Txns {}", hud.latest_tx_count));
-            ui.label(format!("Avg gas price  {:.2} gwei", hud.avg_gas_price_gwei));
+            ui.label(format!(
+                "Avg price  {:.2} {}",
+                hud.avg_price, units.price_unit
+            ));
             if let Some(base_fee) = hud.base_fee_per_gas {
-                ui.label(format!("Base fee  {:.2} gwei", base_fee as f64 / 1e9));
+                ui.label(format!(
+                    "Base fee  {:.2} {}",
+                    units.display_price(base_fee.into()),
+                    units.price_unit
+                ));
             }
             if let Some(blob_gas) = hud.blob_gas_used {
                 if blob_gas > 0 {
@@ -168,8 +224,71 @@ fn hud_overlay_system(
                 }
             }
             ui.label(format!("Time {}", format_timestamp(hud.latest_timestamp)));
+            if let Some(mempool) = mempool.as_ref() {
+                let pending: usize = mempool.pending.values().sum();
+                match mempool.avg_wait() {
+                    Some(wait) => ui.label(format!("Pending {pending}  avg wait {wait:.1}s")),
+                    None => ui.label(format!("Pending {pending}")),
+                };
+            }
             ui.add_space(4.0);
 
+            if let Some(reorg) = hud.last_reorg {
+                let chain_name = chain_name(&reorg.chain);
+                ui.label(
+                    egui::RichText::new(format!(
+                        "Reorg  {chain_name} #{} (depth {})",
+                        reorg.fork_number, reorg.depth
+                    ))
+                    .color(egui::Color32::from_rgb(230, 120, 90)),
+                );
+                ui.add_space(4.0);
+            }
+
+            // Per chain: missing block count and the oldest missing number.
+            let mut missing: Vec<(Chain, usize, u64)> = Vec::new();
+            for ghost in &ghosts {
+                match missing.iter_mut().find(|(chain, ..)| *chain == ghost.chain) {
+                    Some((_, count, oldest)) => {
+                        *count += 1;
+                        *oldest = (*oldest).min(ghost.number);
+                    }
+                    None => missing.push((ghost.chain, 1, ghost.number)),
+                }
+            }
+            missing.sort_by_key(|(chain, ..)| chain.id());
+            for (chain, count, oldest) in &missing {
+                let chain_name = chain_name(chain);
+                ui.label(
+                    egui::RichText::new(format!(
+                        "Missing  {chain_name} {count} block(s), oldest #{oldest}"
+                    ))
+                    .color(egui::Color32::from_rgb(170, 180, 200)),
+                );
+            }
+            if !missing.is_empty() {
+                ui.add_space(4.0);
+            }
+
+            if let Some(share) = builder_share.as_ref() {
+                for (chain, builders) in &share.chains {
+                    show_builder_share(ui, chain, builders);
+                }
+            }
+
+            if let Some(deployments) = deployments.as_ref() {
+                if !deployments.entries.is_empty() {
+                    show_new_contracts(ui, &deployments.entries);
+                }
+            }
+
+            if !hud.fetchers.is_empty() {
+                for status in &hud.fetchers {
+                    show_fetcher_status(ui, status);
+                }
+                ui.add_space(4.0);
+            }
+
             ui.separator();
             ui.label(format!("Blocks rendered  {}", hud.blocks_rendered));
             ui.label(format!("FPS  {fps:.0}"));
@@ -206,9 +325,100 @@ fn hud_overlay_system(
                     .size(11.0)
                     .color(egui::Color32::from_rgb(120, 160, 140)),
             );
+            if let Some(fee_share) = fee_share_state.as_ref() {
+                let fee_share_label = if fee_share.enabled {
+                    "[L] L1 fee share ON"
+                } else {
+                    "[L] L1 fee share OFF"
+                };
+                ui.label(
+                    egui::RichText::new(fee_share_label)
+                        .size(11.0)
+                        .color(egui::Color32::from_rgb(120, 160, 140)),
+                );
+            }
+            if let Some(builder_colors) = builder_colors.as_ref() {
+                let builder_label = if builder_colors.enabled {
+                    "[K] Builder colours ON"
+                } else {
+                    "[K] Builder colours OFF"
+                };
+                ui.label(
+                    egui::RichText::new(builder_label)
+                        .size(11.0)
+                        .color(egui::Color32::from_rgb(120, 160, 140)),
+                );
+            }
+            if let Some(category_colors) = category_colors.as_ref() {
+                let category_label = if category_colors.enabled {
+                    "[C] Contract categories ON"
+                } else {
+                    "[C] Contract categories OFF"
+                };
+                ui.label(
+                    egui::RichText::new(category_label)
+                        .size(11.0)
+                        .color(egui::Color32::from_rgb(120, 160, 140)),
+                );
+            }
         });
 }
 
+/// Share of the chain's blocks in the scene won by each of the top builders.
+fn show_builder_share(ui: &mut egui::Ui, chain: &Chain, builders: &[(String, usize)]) {
+    const MAX_SHOWN: usize = 5;
+    let total: usize = builders.iter().map(|(_, count)| count).sum();
+
+    ui.label(
+        egui::RichText::new(format!("Builders  {} ({total} blocks)", chain_name(chain)))
+            .size(12.0)
+            .color(egui::Color32::from_rgb(140, 160, 180)),
+    );
+    for (builder, count) in builders.iter().take(MAX_SHOWN) {
+        let share = *count as f32 / total as f32 * 100.0;
+        let [r, g, b, _] = crate::scene::builders::builder_color(builder)
+            .to_srgba()
+            .to_u8_array();
+        ui.label(
+            egui::RichText::new(format!("  {builder:<14}{share:>3.0}%"))
+                .size(11.0)
+                .color(egui::Color32::from_rgb(r, g, b)),
+        );
+    }
+    if builders.len() > MAX_SHOWN {
+        let rest: usize = builders[MAX_SHOWN..].iter().map(|(_, count)| count).sum();
+        let share = rest as f32 / total as f32 * 100.0;
+        ui.label(egui::RichText::new(format!("  {:<14}{share:>3.0}%", "Others")).size(11.0));
+    }
+    ui.add_space(4.0);
+}
+
+/// Contracts deployed within the visible window, newest first.
+fn show_new_contracts(ui: &mut egui::Ui, deployments: &[crate::scene::Deployment]) {
+    const MAX_SHOWN: usize = 6;
+
+    ui.label(
+        egui::RichText::new(format!("New contracts  {}", deployments.len()))
+            .size(12.0)
+            .color(egui::Color32::from_rgb(140, 160, 180)),
+    );
+    for deployment in deployments.iter().take(MAX_SHOWN) {
+        let address = deployment.address.to_string();
+        ui.label(
+            egui::RichText::new(format!(
+                "  {}..{}  {} #{}",
+                &address[..6],
+                &address[address.len() - 4..],
+                chain_name(&deployment.chain),
+                deployment.block_number
+            ))
+            .size(11.0)
+            .color(egui::Color32::from_rgb(240, 190, 70)),
+        );
+    }
+    ui.add_space(4.0);
+}
+
 fn format_gas(gas: u64) -> String {
     if gas >= 1_000_000 {
         format!("{:.1}M", gas as f64 / 1_000_000.0)
@@ -219,6 +429,81 @@ fn format_gas(gas: u64) -> String {
     }
 }
 
+/// One HUD row per fetcher: a coloured dot, state, lag, latency and retries,
+/// plus the last error while the fetcher isn't healthy.
+fn show_fetcher_status(ui: &mut egui::Ui, status: &FetcherStatus) {
+    let chain_name = chain_name(&status.chain);
+    let latency = status
+        .latency
+        .map(|l| format!("{}ms", l.as_millis()))
+        .unwrap_or_else(|| "-".to_string());
+
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new("●").color(health_color(status)));
+        ui.label(format!("{chain_name} {}", status.state));
+    });
+    let mut detail = format!(
+        "  {} behind  {latency}  {} retries",
+        status.blocks_behind, status.retries
+    );
+    if status.pending_gaps > 0 {
+        detail.push_str(&format!("  {} gaps", status.pending_gaps));
+    }
+    ui.label(egui::RichText::new(detail).size(11.0));
+    if let Some(endpoint) = &status.endpoint {
+        ui.label(egui::RichText::new(format!("  via {}", truncate(endpoint, 40))).size(11.0));
+    }
+    if status.disagreements > 0 {
+        let last = status.last_disagreement.as_deref().unwrap_or_default();
+        ui.label(
+            egui::RichText::new(format!(
+                "  {} disagreement(s), last {}",
+                status.disagreements,
+                truncate(last, 32)
+            ))
+            .size(11.0)
+            .color(egui::Color32::from_rgb(230, 180, 80)),
+        );
+    }
+
+    if !is_healthy(status) {
+        if let Some(err) = &status.last_error {
+            ui.label(
+                egui::RichText::new(format!("  {}", truncate(err, 48)))
+                    .size(11.0)
+                    .color(egui::Color32::from_rgb(230, 120, 90)),
+            );
+        }
+    }
+}
+
+fn is_healthy(status: &FetcherStatus) -> bool {
+    matches!(
+        status.state,
+        ConnectionState::Live | ConnectionState::Finished
+    ) && status.pending_gaps == 0
+}
+
+fn health_color(status: &FetcherStatus) -> egui::Color32 {
+    match status.state {
+        ConnectionState::Failed => egui::Color32::from_rgb(230, 90, 80),
+        ConnectionState::Retrying => egui::Color32::from_rgb(230, 180, 80),
+        ConnectionState::Connecting | ConnectionState::Syncing => {
+            egui::Color32::from_rgb(120, 170, 230)
+        }
+        ConnectionState::Finished => egui::Color32::from_rgb(150, 160, 170),
+        ConnectionState::Live if status.pending_gaps > 0 => egui::Color32::from_rgb(230, 180, 80),
+        ConnectionState::Live => egui::Color32::from_rgb(100, 220, 180),
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((idx, _)) => format!("{}…", &text[..idx]),
+        None => text.to_string(),
+    }
+}
+
 fn format_timestamp(ts: u64) -> String {
     let secs = ts % 60;
     let mins = (ts / 60) % 60;
@@ -237,6 +522,39 @@ mod tests {
         assert_eq!(format_gas(2_000_000), "2.0M");
     }
 
+    #[test]
+    fn fetcher_status_replaces_snapshot_per_chain() {
+        let mut hud = HudState::default();
+        let mut mainnet = FetcherStatus::new(Chain::mainnet());
+        hud.update_fetcher_status(mainnet.clone());
+        hud.update_fetcher_status(FetcherStatus::new(Chain::base_mainnet()));
+
+        mainnet.state = ConnectionState::Retrying;
+        mainnet.retries = 3;
+        hud.update_fetcher_status(mainnet);
+
+        assert_eq!(hud.fetchers.len(), 2);
+        assert_eq!(hud.fetchers[0].state, ConnectionState::Retrying);
+        assert_eq!(hud.fetchers[0].retries, 3);
+        assert_eq!(hud.fetchers[1].chain, Chain::base_mainnet());
+    }
+
+    #[test]
+    fn pending_gaps_make_a_live_fetcher_unhealthy() {
+        let mut status = FetcherStatus::new(Chain::mainnet());
+        status.state = ConnectionState::Live;
+        assert!(is_healthy(&status));
+        status.pending_gaps = 2;
+        assert!(!is_healthy(&status));
+        assert_eq!(health_color(&status), egui::Color32::from_rgb(230, 180, 80));
+    }
+
+    #[test]
+    fn truncate_shortens_long_errors() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("abcdefghij", 4), "abcd…");
+    }
+
     #[test]
     fn format_timestamp_formats_hh_mm_ss() {
         assert_eq!(format_timestamp(0), "00:00:00 UTC");
@@ -3,11 +3,17 @@
 //! Uses manual ray-AABB intersection instead of Bevy's mesh picking to avoid
 //! input absorption conflicts with bevy_egui.
 
+use alloy::primitives::U256;
+use alloy_chains::Chain;
 use bevy::prelude::*;
 use bevy::render::primitives::Aabb;
 use bevy_egui::{egui, EguiContexts};
 
-use crate::scene::{BlockSlab, TxCube};
+use crate::data::{
+    chain_name, chain_units, format_wei, BuilderRegistry, CallFrame, CallKind, ChainUnits,
+    ContractRegistry, OpStackFees, SelectorDb, TokenRegistry, TransactionEnvelope,
+};
+use crate::scene::{BlockSlab, Deployments, TxCube};
 
 /// Tracks which entity is selected and its original material for highlight restore.
 #[derive(Resource, Default)]
@@ -17,14 +23,19 @@ pub struct SelectedEntity {
 }
 
 pub fn inspector_plugin(app: &mut App) {
-    app.init_resource::<SelectedEntity>().add_systems(
-        Update,
-        (
-            click_raycast_system,
-            inspector_panel_system,
-            dismiss_selection_system,
-        ),
-    );
+    app.init_resource::<SelectedEntity>()
+        .init_resource::<SelectorDb>()
+        .init_resource::<TokenRegistry>()
+        .init_resource::<BuilderRegistry>()
+        .init_resource::<ContractRegistry>()
+        .add_systems(
+            Update,
+            (
+                click_raycast_system,
+                inspector_panel_system,
+                dismiss_selection_system,
+            ),
+        );
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -169,35 +180,45 @@ fn dismiss_selection_system(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn inspector_panel_system(
     mut contexts: EguiContexts,
     selected: Res<SelectedEntity>,
     slabs: Query<&BlockSlab>,
     tx_cubes: Query<&TxCube>,
+    selectors: Res<SelectorDb>,
+    tokens: Res<TokenRegistry>,
+    builders: Res<BuilderRegistry>,
+    contracts: Res<ContractRegistry>,
+    deployments: Option<Res<Deployments>>,
 ) {
     let Some(entity) = selected.entity else {
         return;
     };
 
     if let Ok(slab) = slabs.get(entity) {
-        show_block_panel(&mut contexts, slab);
+        show_block_panel(&mut contexts, slab, &builders);
     } else if let Ok(tx) = tx_cubes.get(entity) {
-        show_tx_panel(&mut contexts, tx);
+        show_tx_panel(
+            &mut contexts,
+            tx,
+            &selectors,
+            &tokens,
+            &contracts,
+            deployments.as_deref(),
+        );
     }
 }
 
-fn show_block_panel(contexts: &mut EguiContexts, slab: &BlockSlab) {
+fn show_block_panel(contexts: &mut EguiContexts, slab: &BlockSlab, builders: &BuilderRegistry) {
     let fullness = if slab.gas_limit > 0 {
         slab.gas_used as f32 / slab.gas_limit as f32
     } else {
         0.0
     };
 
-    let chain_label = slab
-        .chain
-        .named()
-        .map(|n| n.to_string())
-        .unwrap_or_default();
+    let chain_label = chain_name(&slab.chain);
+    let units = chain_units(&slab.chain);
 
     egui::SidePanel::right("inspector")
         .default_width(260.0)
@@ -221,8 +242,16 @@ fn show_block_panel(contexts: &mut EguiContexts, slab: &BlockSlab) {
             );
             ui.add_space(8.0);
 
-            ui.label(format!("Gas Used     {}", format_number(slab.gas_used)));
-            ui.label(format!("Gas Limit    {}", format_number(slab.gas_limit)));
+            if !slab.hash.is_zero() {
+                let hash = format!("{}", slab.hash);
+                ui.label(format!("Hash         {}", abbreviate(&hash, 10, 6)));
+                ui.add_space(4.0);
+            }
+
+            let used = format!("{} Used", units.resource);
+            let limit = format!("{} Limit", units.resource);
+            ui.label(format!("{used:<13}{}", format_number(slab.gas_used)));
+            ui.label(format!("{limit:<13}{}", format_number(slab.gas_limit)));
             ui.label(format!(
                 "Fullness     {fullness:.1}%",
                 fullness = fullness * 100.0
@@ -232,6 +261,29 @@ fn show_block_panel(contexts: &mut EguiContexts, slab: &BlockSlab) {
             ui.label(format!("Transactions {}", slab.tx_count));
             ui.label(format!("Timestamp    {}", slab.timestamp));
 
+            if let Some(builder) = crate::scene::builders::slab_builder(builders, slab) {
+                ui.add_space(4.0);
+                let [r, g, b, _] = crate::scene::builders::builder_color(&builder)
+                    .to_srgba()
+                    .to_u8_array();
+                ui.label(
+                    egui::RichText::new(format!("Builder      {builder}"))
+                        .color(egui::Color32::from_rgb(r, g, b)),
+                );
+                if let Some(fee_recipient) = slab.fee_recipient {
+                    let s = fee_recipient.to_string();
+                    ui.label(format!("Fee recip.   {}", abbreviate(&s, 8, 6)));
+                }
+                if let Some(payment) = slab.proposer_payment {
+                    let s = payment.proposer.to_string();
+                    ui.label(format!(
+                        "Proposer     {} to {}",
+                        units.format_value(payment.value),
+                        abbreviate(&s, 6, 4)
+                    ));
+                }
+            }
+
             if let Some(l1_origin) = slab.l1_origin_number {
                 ui.add_space(4.0);
                 ui.label(
@@ -246,7 +298,17 @@ fn show_block_panel(contexts: &mut EguiContexts, slab: &BlockSlab) {
         });
 }
 
-fn show_tx_panel(contexts: &mut EguiContexts, tx: &TxCube) {
+fn show_tx_panel(
+    contexts: &mut EguiContexts,
+    cube: &TxCube,
+    selectors: &SelectorDb,
+    tokens: &TokenRegistry,
+    contracts: &ContractRegistry,
+    deployments: Option<&Deployments>,
+) {
+    let tx = &cube.tx;
+    let units = chain_units(&cube.chain);
+
     egui::SidePanel::right("inspector")
         .default_width(280.0)
         .frame(inspector_frame())
@@ -254,56 +316,177 @@ fn show_tx_panel(contexts: &mut EguiContexts, tx: &TxCube) {
             apply_inspector_style(ui);
 
             ui.label(
-                egui::RichText::new(format!("Tx #{}", tx.tx_index))
+                egui::RichText::new(format!("Tx #{}", tx.index))
                     .size(18.0)
                     .color(egui::Color32::from_rgb(100, 220, 180)),
             );
             ui.add_space(4.0);
 
             ui.label(
-                egui::RichText::new(format!("Block #{}", tx.block_number))
+                egui::RichText::new(format!("Block #{}", cube.block_number))
                     .size(12.0)
                     .color(egui::Color32::from_rgb(140, 160, 180)),
             );
             ui.add_space(8.0);
 
-            ui.label(format!("Hash  {}", abbreviate(&tx.hash, 10, 6)));
+            ui.label(format!("Hash  {}", abbreviate(&tx.id_display(), 10, 6)));
+            if let Some(tx_type) = tx.tx_type() {
+                let byte = tx.evm.as_ref().and_then(|evm| evm.tx_type).unwrap_or(0);
+                ui.label(format!("Type  {} (0x{byte:02x})", tx_type.name()));
+            }
             ui.add_space(4.0);
 
-            let from_display = format!("{}", tx.from);
-            ui.label(format!("From  {}", abbreviate(&from_display, 8, 6)));
-            if let Some(to_addr) = tx.to {
-                let display =
-                    if let Some(name) = crate::scene::contracts::known_contract_name(&to_addr) {
-                        name.to_string()
-                    } else {
-                        let s = format!("{to_addr}");
-                        abbreviate(&s, 8, 6)
-                    };
-                ui.label(format!("To    {display}"));
-            } else {
-                ui.label(
-                    egui::RichText::new("To    Contract Creation")
-                        .color(egui::Color32::from_rgb(200, 180, 100)),
-                );
+            let from = contracts
+                .name(cube.chain, &tx.sender)
+                .map_or_else(|| abbreviate(&tx.sender_display(), 8, 6), str::to_string);
+            ui.label(format!("From  {from}"));
+            match (tx.recipient, tx.recipient_display()) {
+                (Some(to_addr), Some(to_display)) => {
+                    match contracts.get(cube.chain, &to_addr) {
+                        Some(label) => {
+                            let [r, g, b, _] =
+                                crate::scene::contracts::category_color(label.category)
+                                    .to_srgba()
+                                    .to_u8_array();
+                            ui.horizontal(|ui| {
+                                ui.label(format!("To    {}", label.name));
+                                ui.label(
+                                    egui::RichText::new(label.category.name())
+                                        .size(11.0)
+                                        .color(egui::Color32::from_rgb(r, g, b)),
+                                );
+                            });
+                        }
+                        None => {
+                            ui.label(format!("To    {}", abbreviate(&to_display, 8, 6)));
+                        }
+                    }
+                    if let Some(deployment) = deployments.and_then(|d| d.find(cube.chain, to_addr))
+                    {
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "      deployed in #{} tx {}",
+                                deployment.block_number, deployment.tx_index
+                            ))
+                            .color(egui::Color32::from_rgb(240, 190, 70)),
+                        );
+                    }
+                }
+                _ => {
+                    ui.label(
+                        egui::RichText::new("To    Contract Creation")
+                            .color(egui::Color32::from_rgb(200, 180, 100)),
+                    );
+                }
+            }
+            if let Some(evm) = &tx.evm {
+                if let Some(selector) = evm.selector {
+                    show_method(ui, selectors, &selector, &evm.args);
+                }
             }
             ui.add_space(8.0);
 
-            ui.label(format!("Value   {:.6} ETH", tx.value_eth));
+            ui.label(format!("Value   {}", units.format_value(tx.value)));
+            if let Some(success) = tx.success {
+                let (status, color) = if success {
+                    ("Success", egui::Color32::from_rgb(100, 220, 140))
+                } else {
+                    ("Reverted", egui::Color32::from_rgb(230, 90, 80))
+                };
+                ui.label(egui::RichText::new(format!("Status  {status}")).color(color));
+            }
+            let resource = match tx.resource_used {
+                Some(used) => format!(
+                    "{} / {}",
+                    format_number(used),
+                    format_number(tx.resource_limit)
+                ),
+                None => format_number(tx.resource_limit),
+            };
+            ui.label(format!("{:<8}{resource}", units.resource));
             ui.label(format!(
-                "Gas     {} ({:.2} gwei)",
-                format_number(tx.gas),
-                tx.gas_price as f64 / 1e9,
+                "Price   {:.2} {}",
+                units.display_price(tx.price),
+                units.price_unit
             ));
+            if let Some(fee) = tx.fee_paid {
+                ui.label(format!("Fee     {}", format_fee(&units, fee)));
+            }
             ui.add_space(4.0);
 
-            if tx.blob_count > 0 {
+            if let Some(evm) = &tx.evm {
+                if let Some(price) = evm.effective_gas_price {
+                    ui.label(format!(
+                        "Paid    {:.2} {}",
+                        units.display_price(price),
+                        units.price_unit
+                    ));
+                }
+                if let Some(max_fee) = evm.max_fee_per_gas {
+                    ui.label(format!(
+                        "Max fee {:.2} {}",
+                        units.display_price(max_fee),
+                        units.price_unit
+                    ));
+                }
+                if let Some(tip) = evm.max_priority_fee_per_gas {
+                    ui.label(format!(
+                        "Tip cap {:.2} {}",
+                        units.display_price(tip),
+                        units.price_unit
+                    ));
+                }
+                if evm.access_list_addresses > 0 {
+                    ui.label(format!(
+                        "Access  {} addresses, {} keys",
+                        evm.access_list_addresses, evm.access_list_keys
+                    ));
+                }
+                if evm.authorization_count > 0 {
+                    ui.label(
+                        egui::RichText::new(format!("Delegations {}", evm.authorization_count))
+                            .color(egui::Color32::from_rgb(230, 200, 120)),
+                    );
+                }
+                if let Some(logs) = evm.logs_count {
+                    ui.label(format!("Logs    {logs}"));
+                }
+                if let Some(created) = tx.deployed_contract() {
+                    let s = format!("{created}");
+                    ui.label(
+                        egui::RichText::new(format!("Created {}", abbreviate(&s, 8, 6)))
+                            .color(egui::Color32::from_rgb(200, 180, 100)),
+                    );
+                }
+            }
+
+            if !tx.token_transfers().is_empty() {
+                show_token_transfers(ui, tokens, cube);
+            }
+
+            if let Some(trace) = tx.trace() {
+                show_call_tree(ui, &units, selectors, contracts, cube.chain, trace);
+            }
+
+            if let Some(fees) = &tx.op_stack {
+                show_op_fee_breakdown(ui, fees, tx);
+            }
+
+            if let Some(solana) = &tx.solana {
+                ui.label(format!(
+                    "Priority fee  {} lamports",
+                    format_number(solana.priority_fee)
+                ));
+                ui.label(format!("Logs    {}", solana.log_messages));
+            }
+
+            if let Some(evm) = tx.evm.as_ref().filter(|evm| evm.blob_count > 0) {
                 ui.add_space(4.0);
                 ui.label(
-                    egui::RichText::new(format!("Blobs  {}", tx.blob_count))
+                    egui::RichText::new(format!("Blobs  {}", evm.blob_count))
                         .color(egui::Color32::from_rgb(160, 100, 220)),
                 );
-                if let Some(fee) = tx.max_fee_per_blob_gas {
+                if let Some(fee) = evm.max_fee_per_blob_gas {
                     ui.label(format!("Blob fee  {:.2} gwei", fee as f64 / 1e9));
                 }
             }
@@ -313,6 +496,163 @@ fn show_tx_panel(contexts: &mut EguiContexts, tx: &TxCube) {
         });
 }
 
+/// Method name and decoded arguments, or the raw selector when unknown.
+fn show_method(
+    ui: &mut egui::Ui,
+    selectors: &SelectorDb,
+    selector: &alloy::primitives::Selector,
+    args: &[u8],
+) {
+    let Some(call) = selectors.decode(selector, args) else {
+        ui.label(format!("Method {selector}"));
+        return;
+    };
+    ui.label(
+        egui::RichText::new(format!("Method {}", call.name))
+            .color(egui::Color32::from_rgb(120, 200, 230)),
+    );
+    for (name, value) in &call.args {
+        // Decoded strings and arrays may hold multi-byte characters.
+        let chars: Vec<char> = value.chars().collect();
+        let value = if chars.len() > 24 {
+            let head: String = chars[..12].iter().collect();
+            let tail: String = chars[chars.len() - 8..].iter().collect();
+            format!("{head}..{tail}")
+        } else {
+            value.clone()
+        };
+        ui.label(egui::RichText::new(format!("  {name}  {value}")).size(11.0));
+    }
+}
+
+/// Token movements from the receipt, first few in log order.
+fn show_token_transfers(ui: &mut egui::Ui, tokens: &TokenRegistry, cube: &TxCube) {
+    const MAX_SHOWN: usize = 8;
+    let transfers = cube.tx.token_transfers();
+
+    ui.add_space(4.0);
+    ui.label(
+        egui::RichText::new(format!("Token transfers  {}", transfers.len()))
+            .size(12.0)
+            .color(egui::Color32::from_rgb(140, 160, 180)),
+    );
+    for transfer in transfers.iter().take(MAX_SHOWN) {
+        let from = abbreviate(&transfer.from.to_string(), 6, 4);
+        let to = abbreviate(&transfer.to.to_string(), 6, 4);
+        ui.label(format!(
+            "  {}",
+            tokens.format_transfer(cube.chain, transfer)
+        ));
+        ui.label(egui::RichText::new(format!("    {from} → {to}")).size(11.0));
+    }
+    if transfers.len() > MAX_SHOWN {
+        ui.label(
+            egui::RichText::new(format!("  +{} more", transfers.len() - MAX_SHOWN)).size(11.0),
+        );
+    }
+}
+
+/// Internal calls from the tracer, depth-first with one indent per level.
+fn show_call_tree(
+    ui: &mut egui::Ui,
+    units: &ChainUnits,
+    selectors: &SelectorDb,
+    contracts: &ContractRegistry,
+    chain: Chain,
+    root: &CallFrame,
+) {
+    const MAX_SHOWN: usize = 24;
+    const MAX_DEPTH: usize = 6;
+
+    ui.add_space(4.0);
+    ui.label(
+        egui::RichText::new(format!("Calls  {}", root.frame_count() - 1))
+            .size(12.0)
+            .color(egui::Color32::from_rgb(140, 160, 180)),
+    );
+    let mut shown = 0;
+    let mut hidden = 0;
+    root.walk(&mut |frame, depth| {
+        if depth == 0 {
+            return;
+        }
+        if depth > MAX_DEPTH || shown == MAX_SHOWN {
+            hidden += 1;
+            return;
+        }
+        shown += 1;
+
+        let kind = match frame.kind {
+            CallKind::Call => "call",
+            CallKind::StaticCall => "static",
+            CallKind::DelegateCall => "delegate",
+            CallKind::CallCode => "callcode",
+            CallKind::Create | CallKind::Create2 => "create",
+            CallKind::SelfDestruct => "selfdestruct",
+            CallKind::Other => "other",
+        };
+        let target = frame.to.map_or_else(
+            || "?".to_string(),
+            |to| {
+                contracts
+                    .name(chain, &to)
+                    .map_or_else(|| abbreviate(&to.to_string(), 6, 4), str::to_string)
+            },
+        );
+        let method = frame
+            .selector
+            .map(|selector| {
+                selectors
+                    .name(&selector)
+                    .map_or_else(|| format!(".{selector}"), |name| format!(".{name}"))
+            })
+            .unwrap_or_default();
+        let mut line = format!("{}{kind} {target}{method}", "  ".repeat(depth));
+        if !frame.value.is_zero() {
+            line.push_str(&format!("  {}", units.format_value(frame.value)));
+        }
+        let text = egui::RichText::new(line).size(11.0);
+        ui.label(if frame.error.is_some() {
+            text.color(egui::Color32::from_rgb(230, 90, 80))
+        } else {
+            text
+        });
+    });
+    if hidden > 0 {
+        ui.label(egui::RichText::new(format!("  +{hidden} more")).size(11.0));
+    }
+}
+
+/// L1 data fee vs L2 execution fee for an OP Stack transaction.
+fn show_op_fee_breakdown(ui: &mut egui::Ui, fees: &OpStackFees, tx: &TransactionEnvelope) {
+    ui.add_space(4.0);
+    ui.label(
+        egui::RichText::new("OP Stack fees")
+            .size(12.0)
+            .color(egui::Color32::from_rgb(140, 160, 180)),
+    );
+    ui.label(format!(
+        "L1 data fee  {}",
+        format_wei(U256::from(fees.l1_fee))
+    ));
+    if let Some(l2_fee) = tx.execution_fee() {
+        let share = fees.l1_share(l2_fee);
+        ui.label(format!("L2 exec fee  {}", format_wei(U256::from(l2_fee))));
+        ui.add(
+            egui::ProgressBar::new(share)
+                .text(format!("{:.0}% L1 data", share * 100.0))
+                .fill(egui::Color32::from_rgb(220, 130, 60)),
+        );
+    }
+    if let Some(price) = fees.l1_gas_price {
+        ui.label(format!("L1 base fee  {:.2} gwei", price as f64 / 1e9));
+    }
+    if let Some(blob_fee) = fees.l1_blob_base_fee {
+        ui.label(format!("L1 blob fee  {blob_fee} wei"));
+    }
+    ui.add_space(4.0);
+}
+
 fn abbreviate(s: &str, prefix_len: usize, suffix_len: usize) -> String {
     if s.len() <= prefix_len + suffix_len + 2 {
         return s.to_string();
@@ -339,6 +679,11 @@ fn dismiss_hint(ui: &mut egui::Ui) {
     );
 }
 
+/// Formats a fee in the chain's smallest native unit as whole native units.
+fn format_fee(units: &ChainUnits, fee: u128) -> String {
+    format!("{:.9} {}", units.display_fee(fee), units.native_symbol)
+}
+
 fn format_number(n: u64) -> String {
     if n >= 1_000_000 {
         format!("{:.2}M", n as f64 / 1_000_000.0)
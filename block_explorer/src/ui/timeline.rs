@@ -1,10 +1,15 @@
-//! Timeline scrubber: bottom panel with block rectangles, playback controls.
+//! Timeline scrubber: bottom panel with block rectangles, playback controls,
+//! and (with the `store` feature) the stored history and its replay.
 
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
 
 use crate::camera::CameraTarget;
+#[cfg(feature = "store")]
+use crate::data::{chain_name, replay_receiver, BlockChannel, StoreHistory, StoreWriter};
 use crate::scene::BlockRegistry;
+#[cfg(feature = "store")]
+use crate::scene::ClearScene;
 use crate::ui::HudState;
 
 /// Playback state for the timeline scrubber.
@@ -38,8 +43,14 @@ fn timeline_ui_system(
     mut state: ResMut<TimelineState>,
     mut camera_target: ResMut<CameraTarget>,
     mut hud_state: ResMut<HudState>,
+    #[cfg(feature = "store")] mut history: Option<ResMut<StoreHistory>>,
+    #[cfg(feature = "store")] mut commands: Commands,
 ) {
-    if registry.entries.is_empty() {
+    #[cfg(feature = "store")]
+    let has_history = history.is_some();
+    #[cfg(not(feature = "store"))]
+    let has_history = false;
+    if registry.entries.is_empty() && !has_history {
         return;
     }
 
@@ -84,6 +95,20 @@ fn timeline_ui_system(
 
                 ui.separator();
 
+                #[cfg(feature = "store")]
+                if let Some(ref mut history) = history {
+                    if stored_history_ui(ui, history) {
+                        commands
+                            .insert_resource(BlockChannel(replay_receiver(history.store.clone())));
+                        commands.send_event(ClearScene);
+                        // Replayed blocks are already stored.
+                        commands.remove_resource::<StoreWriter>();
+                        state.current_index = 0;
+                        state.playing = false;
+                    }
+                    ui.separator();
+                }
+
                 // Scrollable row of block rectangles
                 egui::ScrollArea::horizontal()
                     .id_salt("timeline_blocks")
@@ -131,6 +156,31 @@ fn timeline_ui_system(
         });
 }
 
+/// Stored block ranges per chain and a button replaying them in place of the
+/// current source. Returns true when the button is clicked.
+#[cfg(feature = "store")]
+fn stored_history_ui(ui: &mut egui::Ui, history: &mut StoreHistory) -> bool {
+    history.refresh(std::time::Duration::from_secs(2));
+    ui.label("Stored");
+    for (chain, span) in &history.spans {
+        ui.label(
+            egui::RichText::new(format!(
+                "{} #{}–#{}",
+                chain_name(chain),
+                span.first,
+                span.last
+            ))
+            .color(egui::Color32::from_rgb(150, 170, 200)),
+        )
+        .on_hover_text(format!("{} blocks stored", span.count));
+    }
+    !history.spans.is_empty()
+        && ui
+            .button("Replay")
+            .on_hover_text("Replay every stored block, oldest first")
+            .clicked()
+}
+
 fn playback_system(
     time: Res<Time>,
     registry: Res<BlockRegistry>,
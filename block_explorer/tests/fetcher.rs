@@ -21,11 +21,8 @@ async fn fetcher_receives_backfilled_blocks() {
     let node = AnvilNode::latest().start().await.unwrap();
     let rpc_url = anvil_rpc_url(&node).await;
 
-    let config = FetcherConfig {
-        chain: Chain::mainnet(),
-        rpc_url,
-    };
-    let rx = EvmFetcher::spawn(config);
+    let config = FetcherConfig::new(Chain::mainnet(), rpc_url);
+    let rx = Box::new(EvmFetcher::new(config)).spawn();
 
     let payload = rx
         .recv_timeout(RECV_TIMEOUT)
@@ -64,11 +61,8 @@ async fn fetcher_includes_transactions() {
             .expect("transaction should be mined");
     }
 
-    let config = FetcherConfig {
-        chain: Chain::mainnet(),
-        rpc_url,
-    };
-    let rx = EvmFetcher::spawn(config);
+    let config = FetcherConfig::new(Chain::mainnet(), rpc_url);
+    let rx = Box::new(EvmFetcher::new(config)).spawn();
 
     // Drain until we find a block with transactions.
     let mut found = None;
@@ -87,10 +81,10 @@ async fn fetcher_includes_transactions() {
     assert!(block.tx_count >= 1);
 
     let tx = &block.transactions[0];
-    assert!(tx.gas > 0, "transaction gas should be positive");
-    assert_eq!(tx.from, from);
+    assert!(tx.resource_limit > 0, "transaction gas should be positive");
+    assert_eq!(tx.sender, from);
     assert!(
-        tx.value_eth > 0.0,
+        !tx.value.is_zero(),
         "value transfer should have positive ETH value"
     );
 }
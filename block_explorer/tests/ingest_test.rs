@@ -2,7 +2,6 @@
 #[test]
 fn integration_tests_disabled() {
     // Enable with: cargo test --features integration
-    assert!(true);
 }
 
 #[cfg(feature = "integration")]
@@ -22,12 +21,9 @@ mod integration {
         let port = node.get_host_port_ipv4(ANVIL_PORT).await.unwrap();
         let rpc_url = Url::parse(&format!("http://localhost:{port}")).unwrap();
 
-        let config = FetcherConfig {
-            chain: Chain::mainnet(),
-            rpc_url,
-        };
+        let config = FetcherConfig::new(Chain::mainnet(), rpc_url);
 
-        let rx = EvmFetcher::spawn(config);
+        let rx = Box::new(EvmFetcher::new(config)).spawn();
         let payload = rx
             .recv_timeout(Duration::from_secs(10))
             .expect("expected a block payload from anvil");
@@ -1,12 +1,41 @@
 //! Tessera — block space explorer. Runs the block_explorer app.
 
+use std::path::Path;
+use std::time::Duration;
+
+use block_explorer::data::{chain_name, convert_fixture, read_fixture_header, Retention, Rotation};
 use block_explorer::prelude::*;
 
 fn main() {
     let _ = dotenvy::dotenv();
 
+    let mut args = std::env::args().skip(1).peekable();
+    match args.peek().map(String::as_str) {
+        Some("convert") => {
+            args.next();
+            let (Some(input), Some(output)) = (args.next(), args.next()) else {
+                eprintln!("tessera: usage: tessera convert <input> <output>");
+                std::process::exit(1);
+            };
+            convert(&input, &output);
+            return;
+        }
+        Some("fixture-info") => {
+            args.next();
+            let Some(path) = args.next() else {
+                eprintln!("tessera: usage: tessera fixture-info <path>");
+                std::process::exit(1);
+            };
+            fixture_info(&path);
+            return;
+        }
+        _ => {}
+    }
+
     let mut builder = BlockExplorerBuilder::new().chain_configs();
-    let mut args = std::env::args().skip(1);
+    let mut retention: Option<Retention> = None;
+    let mut rotation = Rotation::default();
+    let (mut from, mut to) = (None, None);
     while let Some(arg) = args.next() {
         match arg.as_str() {
             "--fixture" => {
@@ -21,6 +50,85 @@ fn main() {
                 let path = args.next().expect("--record requires a path argument");
                 builder = builder.record(path);
             }
+            "--record-max-mb" => {
+                let raw = args.next().expect("--record-max-mb requires a size in MB");
+                rotation.max_bytes = Some(parse_count("--record-max-mb", &raw) * 1024 * 1024);
+            }
+            "--record-max-minutes" => {
+                let raw = args
+                    .next()
+                    .expect("--record-max-minutes requires a number of minutes");
+                rotation.max_age = Some(Duration::from_secs(
+                    parse_count("--record-max-minutes", &raw) * 60,
+                ));
+            }
+            "--store" => {
+                let path = args.next().expect("--store requires a path argument");
+                builder = builder.store(path);
+            }
+            "--store-keep" => {
+                let raw = args.next().expect("--store-keep requires a block count");
+                retention.get_or_insert_with(Retention::default).max_blocks =
+                    Some(parse_count("--store-keep", &raw));
+            }
+            "--store-max-age" => {
+                let raw = args
+                    .next()
+                    .expect("--store-max-age requires a number of seconds");
+                retention.get_or_insert_with(Retention::default).max_age =
+                    Some(Duration::from_secs(parse_count("--store-max-age", &raw)));
+            }
+            "--replay-store" => {
+                builder = builder.replay_store();
+            }
+            "--receipts" => {
+                builder = builder.receipts();
+            }
+            "--traces" => {
+                builder = builder.traces();
+            }
+            "--mempool" => {
+                builder = builder.mempool();
+            }
+            "--selectors" => {
+                let path = args.next().expect("--selectors requires a path argument");
+                builder = builder.selectors(path);
+            }
+            "--tokens" => {
+                let path = args.next().expect("--tokens requires a path argument");
+                builder = builder.tokens(path);
+            }
+            "--contracts" => {
+                let path = args.next().expect("--contracts requires a path argument");
+                builder = builder.contracts(path);
+            }
+            "--builders" => {
+                let path = args.next().expect("--builders requires a path argument");
+                builder = builder.builders(path);
+            }
+            "--cluster-by-selector" => {
+                builder = builder.cluster_by_selector();
+            }
+            "--from" => {
+                let number = args.next().expect("--from requires a block number");
+                let number = parse_block_number("--from", &number);
+                from = Some(number);
+                builder = builder.from_block(number);
+            }
+            "--to" => {
+                let number = args.next().expect("--to requires a block number");
+                let number = parse_block_number("--to", &number);
+                to = Some(number);
+                builder = builder.to_block(number);
+            }
+            "--at" => {
+                let raw = args.next().expect("--at requires a timestamp");
+                let Some(timestamp) = block_explorer::config::parse_timestamp(&raw) else {
+                    eprintln!("tessera: invalid --at timestamp: {raw}");
+                    std::process::exit(1);
+                };
+                builder = builder.at_timestamp(timestamp);
+            }
             other => {
                 eprintln!("tessera: unknown argument: {other}");
                 std::process::exit(1);
@@ -28,5 +136,66 @@ fn main() {
         }
     }
 
-    builder.build().run();
+    if let (Some(from), Some(to)) = (from, to) {
+        if from > to {
+            eprintln!("tessera: --to {to} is before --from {from}");
+            std::process::exit(1);
+        }
+    }
+
+    if rotation != Rotation::default() {
+        builder = builder.record_rotation(rotation);
+    }
+    if let Some(retention) = retention {
+        builder = builder.store_retention(retention);
+    }
+
+    let mut app = builder.build().unwrap_or_else(|e| {
+        eprintln!("tessera: {e}");
+        std::process::exit(1);
+    });
+    app.run();
+}
+
+fn parse_block_number(flag: &str, raw: &str) -> u64 {
+    raw.parse().unwrap_or_else(|_| {
+        eprintln!("tessera: invalid {flag} block number: {raw}");
+        std::process::exit(1);
+    })
+}
+
+/// Rewrites a fixture in the format picked by the output's extension
+/// (`.tfix` binary, `.jsonl` JSON Lines, otherwise a JSON array).
+fn convert(input: &str, output: &str) {
+    match convert_fixture(Path::new(input), Path::new(output)) {
+        Ok(blocks) => eprintln!("tessera: wrote {blocks} blocks to {output}"),
+        Err(e) => {
+            eprintln!("tessera: failed to convert {input}: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn fixture_info(path: &str) {
+    let header = read_fixture_header(Path::new(path)).unwrap_or_else(|e| {
+        eprintln!("tessera: failed to read {path}: {e}");
+        std::process::exit(1);
+    });
+    println!("version {}, {} blocks", header.version, header.blocks);
+    for range in &header.chains {
+        println!(
+            "  {}: #{}–#{} ({} blocks)",
+            chain_name(&range.chain),
+            range.first,
+            range.last,
+            range.count
+        );
+    }
+}
+
+fn parse_count(flag: &str, raw: &str) -> u64 {
+    raw.parse().unwrap_or_else(|_| {
+        eprintln!("tessera: invalid {flag} value: {raw}");
+        std::process::exit(1);
+    })
 }